@@ -0,0 +1,883 @@
+use std::collections::HashSet;
+
+use ndarray::Array;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph, StatefulWidget, Widget,
+    },
+};
+
+use super::{palette::Palette, theme::Theme};
+use crate::loader::{decimate_max, nearest_index, AutoSpectra};
+
+/// Dashes the max hold is broken into across the x-axis
+const MAX_HOLD_DASHES: usize = 60;
+/// Most labels drawn along either axis
+const MAX_TICKS: usize = 21;
+/// Blank cells kept between neighbouring x-axis labels
+const XLABEL_GAP: usize = 3;
+/// Rows given to each y-axis label
+const YLABEL_ROWS: usize = 2;
+
+/// Symbol the traces are drawn with, Braille is the finest but renders
+/// poorly on some terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum TraceMarker {
+    #[default]
+    Braille,
+    Dot,
+    Block,
+}
+impl TraceMarker {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Braille => Self::Dot,
+            Self::Dot => Self::Block,
+            Self::Block => Self::Braille,
+        }
+    }
+
+    fn symbol(self) -> symbols::Marker {
+        match self {
+            Self::Braille => symbols::Marker::Braille,
+            Self::Dot => symbols::Marker::Dot,
+            Self::Block => symbols::Marker::Block,
+        }
+    }
+}
+
+/// Decimal places that tell apart labels `step` apart, one more than the
+/// step's leading digit unless the step is round, e.g. 10 needs none and 9.83 one.
+fn label_precision(step: f64) -> usize {
+    if !(step.is_finite() && step > 0.0) {
+        return 3;
+    }
+    let decimals = (-step.log10()).ceil().max(0.0);
+    let units = step * 10_f64.powf(decimals);
+    let extra = (units - units.round()).abs() > 1e-6 * units;
+    (decimals as usize + extra as usize).min(6)
+}
+
+/// Evenly spaced label values from `min` to `max`, as many as fit along
+/// `length` cells when each needs `spacing` of them.
+fn tick_values(min: f64, max: f64, length: u16, spacing: usize) -> Vec<f64> {
+    let count = (length as usize / spacing.max(1)).clamp(2, MAX_TICKS);
+    Array::linspace(min, max, count).to_vec()
+}
+
+/// Step between consecutive label values.
+fn tick_step(ticks: &[f64]) -> f64 {
+    match ticks {
+        [first, second, ..] => second - first,
+        _ => f64::NAN,
+    }
+}
+
+/// Approximates where the chart will draw the data inside `inner`, to the
+/// right of the y-axis labels and above the x-axis labels.
+fn graph_area(inner: Rect, ylabel_width: u16, xlabel_width: u16) -> Rect {
+    // the first x label hangs left of the axis
+    let left = ylabel_width
+        .max(xlabel_width.saturating_sub(1))
+        .min(inner.width / 3)
+        + 1;
+
+    Rect::new(
+        inner.x + left,
+        inner.y,
+        inner.width.saturating_sub(left),
+        inner.height.saturating_sub(2),
+    )
+}
+
+/// Every other run of samples of a trace sorted by frequency, cutting
+/// `min` to `max` into `n_dashes` dashes with gaps of the same width between
+/// them, to draw the trace as a dashed line.
+fn dashes(trace: &[(f64, f64)], (min, max): (f64, f64), n_dashes: usize) -> Vec<&[(f64, f64)]> {
+    let width = (max - min) / (2 * n_dashes) as f64;
+    if !(width.is_finite() && width > 0.0) {
+        return vec![trace];
+    }
+    let slot = |(freq, _): &(f64, f64)| ((freq - min) / width).floor() as i64;
+
+    trace
+        .chunk_by(|a, b| slot(a) == slot(b))
+        .filter(|run| slot(&run[0]).rem_euclid(2) == 0)
+        .collect()
+}
+
+/// Where the last chart was drawn and the bounds of its axes, used to map
+/// terminal cells back to data coordinates.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ChartView {
+    /// Cells the data is plotted in
+    pub graph: Rect,
+    /// (min, max) of the x-axis
+    pub x: (f64, f64),
+    /// (min, max) of the y-axis in plotted units
+    pub y: (f64, f64),
+}
+impl ChartView {
+    pub fn contains(&self, column: u16, row: u16) -> bool {
+        self.graph
+            .contains(ratatui::layout::Position::new(column, row))
+    }
+
+    /// Data coordinates of a cell, cells outside the graph are moved to its edge.
+    pub fn to_data(&self, column: u16, row: u16) -> Option<(f64, f64)> {
+        if self.graph.width < 2 || self.graph.height < 2 {
+            return None;
+        }
+        let column = column.clamp(self.graph.left(), self.graph.right() - 1);
+        let row = row.clamp(self.graph.top(), self.graph.bottom() - 1);
+
+        let xfrac = (column - self.graph.x) as f64 / (self.graph.width - 1) as f64;
+        // rows count down from the top of the axis
+        let yfrac = (row - self.graph.y) as f64 / (self.graph.height - 1) as f64;
+
+        Some((
+            self.x.0 + xfrac * (self.x.1 - self.x.0),
+            self.y.1 - yfrac * (self.y.1 - self.y.0),
+        ))
+    }
+
+    /// Size of one cell in data units.
+    pub fn cell_size(&self) -> (f64, f64) {
+        (
+            (self.x.1 - self.x.0) / self.graph.width.saturating_sub(1).max(1) as f64,
+            (self.y.1 - self.y.0) / self.graph.height.saturating_sub(1).max(1) as f64,
+        )
+    }
+}
+
+/// Line plot of the current spectra with an optional crosshair readout.
+pub(crate) struct SpectrumChart<'a> {
+    data: Option<&'a AutoSpectra>,
+    /// Plots the dB spectra instead of the raw ones
+    plot_log: bool,
+    xlims: (Option<f64>, Option<f64>),
+    ylims: (Option<f64>, Option<f64>),
+    crosshair: Option<f64>,
+    hidden: Option<&'a HashSet<String>>,
+    no_data: Option<&'a HashSet<String>>,
+    focus: Option<&'a str>,
+    saturation_threshold: Option<f64>,
+    median: Option<&'a AutoSpectra>,
+    show_traces: bool,
+    max_hold: Option<&'a AutoSpectra>,
+    spread: Option<&'a (AutoSpectra, AutoSpectra)>,
+    outliers: Option<&'a AutoSpectra>,
+    overlays: &'a [(AutoSpectra, Color)],
+    bands: &'a [(f64, f64)],
+    markers: &'a [(f64, String)],
+    db_reference: Option<f64>,
+    noise_floor: bool,
+    raw_labels: bool,
+    palette: Palette,
+    theme: Theme,
+    marker: TraceMarker,
+    scatter: bool,
+    fit_width: bool,
+    legend: bool,
+    block: Option<Block<'a>>,
+}
+impl<'a> SpectrumChart<'a> {
+    pub fn new(data: Option<&'a AutoSpectra>) -> Self {
+        Self {
+            data,
+            plot_log: data.is_some_and(|specs| specs.plot_log),
+            xlims: (None, None),
+            ylims: (None, None),
+            crosshair: None,
+            hidden: None,
+            no_data: None,
+            focus: None,
+            saturation_threshold: None,
+            median: None,
+            show_traces: true,
+            max_hold: None,
+            spread: None,
+            outliers: None,
+            overlays: &[],
+            bands: &[],
+            markers: &[],
+            db_reference: None,
+            noise_floor: false,
+            raw_labels: false,
+            palette: Palette::default(),
+            theme: Theme::default(),
+            marker: TraceMarker::default(),
+            scatter: false,
+            fit_width: false,
+            legend: true,
+            block: None,
+        }
+    }
+
+    /// Restrict the frequency range displayed, `None` uses the data bounds.
+    pub fn xlims(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.xlims = (min, max);
+        self
+    }
+
+    /// Fix the range of the y-axis in plotted units, `None` uses the data bounds.
+    pub fn ylims(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.ylims = (min, max);
+        self
+    }
+
+    /// Draw a vertical marker at this frequency and list the nearest sample
+    /// of every antenna.
+    pub fn crosshair(mut self, freq: Option<f64>) -> Self {
+        self.crosshair = freq;
+        self
+    }
+
+    /// Draw the antenna with this name in its color on top of the others,
+    /// which are dimmed. `None` colors every antenna.
+    pub fn focus(mut self, focus: Option<&'a str>) -> Self {
+        self.focus = focus;
+        self
+    }
+
+    /// Antennas to leave off the plot.
+    pub fn hidden(mut self, hidden: &'a HashSet<String>) -> Self {
+        self.hidden = Some(hidden);
+        self
+    }
+
+    /// Antennas that returned no valid samples, listed in the legend as such.
+    pub fn no_data(mut self, no_data: &'a HashSet<String>) -> Self {
+        self.no_data = Some(no_data);
+        self
+    }
+
+    /// Mark traces whose saturated fraction exceeds this threshold.
+    pub fn saturation_threshold(mut self, threshold: Option<f64>) -> Self {
+        self.saturation_threshold = threshold;
+        self
+    }
+
+    /// Draw a combined spectrum in bold over the individual traces, which are
+    /// either faded out or left off entirely.
+    pub fn median(mut self, median: Option<&'a AutoSpectra>, show_traces: bool) -> Self {
+        self.median = median;
+        self.show_traces = show_traces;
+        self
+    }
+
+    /// Draw the held maximum of each antenna as a dashed trace in the same color.
+    pub fn max_hold(mut self, max_hold: Option<&'a AutoSpectra>) -> Self {
+        self.max_hold = max_hold;
+        self
+    }
+
+    /// Draw faint lines bracketing each trace, e.g. its ±1σ band.
+    pub fn spread(mut self, spread: Option<&'a (AutoSpectra, AutoSpectra)>) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Draw these flagged samples in red on top of the traces.
+    pub fn outliers(mut self, outliers: Option<&'a AutoSpectra>) -> Self {
+        self.outliers = outliers;
+        self
+    }
+
+    /// Draw the samples of each overlay as dots in its color on top of the
+    /// traces, e.g. the channels flagged by a derived statistic.
+    pub fn overlays(mut self, overlays: &'a [(AutoSpectra, Color)]) -> Self {
+        self.overlays = overlays;
+        self
+    }
+
+    /// Shade these (start, stop) frequency ranges behind the traces.
+    pub fn bands(mut self, bands: &'a [(f64, f64)]) -> Self {
+        self.bands = bands;
+        self
+    }
+
+    /// Draw labeled vertical lines at these frequencies.
+    pub fn markers(mut self, markers: &'a [(f64, String)]) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    /// Label the y-axis with the level the dB values are relative to.
+    pub fn db_reference(mut self, level: Option<f64>) -> Self {
+        self.db_reference = level;
+        self
+    }
+
+    /// Label the y-axis as dB above each antenna's noise floor.
+    pub fn noise_floor(mut self, noise_floor: bool) -> Self {
+        self.noise_floor = noise_floor;
+        self
+    }
+
+    /// Label the dB y-axis in raw units, giving a log scaled axis for raw data.
+    pub fn raw_labels(mut self, raw_labels: bool) -> Self {
+        self.raw_labels = raw_labels;
+        self
+    }
+
+    /// Colors used for the traces.
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Colors of the axes and of the lines drawn over the traces.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Symbol used for the traces, the median and the vertical lines.
+    pub fn marker(mut self, marker: TraceMarker) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    /// Draw the traces and the median as points instead of lines.
+    pub fn scatter(mut self, scatter: bool) -> Self {
+        self.scatter = scatter;
+        self
+    }
+
+    /// Thin the traces to about one sample per point the chart can draw,
+    /// keeping the largest of each run, instead of drawing every sample.
+    pub fn fit_width(mut self, fit_width: bool) -> Self {
+        self.fit_width = fit_width;
+        self
+    }
+
+    /// Draw the built-in legend in the corner of the chart.
+    pub fn legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Formats a plotted y value for the labels and readout.
+    ///
+    /// Raw labels undo the dB reference level so they read in absolute units,
+    /// above the noise floor they are the ratio to the floor.
+    fn format_y(&self, y: f64, precision: usize) -> String {
+        let level = match self.noise_floor {
+            true => 1.0,
+            false => self.db_reference.unwrap_or(1.0),
+        };
+        match self.raw_labels {
+            true => format!("{:.3e}", level * 10.0_f64.powf(y / 10.0)),
+            false => format!("{y:.precision$}"),
+        }
+    }
+
+    fn is_hidden(&self, name: &str) -> bool {
+        self.hidden.is_some_and(|hidden| hidden.contains(name))
+    }
+
+    fn has_no_data(&self, name: &str) -> bool {
+        self.no_data.is_some_and(|no_data| no_data.contains(name))
+    }
+
+    /// Whether another antenna is focused, leaving this one dimmed.
+    fn is_dimmed(&self, name: &str) -> bool {
+        self.focus.is_some_and(|focus| focus != name)
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Plots the dB spectra, overriding the choice stored in the data.
+    pub fn log(mut self, plot_log: bool) -> Self {
+        self.plot_log = plot_log;
+        self
+    }
+
+    /// The traces as they are plotted, either raw or in dB.
+    fn plot_data(&self) -> Option<&'a [Vec<(f64, f64)>]> {
+        self.data.map(|specs| match self.plot_log {
+            true => specs.log_spectra.as_slice(),
+            false => specs.spectra.as_slice(),
+        })
+    }
+
+    /// One line per antenna with the sample closest to the crosshair.
+    fn readout(&self, freq: f64) -> Vec<String> {
+        let (Some(specs), Some(plot_data)) = (self.data, self.plot_data()) else {
+            return vec![];
+        };
+
+        let mut lines = vec![format!("Cursor: {freq:.3}")];
+        lines.extend(
+            specs
+                .ant_names
+                .iter()
+                .zip(plot_data)
+                .filter(|(name, _)| !self.is_hidden(name))
+                .filter_map(|(name, trace)| {
+                    nearest_index(trace, freq).map(|index| {
+                        let (x, y) = trace[index];
+                        format!("{name}: ({x:.3}, {})", self.format_y(y, 3))
+                    })
+                }),
+        );
+        lines
+    }
+}
+impl Widget for SpectrumChart<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        StatefulWidget::render(self, area, buf, &mut ChartView::default());
+    }
+}
+impl StatefulWidget for SpectrumChart<'_> {
+    type State = ChartView;
+
+    fn render(self, area: Rect, buf: &mut Buffer, view: &mut ChartView) {
+        let n_spectra = self.data.map_or(0, |specs| specs.spectra.len());
+        let trace_color = |cnt: usize| self.palette.color(cnt, n_spectra);
+
+        let plot_log = self.plot_log;
+        let marker = self.marker.symbol();
+        let graph_type = match self.scatter {
+            true => GraphType::Scatter,
+            false => GraphType::Line,
+        };
+
+        // only what is drawn is thinned, the readout still uses every sample
+        let thinned = self.fit_width.then(|| {
+            let xmin = self.xlims.0.unwrap_or(f64::NEG_INFINITY);
+            let xmax = self.xlims.1.unwrap_or(f64::INFINITY);
+            // the y labels still take a few columns, so this slightly oversamples
+            let inner = self.block.as_ref().map_or(area, |block| block.inner(area));
+            // Braille packs two columns of dots into each cell
+            let n_bins = 2 * inner.width as usize;
+            self.plot_data()
+                .unwrap_or_default()
+                .iter()
+                .map(|trace| decimate_max(trace, xmin, xmax, n_bins))
+                .collect::<Vec<_>>()
+        });
+
+        let mut traces = thinned
+            .as_deref()
+            .or(self.plot_data())
+            .filter(|_| self.show_traces || self.median.is_none())
+            .unwrap_or_default()
+            .iter()
+            .zip(self.data.iter().flat_map(|specs| specs.ant_names.iter()))
+            .enumerate()
+            // hidden antennas keep their color slot so the others don't change
+            .filter(|(_, (x, name))| {
+                (!x.is_empty() || self.has_no_data(name)) && !self.is_hidden(name)
+            })
+            .map(|(cnt, (x, name))| {
+                let color = match self.median {
+                    Some(_) => self.theme.dim(),
+                    None if self.is_dimmed(name) => self.theme.dim(),
+                    None => trace_color(cnt),
+                };
+
+                // still listed in the legend so it isn't mistaken for a typo
+                let (label, style) = match self.has_no_data(name) {
+                    true => (
+                        format!("{name} (no data)"),
+                        Style::default()
+                            .fg(self.theme.dim())
+                            .add_modifier(Modifier::CROSSED_OUT),
+                    ),
+                    false => (name.clone(), Style::default().fg(color)),
+                };
+                let dataset = Dataset::default()
+                    .name(label)
+                    .marker(marker)
+                    .style(style)
+                    .graph_type(graph_type)
+                    .data(x.as_slice());
+                (!self.is_dimmed(name), dataset)
+            })
+            .collect::<Vec<_>>();
+        // later datasets are drawn over earlier ones, keep the focused trace visible
+        traces.sort_by_key(|(focused, _)| *focused);
+        let mut datasets = traces
+            .into_iter()
+            .map(|(_, dataset)| dataset)
+            .collect::<Vec<_>>();
+
+        if let Some(median) = self.median {
+            let trace = match plot_log {
+                true => median.log_spectra.first(),
+                false => median.spectra.first(),
+            };
+            datasets.extend(trace.map(|trace| {
+                Dataset::default()
+                    .name("Median")
+                    .marker(marker)
+                    .style(
+                        Style::default()
+                            .fg(self.theme.text())
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .graph_type(graph_type)
+                    .data(trace.as_slice())
+            }));
+        }
+
+        if let (Some(max_hold), Some(specs)) = (self.max_hold, self.data) {
+            let hold_data = match plot_log {
+                true => &max_hold.log_spectra,
+                false => &max_hold.spectra,
+            };
+            let dash_range = (
+                self.xlims.0.unwrap_or(max_hold.freq_min),
+                self.xlims.1.unwrap_or(max_hold.freq_max),
+            );
+            datasets.extend(
+                max_hold
+                    .ant_names
+                    .iter()
+                    .zip(hold_data.iter())
+                    .filter(|(name, trace)| !trace.is_empty() && !self.is_hidden(name))
+                    .flat_map(|(name, trace)| {
+                        let cnt = specs
+                            .ant_names
+                            .iter()
+                            .position(|x| x == name)
+                            .unwrap_or_default();
+
+                        let color = match self.is_dimmed(name) {
+                            true => self.theme.dim(),
+                            false => trace_color(cnt),
+                        };
+
+                        dashes(trace, dash_range, MAX_HOLD_DASHES)
+                            .into_iter()
+                            .map(move |dash| {
+                                Dataset::default()
+                                    .marker(marker)
+                                    .style(Style::default().fg(color))
+                                    .graph_type(GraphType::Line)
+                                    .data(dash)
+                            })
+                    }),
+            );
+        }
+
+        if let (Some((lower, upper)), Some(specs)) = (self.spread, self.data) {
+            for bound in [lower, upper] {
+                let bound_data = match plot_log {
+                    true => &bound.log_spectra,
+                    false => &bound.spectra,
+                };
+                datasets.extend(
+                    bound
+                        .ant_names
+                        .iter()
+                        .zip(bound_data.iter())
+                        .filter(|(name, trace)| !trace.is_empty() && !self.is_hidden(name))
+                        .map(|(name, trace)| {
+                            let cnt = specs
+                                .ant_names
+                                .iter()
+                                .position(|x| x == name)
+                                .unwrap_or_default();
+
+                            let color = match self.is_dimmed(name) || self.median.is_some() {
+                                true => self.theme.dim(),
+                                false => trace_color(cnt),
+                            };
+
+                            Dataset::default()
+                                .marker(symbols::Marker::Braille)
+                                .style(Style::default().fg(color).add_modifier(Modifier::DIM))
+                                .graph_type(GraphType::Line)
+                                .data(trace.as_slice())
+                        }),
+                );
+            }
+        }
+
+        if let Some(outliers) = self.outliers {
+            let flagged = match plot_log {
+                true => &outliers.log_spectra,
+                false => &outliers.spectra,
+            };
+            datasets.extend(
+                outliers
+                    .ant_names
+                    .iter()
+                    .zip(flagged.iter())
+                    .filter(|(name, trace)| !trace.is_empty() && !self.is_hidden(name))
+                    .map(|(_, trace)| {
+                        Dataset::default()
+                            .marker(symbols::Marker::Dot)
+                            .style(Style::default().fg(self.theme.error()))
+                            .graph_type(GraphType::Scatter)
+                            .data(trace.as_slice())
+                    }),
+            );
+        }
+
+        for (overlay, color) in self.overlays {
+            let samples = match plot_log {
+                true => &overlay.log_spectra,
+                false => &overlay.spectra,
+            };
+            datasets.extend(
+                overlay
+                    .ant_names
+                    .iter()
+                    .zip(samples.iter())
+                    .filter(|(name, trace)| !trace.is_empty() && !self.is_hidden(name))
+                    .map(|(_, trace)| {
+                        Dataset::default()
+                            .marker(symbols::Marker::Dot)
+                            .style(Style::default().fg(*color))
+                            .graph_type(GraphType::Scatter)
+                            .data(trace.as_slice())
+                    }),
+            );
+        }
+
+        // data without any finite values gives infinite or NaN bounds,
+        // fall back to the defaults instead
+        let xmin = self
+            .xlims
+            .0
+            .or_else(|| self.data.map(|x| x.freq_min))
+            .filter(|x| x.is_finite())
+            .unwrap_or(0.0);
+        let xmax = self
+            .xlims
+            .1
+            .or_else(|| self.data.map(|x| x.freq_max))
+            .filter(|x| x.is_finite())
+            .unwrap_or(10.0);
+
+        // split panes can plot the other scale than the data
+        let ymin = self
+            .ylims
+            .0
+            .or_else(|| self.data.map(|x| x.ymin(plot_log)))
+            .filter(|y| y.is_finite())
+            .unwrap_or(-120.0);
+
+        let ymax = self
+            .ylims
+            .1
+            .or_else(|| self.data.map(|x| x.ymax(plot_log)))
+            .filter(|y| y.is_finite())
+            .unwrap_or(-20.0);
+
+        // the crosshair has to span the axis exactly, points outside the
+        // bounds are not drawn at all
+        let crosshair = self.crosshair.map(|freq| [(freq, ymin), (freq, ymax)]);
+        if let Some(crosshair) = crosshair.as_ref() {
+            datasets.push(
+                Dataset::default()
+                    .marker(marker)
+                    .style(Style::default().fg(self.theme.text()))
+                    .graph_type(GraphType::Line)
+                    .data(crosshair),
+            );
+        }
+
+        // markers outside the x-range are left off
+        let marker_lines = self
+            .markers
+            .iter()
+            .filter(|(freq, _)| (xmin..=xmax).contains(freq))
+            .map(|(freq, _)| [(*freq, ymin), (*freq, ymax)])
+            .collect::<Vec<_>>();
+        datasets.extend(marker_lines.iter().map(|line| {
+            Dataset::default()
+                .marker(marker)
+                .style(Style::default().fg(self.theme.marker()))
+                .graph_type(GraphType::Line)
+                .data(line)
+        }));
+
+        // saturated traces get a red band along the bottom of the chart
+        // spanning their frequencies
+        let saturated = match (self.data, self.saturation_threshold) {
+            (Some(specs), Some(threshold)) => specs
+                .spectra
+                .iter()
+                .zip(specs.ant_names.iter())
+                .zip(specs.saturation.iter().flatten())
+                .filter(|((_, name), frac)| **frac > threshold && !self.is_hidden(name))
+                .map(|((trace, _), _)| trace.iter().map(|(freq, _)| (*freq, ymin)).collect())
+                .collect::<Vec<Vec<_>>>(),
+            _ => vec![],
+        };
+        datasets.extend(saturated.iter().map(|band| {
+            Dataset::default()
+                .marker(symbols::Marker::Block)
+                .style(Style::default().fg(self.theme.error()))
+                .graph_type(GraphType::Scatter)
+                .data(band)
+        }));
+
+        let inner = self.block.as_ref().map_or(area, |block| block.inner(area));
+
+        // as many labels as fit without running into each other
+        let yticks = tick_values(ymin, ymax, inner.height, YLABEL_ROWS);
+        let precision = label_precision(tick_step(&yticks));
+        let ylabels = yticks
+            .iter()
+            .map(|y| self.format_y(*y, precision))
+            .collect::<Vec<_>>();
+        let ylabel_width = ylabels.iter().map(|x| x.len()).max().unwrap_or(0) as u16;
+
+        let xlabels = {
+            // size the labels at the default density, then fit as many as the width allows
+            let format = |x: f64, precision: usize| format!("{x:.precision$}");
+            let guess = label_precision((xmax - xmin) / 10.0);
+            let widest = format(xmin, guess).len().max(format(xmax, guess).len());
+            let width = inner.width.saturating_sub(ylabel_width);
+            let xticks = tick_values(xmin, xmax, width, widest + XLABEL_GAP);
+            let precision = label_precision(tick_step(&xticks));
+            xticks
+                .iter()
+                .map(|x| format(*x, precision))
+                .collect::<Vec<_>>()
+        };
+        let xlabel_width = xlabels.first().map_or(0, |label| label.len()) as u16;
+        let labels = xlabels.into_iter().map(Span::raw).collect::<Vec<_>>();
+
+        let xtitle = self.data.map_or("Freq [MHz]", |spec| spec.x_unit.label());
+
+        let title = match (plot_log || self.data.is_none(), self.db_reference) {
+            _ if self.raw_labels && self.noise_floor => "Power [× floor, log scale]".to_owned(),
+            (_, Some(level)) if self.raw_labels => {
+                format!("Power [Absolute, log scale, 0 dB re {level}]")
+            }
+            _ if self.raw_labels => "Power [Absolute, log scale]".to_owned(),
+            (true, _) if self.noise_floor => "Power [dB above floor]".to_owned(),
+            (true, Some(level)) => format!("Power [dB re {level}]"),
+            (true, None) => "Power [dB]".to_owned(),
+            (false, _) => "Power [Absolute]".to_owned(),
+        };
+
+        let mut chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .title(xtitle)
+                    .style(Style::default().fg(self.theme.muted()))
+                    .bounds([xmin, xmax])
+                    .labels(labels),
+            )
+            .y_axis(
+                Axis::default()
+                    .title(title)
+                    .style(Style::default().fg(self.theme.muted()))
+                    .bounds([ymin, ymax])
+                    .labels(ylabels.into_iter().map(Span::raw).collect::<Vec<_>>()),
+            );
+        if let Some(block) = self.block.clone() {
+            chart = chart.block(block);
+        }
+        if !self.legend {
+            chart = chart.legend_position(None);
+        }
+        chart.render(area, buf);
+
+        // The chart resets the background of the whole plot so the bands
+        // are painted afterwards, only changing the background keeps the
+        // traces visible on top.
+        let graph = graph_area(inner, ylabel_width, xlabel_width);
+        *view = ChartView {
+            graph,
+            x: (xmin, xmax),
+            y: (ymin, ymax),
+        };
+        if graph.width > 0 && xmax > xmin {
+            let to_column = |freq: f64| {
+                let frac = (freq - xmin) / (xmax - xmin);
+                graph.x + (frac * (graph.width - 1) as f64).round() as u16
+            };
+            for (freq, label) in self.markers.iter() {
+                if !(xmin..=xmax).contains(freq) || label.is_empty() {
+                    continue;
+                }
+                // labels go just right of the line along the top of the plot
+                let x = to_column(*freq) + 1;
+                if x < graph.right() {
+                    buf.set_stringn(
+                        x,
+                        graph.y,
+                        label,
+                        (graph.right() - x) as usize,
+                        Style::default().fg(self.theme.marker()),
+                    );
+                }
+            }
+            for (start, stop) in self.bands.iter() {
+                if *stop < xmin || *start > xmax {
+                    continue;
+                }
+                for x in to_column(start.max(xmin))..=to_column(stop.min(xmax)) {
+                    for y in graph.top()..graph.bottom() {
+                        buf[(x, y)].set_bg(self.theme.band());
+                    }
+                }
+            }
+        }
+
+        // The legend sits in the top right corner so the readout goes in the
+        // top left of the plotting area, just past the y-axis labels.
+        if let Some(freq) = self.crosshair {
+            let lines = self.readout(freq);
+            if lines.is_empty() {
+                return;
+            }
+            let width = lines.iter().map(|x| x.chars().count()).max().unwrap_or(0) as u16 + 2;
+            let readout_area = Rect::new(
+                inner.x + ylabel_width + 2,
+                inner.y + 1,
+                width.min(inner.width / 2),
+                lines.len() as u16 + 2,
+            )
+            .intersection(inner);
+
+            Clear.render(readout_area, buf);
+            Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+                .block(Block::default().borders(Borders::ALL))
+                .render(readout_area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn y_bounds_follow_the_plotted_scale() {
+        let spec = AutoSpectra::from_pairs(
+            vec!["LWA-001A".to_owned()],
+            vec![vec![(30.0, 1.0), (40.0, 10.0), (50.0, 100.0)]],
+            true,
+        );
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+
+        let mut view = ChartView::default();
+        StatefulWidget::render(SpectrumChart::new(Some(&spec)), area, &mut buf, &mut view);
+        assert_eq!((0.0, 22.0), view.y);
+
+        // the raw pane of a split view gets raw bounds from dB data
+        let mut view = ChartView::default();
+        let raw = SpectrumChart::new(Some(&spec)).log(false);
+        StatefulWidget::render(raw, area, &mut buf, &mut view);
+        assert_eq!((0.9, 110.0), view.y);
+    }
+}
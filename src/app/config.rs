@@ -0,0 +1,165 @@
+#[cfg(feature = "ovro")]
+use std::collections::BTreeMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::palette::Palette;
+
+/// Directory holding the settings files, `$XDG_CONFIG_HOME/spectrum-tui`
+/// falling back to `~/.config/spectrum-tui`.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("spectrum-tui"))
+}
+
+/// User provided settings read from `config.toml` in the [config_dir].
+///
+/// ```toml
+/// # frequency ranges, in x-axis units, shaded on the chart
+/// rfi_bands = [[88.0, 108.0], [118.0, 137.0]]
+///
+/// # antenna filters to cycle through (OVRO), also written by the save preset key
+/// [filter_presets]
+/// core = ["LWA-124", "LWA-125", "LWA-126"]
+/// outriggers = ["LWA-353", "LWA-354"]
+///
+/// # written by the save defaults key
+/// [view]
+/// log_plot = true
+/// smoothing_width = 5
+/// palette = "viridis"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub(crate) struct Config {
+    /// Known RFI bands as (start, stop) pairs
+    #[serde(default)]
+    pub rfi_bands: Vec<(f64, f64)>,
+    /// View to start in, replacing the settings remembered from the last run
+    #[serde(default)]
+    pub view: Option<ViewConfig>,
+    #[cfg(feature = "ovro")]
+    /// Named antenna filters
+    #[serde(default)]
+    pub filter_presets: BTreeMap<String, Vec<String>>,
+}
+
+/// View settings saved as the defaults for future launches.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ViewConfig {
+    pub log_plot: Option<bool>,
+    #[serde(default)]
+    pub log_axis: bool,
+    /// Lower Y-limit in absolute units
+    pub ymin: Option<f64>,
+    /// Upper Y-limit in absolute units
+    pub ymax: Option<f64>,
+    /// Width in channels of the running mean along frequency
+    pub smoothing_width: Option<usize>,
+    pub palette: Option<Palette>,
+}
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    /// Reads the config file, falling back to the defaults if the file
+    /// is missing or cannot be parsed.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        if !path.exists() {
+            return Self::default();
+        }
+
+        let mut config: Self = match fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read {}", path.display()))
+            .and_then(|text| {
+                toml::from_str(&text).with_context(|| format!("Unable to parse {}", path.display()))
+            }) {
+            Ok(config) => config,
+            Err(err) => {
+                log::warn!("{err:#}. Using default settings.");
+                return Self::default();
+            }
+        };
+
+        // allow the band edges to be given in either order
+        for band in config.rfi_bands.iter_mut() {
+            if band.0 > band.1 {
+                *band = (band.1, band.0);
+            }
+        }
+
+        config
+    }
+
+    /// Writes the view into the `[view]` table of the config file, keeping
+    /// the other settings. Comments in the file are not preserved.
+    ///
+    /// Returns where the file was written.
+    pub fn save_view(view: &ViewConfig) -> Result<PathBuf> {
+        let path = Self::path().context("Unable to determine the config directory.")?;
+
+        let mut table = read_table(&path)?;
+        table.insert(
+            "view".to_owned(),
+            toml::Value::try_from(view).context("Unable to serialize view settings.")?,
+        );
+        write_table(&path, &table)?;
+
+        Ok(path)
+    }
+
+    #[cfg(feature = "ovro")]
+    /// Adds the antennas to the `[filter_presets]` table of the config file
+    /// under `name`, replacing any preset with the same name.
+    ///
+    /// Returns where the file was written.
+    pub fn save_filter_preset(name: &str, antennas: &[String]) -> Result<PathBuf> {
+        let path = Self::path().context("Unable to determine the config directory.")?;
+
+        let mut table = read_table(&path)?;
+        let presets = table
+            .entry("filter_presets")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("filter_presets in {} is not a table", path.display()))?;
+        presets.insert(
+            name.to_owned(),
+            toml::Value::try_from(antennas).context("Unable to serialize the antenna filter.")?,
+        );
+        write_table(&path, &table)?;
+
+        Ok(path)
+    }
+}
+
+/// Contents of the config file, empty if there is none yet.
+fn read_table(path: &Path) -> Result<toml::Table> {
+    // don't clobber a file we can't read back
+    match path.exists() {
+        true => fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?
+            .parse::<toml::Table>()
+            .with_context(|| format!("Unable to parse {}", path.display())),
+        false => Ok(toml::Table::new()),
+    }
+}
+
+fn write_table(path: &Path, table: &toml::Table) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Unable to create {}", parent.display()))?;
+    }
+    fs::write(path, table.to_string())
+        .with_context(|| format!("Unable to write {}", path.display()))
+}
@@ -0,0 +1,296 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+#[cfg(feature = "ovro")]
+use ndarray_npy::write_npy;
+use plotters::prelude::*;
+
+use crate::loader::AutoSpectra;
+
+/// A file name in the current directory stamped with the current unix time,
+/// e.g. `spectrum-tui_1700000000.csv`.
+pub(crate) fn timestamped_path(extension: &str) -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+
+    PathBuf::from(format!("spectrum-tui_{now}.{extension}"))
+}
+
+/// Writes the raw spectra as CSV, a frequency column followed by one column
+/// per antenna.
+///
+/// Antennas are grouped by their frequency axis, if they do not all share the
+/// same one each group is written as its own section separated by a blank line.
+pub(crate) fn write_csv(spec: &AutoSpectra, path: &Path) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Unable to create {}", path.display()))?;
+
+    write_sections(spec, BufWriter::new(file))
+        .with_context(|| format!("Unable to write {}", path.display()))
+}
+
+/// Quotes a CSV field holding a separator, quote or line break.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => Cow::Owned(format!("\"{}\"", field.replace('"', "\"\""))),
+        false => Cow::Borrowed(field),
+    }
+}
+
+fn write_sections<W: Write>(spec: &AutoSpectra, mut writer: W) -> io::Result<()> {
+    let mut groups: Vec<Vec<usize>> = vec![];
+    for (index, trace) in spec.spectra.iter().enumerate() {
+        let same_axis = |other: &Vec<usize>| {
+            let first = &spec.spectra[other[0]];
+            first.len() == trace.len() && first.iter().zip(trace).all(|(a, b)| a.0 == b.0)
+        };
+        match groups.iter_mut().find(|group| same_axis(group)) {
+            Some(group) => group.push(index),
+            None => groups.push(vec![index]),
+        }
+    }
+
+    for (cnt, group) in groups.iter().enumerate() {
+        if cnt > 0 {
+            writeln!(writer)?;
+        }
+
+        write!(writer, "freq")?;
+        for index in group {
+            write!(writer, ",{}", csv_field(&spec.ant_names[*index]))?;
+        }
+        writeln!(writer)?;
+
+        for (row, (freq, _)) in spec.spectra[group[0]].iter().enumerate() {
+            write!(writer, "{freq}")?;
+            for index in group {
+                write!(writer, ",{}", spec.spectra[*index][row].1)?;
+            }
+            writeln!(writer)?;
+        }
+    }
+
+    writer.flush()
+}
+
+/// Writes the raw spectra as a (n_ant, nfreqs) npy array that can be read back
+/// with the `file` subcommand.
+///
+/// The antenna names and frequency axis are written next to it with a `.txt`
+/// extension.
+#[cfg(feature = "ovro")]
+pub(crate) fn write_npy_snapshot(spec: &AutoSpectra, path: &Path) -> Result<()> {
+    let data = spec
+        .as_array()
+        .context("Unable to save spectra with different numbers of channels to npy.")?;
+
+    write_npy(path, &data).with_context(|| format!("Unable to write {}", path.display()))?;
+
+    let meta_path = path.with_extension("txt");
+    let file = File::create(&meta_path)
+        .with_context(|| format!("Unable to create {}", meta_path.display()))?;
+    write_axes(spec, BufWriter::new(file))
+        .with_context(|| format!("Unable to write {}", meta_path.display()))
+}
+
+#[cfg(feature = "ovro")]
+fn write_axes<W: Write>(spec: &AutoSpectra, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "# antennas")?;
+    for name in spec.ant_names.iter() {
+        writeln!(writer, "{name}")?;
+    }
+
+    writeln!(writer, "# {}", spec.x_unit.label())?;
+    for (freq, _) in spec.spectra.first().into_iter().flatten() {
+        writeln!(writer, "{freq}")?;
+    }
+
+    writer.flush()
+}
+
+/// Renders the spectra to a PNG with the same axes as the terminal chart.
+///
+/// Limits left as `None` fall back to the data bounds.
+pub(crate) fn write_png(
+    spec: &AutoSpectra,
+    path: &Path,
+    xlims: (Option<f64>, Option<f64>),
+    ylims: (Option<f64>, Option<f64>),
+) -> Result<()> {
+    draw_png(spec, path, xlims, ylims)
+        .with_context(|| format!("Unable to write {}", path.display()))
+}
+
+/// (min, max) of finite values, `None` if there are none.
+fn finite_range(vals: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    vals.filter(|val| val.is_finite())
+        .fold(None, |range, val| match range {
+            None => Some((val, val)),
+            Some((min, max)) => Some((val.min(min), val.max(max))),
+        })
+}
+
+/// Widens an empty range around its value so the axis can be drawn.
+fn pad_range((min, max): (f64, f64)) -> (f64, f64) {
+    match max > min {
+        true => (min, max),
+        false => {
+            let pad = match min.abs() {
+                0.0 => 1.0,
+                val => 0.05 * val,
+            };
+            (min - pad, min + pad)
+        }
+    }
+}
+
+/// Axis ranges for the PNG, limits that are `None` or not finite use the
+/// bounds of the finite samples in view.
+fn plot_bounds(
+    plot_data: &[Vec<(f64, f64)>],
+    xlims: (Option<f64>, Option<f64>),
+    ylims: (Option<f64>, Option<f64>),
+) -> Result<((f64, f64), (f64, f64))> {
+    let finite = || {
+        plot_data
+            .iter()
+            .flatten()
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+    };
+    let (data_xmin, data_xmax) =
+        finite_range(finite().map(|(x, _)| *x)).context("There are no finite samples to plot.")?;
+
+    let xmin = xlims.0.filter(|x| x.is_finite()).unwrap_or(data_xmin);
+    let xmax = xlims.1.filter(|x| x.is_finite()).unwrap_or(data_xmax);
+    ensure!(xmin <= xmax, "The X-limits {xmin} to {xmax} are reversed.");
+
+    // only the samples within the x-limits set the automatic y-limits
+    let in_view = finite_range(
+        finite()
+            .filter(|(x, _)| (xmin..=xmax).contains(x))
+            .map(|(_, y)| *y),
+    );
+    let ymin = ylims.0.filter(|y| y.is_finite()).or(in_view.map(|y| y.0));
+    let ymax = ylims.1.filter(|y| y.is_finite()).or(in_view.map(|y| y.1));
+    let (Some(ymin), Some(ymax)) = (ymin, ymax) else {
+        bail!("There are no finite samples between {xmin} and {xmax} to plot.");
+    };
+    ensure!(ymin <= ymax, "The Y-limits {ymin} to {ymax} are reversed.");
+
+    Ok((pad_range((xmin, xmax)), pad_range((ymin, ymax))))
+}
+
+fn draw_png(
+    spec: &AutoSpectra,
+    path: &Path,
+    xlims: (Option<f64>, Option<f64>),
+    ylims: (Option<f64>, Option<f64>),
+) -> Result<()> {
+    let plot_data = match spec.plot_log {
+        true => &spec.log_spectra,
+        false => &spec.spectra,
+    };
+
+    let ((xmin, xmax), (ymin, ymax)) = plot_bounds(plot_data, xlims, ylims)?;
+
+    let root = BitMapBackend::new(path, (1280, 720)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("AutoSpectra", ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(70)
+        .build_cartesian_2d(xmin..xmax, ymin..ymax)?;
+
+    chart
+        .configure_mesh()
+        .x_labels(11)
+        .y_labels(11)
+        .x_desc(spec.x_unit.label())
+        .y_desc(match spec.plot_log {
+            true => "Power [dB]",
+            false => "Power [Absolute]",
+        })
+        .draw()?;
+
+    for (cnt, (trace, name)) in plot_data.iter().zip(spec.ant_names.iter()).enumerate() {
+        let color = Palette99::pick(cnt).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                trace.iter().copied().filter(|(_, val)| val.is_finite()),
+                &color,
+            ))?
+            .label(name)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &color));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ndarray::{arr1, arr2};
+
+    #[test]
+    fn png_bounds() {
+        let data = vec![
+            vec![(1.0, 5.0), (2.0, f64::NAN), (3.0, -2.0)],
+            vec![],
+            vec![(f64::NAN, 100.0), (4.0, f64::INFINITY)],
+        ];
+        assert_eq!(
+            ((1.0, 3.0), (-2.0, 5.0)),
+            plot_bounds(&data, (None, None), (None, None)).unwrap()
+        );
+        // only samples within the x-limits set the y-limits
+        assert_eq!(
+            ((1.0, 2.0), (4.75, 5.25)),
+            plot_bounds(&data, (None, Some(2.0)), (None, None)).unwrap()
+        );
+        // limits that aren't finite are ignored
+        assert_eq!(
+            ((0.0, 3.0), (-2.0, 10.0)),
+            plot_bounds(&data, (Some(0.0), Some(f64::NAN)), (None, Some(10.0))).unwrap()
+        );
+
+        assert!(plot_bounds(&[], (None, None), (None, None)).is_err());
+        let all_nan = vec![vec![(1.0, f64::NAN), (2.0, f64::NAN)]];
+        assert!(plot_bounds(&all_nan, (None, None), (None, None)).is_err());
+        assert!(plot_bounds(&data, (Some(10.0), Some(20.0)), (None, None)).is_err());
+    }
+
+    #[test]
+    fn csv_quotes_names() {
+        let spec = AutoSpectra::new(
+            vec!["LWA-001a".to_owned(), "snap 1, \"A\"".to_owned()],
+            arr1(&[10.0, 20.0]),
+            arr2(&[[1.0, 2.0], [3.0, 4.0]]),
+            false,
+        );
+
+        let mut out = vec![];
+        write_sections(&spec, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            "freq,LWA-001a,\"snap 1, \"\"A\"\"\"\n10,1,3\n20,2,4\n",
+            text
+        );
+    }
+}
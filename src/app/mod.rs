@@ -1,50 +1,83 @@
 use std::{
+    collections::{HashSet, VecDeque},
     io::{self, Write},
+    path::PathBuf,
     pin::Pin,
-    time::Duration,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 #[cfg(not(any(feature = "ovro", feature = "lwa-na")))]
 use ndarray::{arr2, Array};
 
 use anyhow::{bail, Context, Error, Result};
-use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{
+    Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
 use futures::Stream;
-use log::{debug, info};
+use log::{debug, info, LevelFilter};
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Clear},
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph},
     Frame, Terminal,
 };
-use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::{
+    sync::mpsc::{error::TrySendError, Receiver, Sender},
+    task::JoinHandle,
+};
 use tokio_stream::{wrappers::ReceiverStream, StreamExt, StreamMap};
 use tui_textarea::TextArea;
 
 #[cfg(feature = "lwa-na")]
-use crate::loader::north_arm::{DRLoader, DiskLoader as NADiskLoader, SaturationStats};
+use crate::loader::north_arm::{
+    DRLoader, DiskLoader as NADiskLoader, SaturationLog, SaturationStats,
+};
 
 #[cfg(feature = "ovro")]
 use {
-    crate::loader::ovro::{DiskLoader as OvroDiskLoader, EtcdLoader},
-    ratatui::{
-        layout::Position,
-        widgets::{HighlightSpacing, List, ListItem, ListState, Paragraph},
+    crate::loader::{
+        ovro::{matches_known_antennas, DiskLoader as OvroDiskLoader, EtcdLoader, SpectraShape},
+        BackendStatus,
     },
+    ratatui::layout::Position,
+    std::{collections::BTreeMap, ops::Bound},
+    tokio::sync::watch,
 };
 
 // otherwise clippy complains about the Trait import
 #[allow(unused_imports)]
 use crate::{
-    loader::{AutoSpectra, SpectrumLoader},
+    loader::{
+        median_of, nearest_index,
+        replay::{self, ReplayLoader},
+        AutoSpectra, SpectrumLoader, StatusUpdate,
+    },
     Action, TuiType,
 };
 
+#[cfg(any(feature = "ovro", feature = "lwa-na"))]
+use crate::loader::StatusReporter;
+
+pub(crate) mod chart;
+mod config;
+mod export;
+pub(crate) mod palette;
+mod state;
+pub(crate) mod theme;
 pub(crate) mod ui;
+pub(crate) mod waterfall;
 
-#[cfg(feature = "ovro")]
-const SELECTED_STYLE: Style = Style::new().bg(Color::Gray).add_modifier(Modifier::BOLD);
+use chart::{ChartView, TraceMarker};
+use config::{Config, ViewConfig};
+use palette::Palette;
+use state::SavedState;
+use theme::Theme;
 
 enum StreamReturn {
     Action(Result<Event, io::Error>),
@@ -52,9 +85,145 @@ enum StreamReturn {
     Data((AutoSpectra, Option<SaturationStats>)),
     #[cfg(not(feature = "lwa-na"))]
     Data(AutoSpectra),
+    Status(StatusUpdate),
     Tick,
 }
 
+/// Commands sent from the UI to the spawned backend task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BackendCommand {
+    #[cfg(feature = "lwa-na")]
+    /// Move through the spectra in a file by the given number of spectra
+    Step(isize),
+    #[cfg(feature = "lwa-na")]
+    /// Cycle which DR tunings are plotted
+    CycleTuning,
+    #[cfg(feature = "lwa-na")]
+    /// Switch between the recorded products and Stokes parameters
+    ToggleStokes,
+    #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+    /// Change how often live data is polled
+    SetDelay(Duration),
+}
+
+/// Reads the latest spectra from every data recorder and merges them into one
+/// plot, the traces are labelled with their data recorder when there is more than one.
+///
+/// Warns when the recorders' frequency axes start or stop differing, tracked by `axes_differ`.
+#[cfg(feature = "lwa-na")]
+async fn poll_recorders(
+    loaders: &mut [DRLoader],
+    axes_differ: &mut bool,
+) -> Option<(AutoSpectra, Option<SaturationStats>)> {
+    let label = loaders.len() > 1;
+
+    let mut specs = vec![];
+    let mut stats = vec![];
+    let mut grids = vec![];
+    for loader in loaders.iter_mut() {
+        let Some(mut spec) = loader.get_data().await else {
+            continue;
+        };
+        let mut stat = loader.get_stats();
+        if label {
+            let host = &loader.data_recorder;
+            spec.ant_names
+                .iter_mut()
+                .for_each(|name| *name = format!("{host}:{name}"));
+            stat = stat.map(|stat| stat.with_host(host));
+        }
+        grids.push((loader.data_recorder.as_str(), spec.freq_grids()));
+        specs.push(spec);
+        stats.extend(stat);
+    }
+
+    // every trace keeps its own frequencies when stacked, but channels no
+    // longer line up between recorders, e.g. for the median
+    let differ = grids.windows(2).any(|pair| pair[0].1 != pair[1].1);
+    if differ && !*axes_differ {
+        let describe = |(host, grids): &(&str, Vec<(usize, f64, f64)>)| {
+            let grids = grids
+                .iter()
+                .map(|(n_chans, first, last)| {
+                    format!("{n_chans} channels {first:.3}-{last:.3} MHz")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{host}: {grids}")
+        };
+        log::warn!(
+            "Frequency axes differ between data recorders, each is plotted on its own axis. {}",
+            grids.iter().map(describe).collect::<Vec<_>>().join("; ")
+        );
+    } else if !differ && *axes_differ {
+        info!("Frequency axes of the data recorders match again.");
+    }
+    *axes_differ = differ;
+
+    let spec = AutoSpectra::stack(specs)?;
+    Some((spec, SaturationStats::stack(stats)))
+}
+
+/// How often an OVRO input file is checked for changes
+#[cfg(feature = "ovro")]
+const FILE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shortest allowed delay between polls of live data
+#[cfg(any(feature = "ovro", feature = "lwa-na"))]
+const MIN_POLL_DELAY: Duration = Duration::from_secs(1);
+
+/// Polls that can be missed before live data is flagged as stale
+const STALE_AFTER_POLLS: u32 = 3;
+
+/// How far, in robust standard deviations, a channel's spectral kurtosis
+/// must be from the median to be colored
+const KURTOSIS_SIGMA: f64 = 5.0;
+
+/// Quantile of each trace's power over frequency taken as its noise floor
+const NOISE_FLOOR_QUANTILE: f64 = 0.1;
+
+/// How long `--oneshot` waits for the first spectra, long enough for the
+/// etcd configuration retries
+const ONESHOT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How the median across antennas is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum MedianMode {
+    #[default]
+    Off,
+    /// Median in bold over the faded individual traces
+    WithTraces,
+    /// Only the median
+    Only,
+}
+impl MedianMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Off => Self::WithTraces,
+            Self::WithTraces => Self::Only,
+            Self::Only => Self::Off,
+        }
+    }
+}
+
+/// How the captured reference spectrum is removed from new spectra
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ReferenceMode {
+    #[default]
+    Off,
+    Difference,
+    Ratio,
+}
+impl ReferenceMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Difference,
+            Self::Difference => Self::Ratio,
+            Self::Ratio => Self::Off,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum InputMode {
     Normal,
@@ -62,7 +231,17 @@ enum InputMode {
     AntennaInput,
     #[cfg(feature = "ovro")]
     RemoveAntenna,
+    #[cfg(feature = "ovro")]
+    PresetName,
     ChartLims,
+    FreqLims,
+    Crosshair,
+    HideAntenna,
+    Compare,
+    Help,
+    RefLevel,
+    Marker,
+    ConfirmQuit,
 }
 
 #[cfg(feature = "ovro")]
@@ -70,152 +249,118 @@ enum InputMode {
 struct AntennaFilter {
     items: Vec<String>,
     state: ListState,
+    /// Rows shown in the removal popup, how far PageUp/PageDown move
+    page: u16,
 }
 
+/// Limits of one axis with the popup to type them in. The first two boxes
+/// hold the min and max, any further ones are spans around the data.
 #[derive(Debug, Clone)]
-pub(crate) struct Ylims<'a> {
+pub(crate) struct Limits<'a, const N: usize> {
     max: Option<f64>,
     min: Option<f64>,
 
     //  use an array to make switching focus easier
-    textareas: [TextArea<'a>; 2],
+    textareas: [TextArea<'a>; N],
+    titles: [&'static str; N],
 
     focus: usize,
     is_valid: bool,
     layout: Layout,
+    theme: Theme,
 }
-impl<'a> Ylims<'a> {
-    fn new() -> Self {
-        let min_text = {
-            let mut tmp = TextArea::default();
-            tmp.set_cursor_line_style(Style::default());
-            tmp.set_block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::DarkGray))
-                    .title("Ymin:"),
-            );
-            tmp.set_placeholder_text("auto");
-            tmp
-        };
 
-        let max_text = {
+/// Y-limits, stored in absolute units whether or not the plot is in dB.
+pub(crate) type Ylims<'a> = Limits<'a, 3>;
+/// X-limits in the units of the frequency axis.
+pub(crate) type Xlims<'a> = Limits<'a, 2>;
+
+impl<'a, const N: usize> Limits<'a, N> {
+    fn with_titles(titles: [&'static str; N]) -> Self {
+        let textareas = std::array::from_fn(|cnt| {
             let mut tmp = TextArea::default();
             tmp.set_cursor_line_style(Style::default());
-            tmp.set_block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::DarkGray))
-                    .title("Ymax:"),
-            );
-            tmp.set_placeholder_text("auto");
+            tmp.set_placeholder_text(Self::unset_text(cnt));
             tmp
-        };
+        });
 
-        Self {
+        let mut me = Self {
             max: None,
             min: None,
-            textareas: [min_text, max_text],
+            textareas,
+            titles,
             focus: 0,
             is_valid: true,
             layout: Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref()),
+                .constraints([Constraint::Ratio(1, N as u32); N].as_ref()),
+            theme: Theme::default(),
+        };
+        me.reset_blocks();
+        me
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.reset_blocks();
+    }
+
+    /// Text leaving a box unset, spans are off rather than automatic.
+    fn unset_text(cnt: usize) -> &'static str {
+        match cnt {
+            0 | 1 => "auto",
+            _ => "off",
         }
     }
 
-    pub(crate) fn get_max(&self, plot_log: bool) -> Option<f64> {
-        self.max.map(|val| match plot_log {
-            true => {
-                let tmp = 10.0 * val.log10();
-                match tmp.is_finite() {
-                    true => tmp,
-                    false => f64::INFINITY,
-                }
-            }
-            false => val,
-        })
+    /// Parses a box, `None` if it is unset. Only validated text is parsed.
+    fn parse(&self, cnt: usize, line: &str) -> Option<f64> {
+        let text = line.trim().to_lowercase();
+        match text == Self::unset_text(cnt) || text.is_empty() {
+            true => None,
+            false => Some(text.parse::<f64>().unwrap_or_else(|_| {
+                panic!("Valid {} text changed before parsing", self.titles[cnt])
+            })),
+        }
     }
 
-    pub(crate) fn get_min(&self, plot_log: bool) -> Option<f64> {
-        self.min.map(|val| match plot_log {
-            true => {
-                let tmp = 10.0 * val.log10();
-                match tmp.is_finite() {
-                    true => tmp,
-                    false => f64::NEG_INFINITY,
-                }
-            }
-            false => val,
-        })
+    /// Stores the limits in absolute units, swapping them if they are reversed.
+    fn set(&mut self, min: Option<f64>, max: Option<f64>) {
+        self.min = min;
+        self.max = max;
+
+        // only swap when both are set, `None` compares less than any value
+        if self.min.zip(self.max).is_some_and(|(min, max)| min > max) {
+            log::info!(
+                "{} > {}, swapping for your convenience.",
+                self.titles[0].trim_end_matches(':'),
+                self.titles[1].trim_end_matches(':')
+            );
+            std::mem::swap(&mut self.min, &mut self.max);
+        }
+
+        debug!("{} {:?}", self.titles[0], self.min);
+        debug!("{} {:?}", self.titles[1], self.max);
     }
 
     fn input(&mut self, input: KeyEvent) -> bool {
         self.textareas[self.focus].input(input)
     }
 
-    fn get_text(&mut self) -> [String; 2] {
-        self.textareas[0].select_all();
-        self.textareas[0].cut();
-        self.textareas[1].select_all();
-        self.textareas[1].cut();
-        let out = [self.textareas[0].yank_text(), self.textareas[1].yank_text()];
-        self.textareas.iter_mut().for_each(|textarea| {
+    fn get_text(&mut self) -> [String; N] {
+        self.textareas.each_mut().map(|textarea| {
+            textarea.select_all();
+            textarea.cut();
+            let text = textarea.yank_text();
             textarea.set_yank_text("");
-        });
-        out
+            text
+        })
     }
 
     fn clear(&mut self) {
         let _ = self.get_text();
     }
 
-    fn update_vals(&mut self, plot_log: bool) {
-        let [min_line, max_line] = self.get_text();
-        let text = min_line.trim().to_lowercase();
-
-        if text == "auto" || text.is_empty() {
-            self.min = None;
-        } else {
-            self.min = Some({
-                let val = text
-                    .parse::<f64>()
-                    .expect("Valid YMin text changed before parsing");
-                // always store limits in absolute units
-                // so convert back if we're plotting in log
-                match plot_log {
-                    true => 10.0_f64.powf(val / 10.0),
-                    false => val,
-                }
-            })
-        }
-
-        let text = max_line.trim().to_lowercase();
-
-        if text.to_lowercase() == "auto" || text.is_empty() {
-            self.max = None;
-        } else {
-            self.max = Some({
-                let val = text
-                    .parse::<f64>()
-                    .expect("Valid Ymax text changed before parsing");
-                // always store limits in absolute units
-                // so convert back if we're plotting in log
-                match plot_log {
-                    true => 10.0_f64.powf(val / 10.0),
-                    false => val,
-                }
-            })
-        }
-        if self.min > self.max {
-            log::info!("Ymin > Ymax, swapping for your convenience.");
-            std::mem::swap(&mut self.min, &mut self.max);
-        }
-
-        debug!("min: {:?}", self.min);
-        debug!("max: {:?}", self.max);
-    }
-
     fn inactivate(&mut self) {
         let textarea = &mut self.textareas[self.focus];
 
@@ -236,195 +381,723 @@ impl<'a> Ylims<'a> {
             .iter_mut()
             .enumerate()
             .all(|(cnt, textarea)| {
-                let name = if cnt == 0 { "Min:" } else { "Max:" };
                 let line = textarea.lines()[0].trim().to_lowercase();
-                if line == "auto" || line.is_empty() {
-                    textarea.set_style(Style::default().fg(if self.focus == cnt {
-                        Color::LightGreen
-                    } else {
-                        Color::DarkGray
-                    }));
-                    textarea.set_block(
-                        Block::default()
-                            .border_style(if self.focus == cnt {
-                                Color::LightGreen
-                            } else {
-                                Color::DarkGray
-                            })
-                            .borders(Borders::ALL)
-                            .title(format!("{} Auto", name)),
-                    );
-                    true
-                } else if line.parse::<f64>().is_err() {
-                    textarea.set_style(Style::default().fg(if self.focus == cnt {
-                        Color::LightRed
-                    } else {
-                        Color::DarkGray
-                    }));
-                    textarea.set_block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_style(if self.focus == cnt {
-                                Color::LightRed
-                            } else {
-                                Color::DarkGray
-                            })
-                            .title(format!("{} Invalid", name,)),
-                    );
-                    false
-                } else {
-                    textarea.set_style(Style::default().fg(if self.focus == cnt {
-                        Color::LightGreen
+                let theme = self.theme;
+                let (color, unfocused, status, valid) =
+                    if line == Self::unset_text(cnt) || line.is_empty() {
+                        (theme.good(), theme.dim(), "Auto", true)
+                    } else if line
+                        .parse::<f64>()
+                        // a span is either side of the center
+                        .map_or(true, |val| cnt >= 2 && val <= 0.0)
+                    {
+                        (theme.error(), theme.dim(), "Invalid", false)
                     } else {
-                        Color::Green
-                    }));
-                    textarea.set_block(
-                        Block::default()
-                            .border_style(if self.focus == cnt {
-                                Color::LightGreen
-                            } else {
-                                Color::Green
-                            })
-                            .borders(Borders::ALL)
-                            .title(format!("{} Ok", name)),
-                    );
-                    true
-                }
+                        (theme.good(), theme.good(), "Ok", true)
+                    };
+                let status = match (status, cnt) {
+                    ("Auto", 2..) => "Off",
+                    (status, _) => status,
+                };
+                let color = if self.focus == cnt { color } else { unfocused };
+
+                textarea.set_style(Style::default().fg(color));
+                textarea.set_block(
+                    Block::default()
+                        .border_style(color)
+                        .borders(Borders::ALL)
+                        .title(format!("{} {}", self.titles[cnt], status)),
+                );
+                valid
             });
     }
 
     fn change_focus(&mut self) {
         self.inactivate();
-        self.focus = (self.focus + 1) % 2;
+        self.focus = (self.focus + 1) % N;
         self.activate();
         self.validate();
     }
 
     fn reset_blocks(&mut self) {
         // reset the focus/curson on each
-        self.focus = 1;
-        self.inactivate();
+        for focus in 1..N {
+            self.focus = focus;
+            self.inactivate();
+        }
         self.focus = 0;
         self.activate();
 
+        let theme = self.theme;
         self.textareas
             .iter_mut()
-            .enumerate()
-            .for_each(|(cnt, text)| {
+            .zip(self.titles)
+            .for_each(|(text, title)| {
+                text.set_style(Style::default());
                 text.set_block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .style(Style::default().fg(Color::DarkGray))
-                        .title(if cnt == 0 { "Ymin:" } else { "Ymax:" }),
+                        .style(Style::default().fg(theme.dim()))
+                        .title(title),
                 );
             });
     }
 }
 
-#[derive(Debug)]
-pub(crate) struct App<'a> {
-    #[cfg(feature = "ovro")]
-    /// Used to store/update which antennas are currently being plotted
-    antenna_filter: AntennaFilter,
-
-    /// Spectra to be plotted on the next draw
-    ///
-    spectra: Option<AutoSpectra>,
-    /// The ambient refresh tick if nothing happens
-    refresh_rate: Duration,
+impl Limits<'_, 3> {
+    fn new() -> Self {
+        // the span centers the limits on the data instead of using min and max
+        Self::with_titles(["Ymin:", "Ymax:", "Span ±:"])
+    }
 
-    /// Determines backend and how to load data
-    data_backend: TuiType,
+    pub(crate) fn get_max(&self, plot_log: bool) -> Option<f64> {
+        self.max.map(|val| match plot_log {
+            true => {
+                let tmp = 10.0 * val.log10();
+                match tmp.is_finite() {
+                    true => tmp,
+                    false => f64::INFINITY,
+                }
+            }
+            false => val,
+        })
+    }
 
-    #[allow(dead_code)]
-    // we use this channel to indicate to the loader when to
-    // halt even if there is no filtering functionality
-    /// Channel used to send new filters to the backend
-    filter_sender: Sender<Vec<String>>,
+    pub(crate) fn get_min(&self, plot_log: bool) -> Option<f64> {
+        self.min.map(|val| match plot_log {
+            true => {
+                let tmp = 10.0 * val.log10();
+                match tmp.is_finite() {
+                    true => tmp,
+                    false => f64::NEG_INFINITY,
+                }
+            }
+            false => val,
+        })
+    }
 
-    /// Filter receving channel to give to the SpectrumLoader backend
-    filter_recv: Option<Receiver<Vec<String>>>,
+    /// Sets both limits from values in plotted units.
+    fn set_plotted(&mut self, min: f64, max: f64, plot_log: bool) {
+        self.set_typed(Some(min), Some(max), plot_log);
+    }
 
-    #[cfg(feature = "ovro")]
-    /// Current value of the input box
-    input: String,
+    /// Sets the limits from the text entered, a span centers them on `center`
+    /// instead of using the min and max.
+    fn update_vals(&mut self, plot_log: bool, center: Option<f64>) {
+        let [min_line, max_line, span_line] = self.get_text();
+        let min = self.parse(0, &min_line);
+        let max = self.parse(1, &max_line);
+
+        match (self.parse(2, &span_line), center) {
+            (Some(span), Some(center)) => {
+                info!("Y-limits centered on {center:.3} ± {span}.");
+                self.set_typed(Some(center - span), Some(center + span), plot_log);
+            }
+            (Some(_), None) => info!("No data in view to center the Y-limits on."),
+            (None, _) => self.set_typed(min, max, plot_log),
+        }
+    }
 
-    #[cfg(feature = "ovro")]
-    /// Position of cursor in the editor area.
-    character_index: usize,
-    /// Tracks if we're adding to the Antenna filter or not
-    input_mode: InputMode,
+    /// Whether a span was entered, centering the limits on the data.
+    fn has_span(&self) -> bool {
+        let line = self.textareas[2].lines()[0].trim().to_lowercase();
+        !(line.is_empty() || line == "off")
+    }
 
-    log_plot: Option<bool>,
+    /// Sets the limits as if typed into the popup, in plotted units with
+    /// `None` for auto.
+    fn set_typed(&mut self, min: Option<f64>, max: Option<f64>, plot_log: bool) {
+        // always store limits in absolute units
+        // so convert back if we're plotting in log
+        let to_absolute = |val: f64| match plot_log {
+            true => 10.0_f64.powf(val / 10.0),
+            false => val,
+        };
+        self.set(min.map(to_absolute), max.map(to_absolute));
+    }
+}
 
-    #[cfg(feature = "lwa-na")]
-    /// some saturation statistics to print
-    saturations: Option<SaturationStats>,
+impl Limits<'_, 2> {
+    fn new() -> Self {
+        Self::with_titles(["Xmin:", "Xmax:"])
+    }
 
-    #[cfg(feature = "lwa-na")]
-    show_stats: bool,
+    pub(crate) fn get_max(&self) -> Option<f64> {
+        self.max
+    }
 
-    ylims: Ylims<'a>,
-}
-#[cfg(feature = "ovro")]
-impl<'a> App<'a> {
-    // BEGIN: function pulled from the ratatui user input example
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.input.chars().count())
+    pub(crate) fn get_min(&self) -> Option<f64> {
+        self.min
     }
 
-    fn move_cursor_left(&mut self) {
-        let cursor_moved_left = self.character_index.saturating_sub(1);
-        self.character_index = self.clamp_cursor(cursor_moved_left);
+    fn update_vals(&mut self) {
+        let [min_line, max_line] = self.get_text();
+
+        // frequencies are plotted linearly so no unit conversion is needed here
+        let min = self.parse(0, &min_line);
+        let max = self.parse(1, &max_line);
+        self.set(min, max);
     }
+}
 
-    fn move_cursor_right(&mut self) {
-        let cursor_moved_right = self.character_index.saturating_add(1);
-        self.character_index = self.clamp_cursor(cursor_moved_right);
+/// The level that log plots are shown relative to, with the popup to set it.
+#[derive(Debug, Clone)]
+pub(crate) struct RefLevel<'a> {
+    /// Reference level in raw units, `None` plots dB relative to 1.0
+    level: Option<f64>,
+
+    textarea: TextArea<'a>,
+    is_valid: bool,
+    theme: Theme,
+}
+impl<'a> RefLevel<'a> {
+    fn new() -> Self {
+        let mut textarea = TextArea::default();
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_placeholder_text("none");
+
+        let mut me = Self {
+            level: None,
+            textarea,
+            is_valid: true,
+            theme: Theme::default(),
+        };
+        me.reset_block();
+        me
     }
 
-    /// Returns the byte index based on the character position.
-    ///
-    /// Since each character in a string can be contain multiple bytes, it's necessary to calculate
-    /// the byte index based on the index of the character.
-    fn byte_index(&self) -> usize {
-        self.input
-            .char_indices()
-            .map(|(i, _)| i)
-            .nth(self.character_index)
-            .unwrap_or(self.input.len())
+    /// Parses a level, the outer `None` means the text is not a valid level.
+    fn parse(line: &str) -> Option<Option<f64>> {
+        let line = line.trim().to_lowercase();
+        if line == "none" || line.is_empty() {
+            return Some(None);
+        }
+        line.parse::<f64>()
+            .ok()
+            .filter(|level| level.is_finite() && *level > 0.0)
+            .map(Some)
     }
 
-    fn enter_char(&mut self, new_char: char) {
-        let index = self.byte_index();
-        self.input.insert(index, new_char);
-        self.move_cursor_right();
+    fn input(&mut self, input: KeyEvent) -> bool {
+        self.textarea.input(input)
     }
 
-    fn delete_char(&mut self) {
-        let is_not_cursor_leftmost = self.character_index != 0;
-        if is_not_cursor_leftmost {
-            // Method "remove" is not used on the saved text for deleting the selected char.
-            // Reason: Using remove on String works on bytes instead of the chars.
-            // Using remove would require special care because of char boundaries.
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.reset_block();
+    }
 
-            let current_index = self.character_index;
-            let from_left_to_current_index = current_index - 1;
+    fn validate(&mut self) {
+        let (valid, status) = match Self::parse(&self.textarea.lines()[0]) {
+            Some(None) => (true, "None"),
+            Some(Some(_)) => (true, "Ok"),
+            None => (false, "Invalid"),
+        };
+        self.is_valid = valid;
+        let color = match valid {
+            true => self.theme.good(),
+            false => self.theme.error(),
+        };
 
-            // Getting all characters before the selected character.
-            let before_char_to_delete = self.input.chars().take(from_left_to_current_index);
-            // Getting all characters after selected character.
-            let after_char_to_delete = self.input.chars().skip(current_index);
+        self.textarea.set_style(Style::default().fg(color));
+        self.textarea.set_block(
+            Block::default()
+                .border_style(color)
+                .borders(Borders::ALL)
+                .title(format!("Reference Level: {status}")),
+        );
+    }
 
-            // Put all characters together except the selected one.
-            // By leaving the selected one out, it is forgotten and therefore deleted.
-            self.input = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
+    /// Stores the typed level and empties the text box.
+    fn update_val(&mut self) {
+        if let Some(level) = Self::parse(&self.textarea.lines()[0]) {
+            self.level = level;
         }
+        self.clear();
     }
 
-    fn reset_cursor(&mut self) {
-        self.character_index = 0;
+    fn clear(&mut self) {
+        self.textarea.select_all();
+        self.textarea.cut();
+        self.textarea.set_yank_text("");
+        self.is_valid = true;
+        self.reset_block();
+    }
+
+    fn reset_block(&mut self) {
+        self.textarea.set_style(Style::default());
+        self.textarea.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(self.theme.dim()))
+                .title("Reference Level (raw units):"),
+        );
+    }
+}
+
+/// Popup to add a labeled marker at a frequency.
+#[derive(Debug, Clone)]
+pub(crate) struct MarkerInput<'a> {
+    textarea: TextArea<'a>,
+    is_valid: bool,
+    theme: Theme,
+}
+impl MarkerInput<'_> {
+    fn new() -> Self {
+        let mut textarea = TextArea::default();
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_placeholder_text("137.5 NOAA");
+
+        let mut me = Self {
+            textarea,
+            is_valid: false,
+            theme: Theme::default(),
+        };
+        me.reset_block();
+        me
+    }
+
+    /// Parses a frequency followed by an optional label, the outer `None`
+    /// means the text is not valid and the inner `None` clears the markers.
+    fn parse(line: &str) -> Option<Option<(f64, String)>> {
+        let line = line.trim();
+        if line.eq_ignore_ascii_case("clear") {
+            return Some(None);
+        }
+        let (freq, label) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        freq.parse::<f64>()
+            .ok()
+            .filter(|freq| freq.is_finite())
+            .map(|freq| Some((freq, label.trim().to_owned())))
+    }
+
+    fn input(&mut self, input: KeyEvent) -> bool {
+        self.textarea.input(input)
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.reset_block();
+    }
+
+    fn validate(&mut self) {
+        let (valid, status) = match Self::parse(&self.textarea.lines()[0]) {
+            Some(None) => (true, "Clear"),
+            Some(Some(_)) => (true, "Ok"),
+            None => (false, "Invalid"),
+        };
+        self.is_valid = valid;
+        let color = match valid {
+            true => self.theme.good(),
+            false => self.theme.error(),
+        };
+
+        self.textarea.set_style(Style::default().fg(color));
+        self.textarea.set_block(
+            Block::default()
+                .border_style(color)
+                .borders(Borders::ALL)
+                .title(format!("Marker: {status}")),
+        );
+    }
+
+    /// Returns the typed marker and empties the text box.
+    fn take(&mut self) -> Option<Option<(f64, String)>> {
+        let marker = Self::parse(&self.textarea.lines()[0]);
+        self.clear();
+        marker
+    }
+
+    fn clear(&mut self) {
+        self.textarea.select_all();
+        self.textarea.cut();
+        self.textarea.set_yank_text("");
+        self.is_valid = false;
+        self.reset_block();
+    }
+
+    fn reset_block(&mut self) {
+        self.textarea.set_style(Style::default());
+        self.textarea.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(self.theme.dim()))
+                .title("Marker (freq [label]):"),
+        );
+    }
+}
+
+/// Lazily opened system clipboard, headless sessions have none.
+#[derive(Default)]
+struct Clipboard(Option<arboard::Clipboard>);
+impl Clipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), arboard::Error> {
+        let clipboard = match self.0.as_mut() {
+            Some(clipboard) => clipboard,
+            None => self.0.insert(arboard::Clipboard::new()?),
+        };
+        clipboard.set_text(text)
+    }
+}
+impl std::fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Clipboard")
+            .field(&self.0.as_ref().map(|_| "open"))
+            .finish()
+    }
+}
+
+/// Counts a running export, releasing it on drop so a panicking task
+/// cannot leave the quit prompt waiting forever.
+struct PendingGuard(Arc<AtomicUsize>);
+impl PendingGuard {
+    fn new(pending: Arc<AtomicUsize>) -> Self {
+        pending.fetch_add(1, Ordering::SeqCst);
+        Self(pending)
+    }
+}
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct App<'a> {
+    #[cfg(feature = "ovro")]
+    /// Used to store/update which antennas are currently being plotted
+    antenna_filter: AntennaFilter,
+
+    /// Spectra to be plotted on the next draw
+    ///
+    spectra: Option<AutoSpectra>,
+
+    /// The latest (averaged) spectra before any display processing
+    latest: Option<AutoSpectra>,
+    /// The ambient refresh tick if nothing happens
+    refresh_rate: Duration,
+
+    /// Determines backend and how to load data
+    data_backend: TuiType,
+
+    #[allow(dead_code)]
+    // we use this channel to indicate to the loader when to
+    // halt even if there is no filtering functionality
+    /// Channel used to send new filters to the backend
+    filter_sender: Sender<Vec<String>>,
+
+    /// Filter receving channel to give to the SpectrumLoader backend
+    filter_recv: Option<Receiver<Vec<String>>>,
+
+    #[allow(dead_code)]
+    /// Channel used to send commands to the backend
+    command_sender: Sender<BackendCommand>,
+
+    /// Command receiving channel to give to the SpectrumLoader backend
+    command_recv: Option<Receiver<BackendCommand>>,
+
+    #[cfg(feature = "ovro")]
+    /// Antenna names in the correlator configuration, empty until the backend knows them
+    known_antennas: watch::Receiver<Vec<String>>,
+
+    #[cfg(feature = "ovro")]
+    /// Sender for the known antennas to give to the SpectrumLoader backend
+    known_sender: Option<watch::Sender<Vec<String>>>,
+
+    #[cfg(feature = "ovro")]
+    /// Named antenna filters from the config file
+    filter_presets: BTreeMap<String, Vec<String>>,
+
+    #[cfg(feature = "ovro")]
+    /// Name of the last preset applied or saved
+    current_preset: Option<String>,
+
+    #[cfg(feature = "ovro")]
+    /// Current value of the input box
+    input: String,
+
+    #[cfg(feature = "ovro")]
+    /// Position of cursor in the editor area.
+    character_index: usize,
+    /// Tracks if we're adding to the Antenna filter or not
+    input_mode: InputMode,
+
+    /// Data transform, plot the spectra in dB instead of raw units
+    log_plot: Option<bool>,
+
+    /// Axis scaling, space the y-axis logarithmically while keeping raw units
+    ///
+    /// The chart can only draw linear axes so this plots the dB values with
+    /// the ticks labelled in raw units. It has no effect when plotting in dB.
+    log_axis: bool,
+
+    /// Stacks a raw and a dB chart of the same spectra
+    split_scales: bool,
+
+    /// Y-axis limits from the command line, in plotted units, waiting for the
+    /// first spectra to say whether they are in dB
+    launch_ylims: Option<(Option<f64>, Option<f64>)>,
+
+    #[cfg(feature = "lwa-na")]
+    /// some saturation statistics to print
+    saturations: Option<SaturationStats>,
+
+    /// Where the saturation of every poll is appended, if anywhere
+    #[cfg(feature = "lwa-na")]
+    saturation_log: Option<SaturationLog>,
+
+    #[cfg(feature = "lwa-na")]
+    show_stats: bool,
+
+    #[cfg(feature = "lwa-na")]
+    /// Show the recorder fill fractions and error bits below the chart
+    show_quality: bool,
+
+    /// Show min/max/mean/std of the visible traces below the chart
+    show_stats_panel: bool,
+
+    /// Most verbose level kept by the logger
+    log_level: LevelFilter,
+
+    ylims: Ylims<'a>,
+
+    /// Set when the Y-limits were fit to the data, fitting again returns to auto
+    ylims_fitted: bool,
+
+    /// Set while the Y-limits hold a snapshot of the auto scale, locking again returns to auto
+    ylims_locked: bool,
+
+    /// Level the dB values are plotted relative to
+    ref_level: RefLevel<'a>,
+
+    /// Colors used for the traces
+    palette: Palette,
+
+    /// Colors of everything but the traces
+    theme: Theme,
+
+    /// Symbol the traces are drawn with
+    trace_marker: TraceMarker,
+
+    /// Draw the traces as points instead of lines
+    scatter: bool,
+
+    /// Thin the plotted traces to the width of the chart
+    fit_width: bool,
+
+    /// Show the antenna colors in a panel next to the chart
+    show_legend: bool,
+
+    xlims: Xlims<'a>,
+
+    /// The most recent spectra and their arrival times, oldest first, used to
+    /// draw the waterfall
+    history: VecDeque<(Instant, AutoSpectra)>,
+
+    /// Maximum number of spectra kept in the history
+    history_depth: usize,
+
+    show_waterfall: bool,
+
+    /// Number of rows the help popup is scrolled down
+    help_scroll: usize,
+
+    /// Where the chart was last drawn, to turn mouse positions into data
+    chart_view: ChartView,
+
+    /// Start and current cell of a region being selected with the mouse
+    selection: Option<((u16, u16), (u16, u16))>,
+
+    /// Last cell of a pan in progress
+    pan_from: Option<(u16, u16)>,
+
+    /// Number of exports still writing in the background
+    pending_tasks: Arc<AtomicUsize>,
+
+    /// The backend task, aborted on exit so its connections close promptly
+    backend_task: Option<BackendTask>,
+
+    /// Ticks since startup, animates the spinner while waiting for data
+    ticks: usize,
+
+    /// Number of spectra to average together before plotting
+    averaging_window: usize,
+
+    /// The raw spectra that make up the current running mean
+    average_buffer: VecDeque<AutoSpectra>,
+
+    /// Shows a ±1σ band around the running average
+    show_spread: bool,
+
+    /// Standard deviation of each channel over the running average
+    spread: Option<Vec<Vec<f64>>>,
+
+    /// The plotted spectra one standard deviation below and above
+    spread_band: Option<(AutoSpectra, AutoSpectra)>,
+
+    /// Width in channels of the running mean applied along frequency
+    smoothing_width: usize,
+
+    /// Stops new spectra from replacing the plotted spectra
+    paused: bool,
+
+    /// Frequency of the crosshair marker, only set in crosshair mode
+    crosshair: Option<f64>,
+
+    /// Opened on the first copy and kept open, on X11 the copied text
+    /// is only served while the clipboard is alive
+    clipboard: Clipboard,
+
+    /// Antennas still loaded but left off the chart
+    hidden: HashSet<String>,
+
+    /// Last reported status of each live source
+    backend_status: Vec<StatusUpdate>,
+
+    /// Antennas in the latest spectra that returned no valid samples
+    no_data: HashSet<String>,
+
+    /// Index into the antenna names of the trace drawn in color while the
+    /// others are dimmed, `None` colors them all
+    focused: Option<usize>,
+
+    /// Where received spectra are sent to be recorded, if recording
+    recorder: Option<Sender<AutoSpectra>>,
+
+    /// Delay between polls of live data, `None` when reading a file
+    poll_delay: Option<Duration>,
+
+    /// When spectra last arrived, or when the app started
+    last_data: Instant,
+
+    /// Live data has not arrived for too long and a warning was logged
+    stale: bool,
+
+    /// Frequency ranges shaded on the chart as known RFI
+    rfi_bands: Vec<(f64, f64)>,
+
+    /// Labeled frequencies marked on the chart
+    markers: Vec<(f64, String)>,
+    marker_input: MarkerInput<'a>,
+
+    /// Saturated fraction above which a trace is flagged on the chart
+    saturation_threshold: Option<f64>,
+
+    median_mode: MedianMode,
+
+    show_max_hold: bool,
+
+    /// Highest raw value seen in each channel since the last reset
+    max_hold: Option<AutoSpectra>,
+
+    /// The max hold with the same display processing as the plotted spectra
+    max_hold_view: Option<AutoSpectra>,
+
+    /// Spectrum captured as a baseline, e.g. of a quiet sky
+    reference: Option<AutoSpectra>,
+
+    reference_mode: ReferenceMode,
+
+    /// Plot dB relative to each antenna's noise floor instead of the reference level
+    floor_relative: bool,
+
+    /// Noise floor of each antenna in the current view, while plotting relative to it
+    noise_floors: Option<Vec<(String, f64)>>,
+
+    /// Median across antennas of the current spectra, kept up to date while shown
+    median: Option<AutoSpectra>,
+
+    /// Flag channels far above each trace's median
+    show_outliers: bool,
+
+    /// Number of robust standard deviations above the median a channel is flagged at
+    outlier_sigma: f64,
+
+    /// Flagged channels of the current spectra, kept up to date while shown
+    outliers: Option<AutoSpectra>,
+
+    /// Color channels by their spectral kurtosis over the recent polls
+    show_kurtosis: bool,
+
+    /// Channels with high (impulsive) and low (steady) kurtosis, with their colors
+    kurtosis_flags: Vec<(AutoSpectra, Color)>,
+
+    /// Selection in the antenna visibility list
+    visibility_state: ListState,
+
+    /// Pair of antennas plotted as a single comparison trace
+    compare: Option<(String, String)>,
+
+    /// First antenna picked for a comparison and the selection in the list
+    compare_first: Option<String>,
+    compare_state: ListState,
+}
+#[cfg(feature = "ovro")]
+impl<'a> App<'a> {
+    // BEGIN: function pulled from the ratatui user input example
+    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
+        new_cursor_pos.clamp(0, self.input.chars().count())
+    }
+
+    fn move_cursor_left(&mut self) {
+        let cursor_moved_left = self.character_index.saturating_sub(1);
+        self.character_index = self.clamp_cursor(cursor_moved_left);
+    }
+
+    fn move_cursor_right(&mut self) {
+        let cursor_moved_right = self.character_index.saturating_add(1);
+        self.character_index = self.clamp_cursor(cursor_moved_right);
+    }
+
+    /// Returns the byte index based on the character position.
+    ///
+    /// Since each character in a string can be contain multiple bytes, it's necessary to calculate
+    /// the byte index based on the index of the character.
+    fn byte_index(&self) -> usize {
+        self.input
+            .char_indices()
+            .map(|(i, _)| i)
+            .nth(self.character_index)
+            .unwrap_or(self.input.len())
+    }
+
+    fn enter_char(&mut self, new_char: char) {
+        let index = self.byte_index();
+        self.input.insert(index, new_char);
+        self.move_cursor_right();
+    }
+
+    fn delete_char(&mut self) {
+        let is_not_cursor_leftmost = self.character_index != 0;
+        if is_not_cursor_leftmost {
+            // Method "remove" is not used on the saved text for deleting the selected char.
+            // Reason: Using remove on String works on bytes instead of the chars.
+            // Using remove would require special care because of char boundaries.
+
+            let current_index = self.character_index;
+            let from_left_to_current_index = current_index - 1;
+
+            // Getting all characters before the selected character.
+            let before_char_to_delete = self.input.chars().take(from_left_to_current_index);
+            // Getting all characters after selected character.
+            let after_char_to_delete = self.input.chars().skip(current_index);
+
+            // Put all characters together except the selected one.
+            // By leaving the selected one out, it is forgotten and therefore deleted.
+            self.input = before_char_to_delete.chain(after_char_to_delete).collect();
+            self.move_cursor_left();
+        }
+    }
+
+    fn reset_cursor(&mut self) {
+        self.character_index = 0;
+    }
+
+    /// Checks the names, ranges or comma separated list against the correlator
+    /// configuration, anything goes until the configuration is loaded.
+    fn is_known_antenna(&self, name: &str) -> bool {
+        let known = self.known_antennas.borrow();
+        known.is_empty() || matches_known_antennas(name, &known)
     }
 
     // Submit the antenna to the backend but also reset to plotter mode
@@ -434,255 +1107,1805 @@ impl<'a> App<'a> {
             info!("Invalid antenna name...Skipping");
             return Ok(());
         }
-        info!("Adding Antenna {new_ant:?}");
-        self.antenna_filter.items.push(new_ant);
+        if !self.is_known_antenna(&new_ant) {
+            log::warn!("Antenna {new_ant:?} is not in the correlator configuration.");
+            return Ok(());
+        }
+        info!("Adding Antenna {new_ant:?}");
+        self.antenna_filter.items.push(new_ant);
+        self.average_buffer.clear();
+
+        self.filter_sender
+            .send(self.antenna_filter.items.clone())
+            .await?;
+
+        self.input.clear();
+        self.reset_cursor();
+        self.input_mode = InputMode::Normal;
+
+        Ok(())
+    }
+
+    // END ratatui example functions
+
+    // BEGIN functions pulled from list examples edited for need
+    fn select_next(&mut self) {
+        self.antenna_filter.state.select_next();
+    }
+
+    fn select_previous(&mut self) {
+        self.antenna_filter.state.select_previous();
+    }
+
+    async fn remove_antenna(&mut self) -> Result<()> {
+        // the selection is only clamped to the list when it is drawn
+        let last = self.antenna_filter.items.len().checked_sub(1);
+        if let Some(i) = self
+            .antenna_filter
+            .state
+            .selected()
+            .zip(last)
+            .map(|(i, last)| i.min(last))
+        {
+            let removed = self.antenna_filter.items.remove(i);
+            info!("Removing: {removed}");
+            self.average_buffer.clear();
+            self.filter_sender
+                .send(self.antenna_filter.items.clone())
+                .await?;
+        }
+
+        // reset the list state and the input mode
+        self.input_mode = InputMode::Normal;
+        self.antenna_filter.state = ListState::default();
+
+        Ok(())
+    }
+
+    /// Saves the current filter to the config file under the typed name.
+    fn save_filter_preset(&mut self) {
+        let name = self.input.trim().to_owned();
+        if name.is_empty() {
+            info!("Invalid preset name...Skipping");
+            return;
+        }
+        match Config::save_filter_preset(&name, &self.antenna_filter.items) {
+            Ok(path) => {
+                info!("Saved the antenna filter as {name:?} in {}", path.display());
+                self.filter_presets
+                    .insert(name.clone(), self.antenna_filter.items.clone());
+                self.current_preset = Some(name);
+            }
+            Err(err) => log::warn!("Unable to save the filter preset: {err:#}"),
+        }
+
+        self.input.clear();
+        self.reset_cursor();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Replaces the filter with the preset after the current one.
+    async fn next_filter_preset(&mut self) -> Result<()> {
+        // presets are kept sorted by name, wrap around after the last
+        let next = self
+            .current_preset
+            .as_ref()
+            .and_then(|current| {
+                self.filter_presets
+                    .range::<String, _>((Bound::Excluded(current), Bound::Unbounded))
+                    .next()
+            })
+            .or_else(|| self.filter_presets.iter().next());
+        let Some((name, antennas)) = next else {
+            info!("No filter presets in the config file, press U to save one.");
+            return Ok(());
+        };
+        info!("Filtering to preset {name:?}: {}", antennas.join(", "));
+
+        self.antenna_filter.items = antennas.clone();
+        self.antenna_filter.state = ListState::default();
+        self.current_preset = Some(name.clone());
+        self.average_buffer.clear();
+        self.filter_sender
+            .send(self.antenna_filter.items.clone())
+            .await?;
+
+        Ok(())
+    }
+    // END list examples
+}
+
+/// Input, data, status and tick events, keyed by their source
+type EventStreams = StreamMap<&'static str, Pin<Box<dyn Stream<Item = StreamReturn> + Send>>>;
+
+/// The task loading spectra in the background
+type BackendTask = JoinHandle<Result<()>>;
+#[cfg(feature = "lwa-na")]
+type BackendReturn = Result<(
+    Receiver<(AutoSpectra, Option<SaturationStats>)>,
+    Receiver<StatusUpdate>,
+    BackendTask,
+)>;
+#[cfg(not(feature = "lwa-na"))]
+type BackendReturn = Result<(Receiver<AutoSpectra>, Receiver<StatusUpdate>, BackendTask)>;
+impl<'a> App<'a> {
+    pub fn new(
+        refresh_rate: Duration,
+        data_backend: TuiType,
+        history_depth: usize,
+        // `None` uses the saved default
+        palette: Option<Palette>,
+        log_level: LevelFilter,
+        // `false` starts from the default view, ignoring the saved one
+        saved_view: bool,
+    ) -> Self {
+        let (filter_sender, filter_recv) = tokio::sync::mpsc::channel(10);
+        let (command_sender, command_recv) = tokio::sync::mpsc::channel(10);
+        #[cfg(feature = "ovro")]
+        let (known_sender, known_antennas) = tokio::sync::watch::channel(vec![]);
+
+        let config = Config::load();
+        // the saved defaults are the base, the view at the last exit goes on top
+        let view = match saved_view {
+            false => ViewConfig::default(),
+            true => {
+                let view = config.view.unwrap_or_default();
+                let saved = SavedState::load();
+                ViewConfig {
+                    log_plot: saved.log_plot.or(view.log_plot),
+                    ymin: saved.ymin.or(view.ymin),
+                    ymax: saved.ymax.or(view.ymax),
+                    ..view
+                }
+            }
+        };
+        let mut ylims = Ylims::new();
+        ylims.min = view.ymin;
+        ylims.max = view.ymax;
+
+        let poll_delay = match &data_backend {
+            #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+            TuiType::Live { delay, .. } => Some(Duration::from_secs_f64(*delay)),
+            _ => None,
+        };
+
+        cfg_if::cfg_if! {
+            if #[cfg(feature="lwa-na")]{
+                let saturation_threshold = match &data_backend {
+                    TuiType::File { saturation_threshold, .. }
+                    | TuiType::Live { saturation_threshold, .. } => Some(*saturation_threshold),
+                    TuiType::Replay { .. } => None,
+                };
+            } else {
+                let saturation_threshold = None;
+            }
+        }
+
+        #[cfg(feature = "ovro")]
+        let antenna_filter = match &data_backend {
+            // without a count the antennas are filled in once the file is read
+            TuiType::File { nspectra, .. } => (0..nspectra.unwrap_or_default())
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>(),
+            TuiType::Live { antenna, .. } => antenna.clone(),
+            TuiType::Replay { .. } => vec![],
+        };
+
+        Self {
+            #[cfg(feature = "ovro")]
+            antenna_filter: AntennaFilter {
+                items: antenna_filter,
+                state: ListState::default(),
+                page: 1,
+            },
+            #[cfg(feature = "ovro")]
+            filter_presets: config.filter_presets,
+            #[cfg(feature = "ovro")]
+            current_preset: None,
+            spectra: None,
+            latest: None,
+            refresh_rate,
+            data_backend,
+            input_mode: InputMode::Normal,
+            filter_sender,
+            filter_recv: Some(filter_recv),
+            command_sender,
+            command_recv: Some(command_recv),
+            #[cfg(feature = "ovro")]
+            known_antennas,
+            #[cfg(feature = "ovro")]
+            known_sender: Some(known_sender),
+            #[cfg(feature = "ovro")]
+            input: String::new(),
+            #[cfg(feature = "ovro")]
+            character_index: 0,
+            log_plot: view.log_plot,
+            log_axis: view.log_axis,
+            split_scales: false,
+            launch_ylims: None,
+            #[cfg(feature = "lwa-na")]
+            saturations: None,
+            #[cfg(feature = "lwa-na")]
+            saturation_log: None,
+            #[cfg(feature = "lwa-na")]
+            show_stats: false,
+            #[cfg(feature = "lwa-na")]
+            show_quality: false,
+            show_stats_panel: false,
+            log_level,
+            ylims,
+            ylims_fitted: false,
+            ylims_locked: false,
+            ref_level: RefLevel::new(),
+            palette: palette.or(view.palette).unwrap_or_default(),
+            theme: Theme::default(),
+            trace_marker: TraceMarker::default(),
+            scatter: false,
+            fit_width: false,
+            show_legend: false,
+            xlims: Xlims::new(),
+            history: VecDeque::with_capacity(history_depth),
+            history_depth,
+            show_waterfall: false,
+            help_scroll: 0,
+            chart_view: ChartView::default(),
+            selection: None,
+            pan_from: None,
+            pending_tasks: Arc::new(AtomicUsize::new(0)),
+            backend_task: None,
+            ticks: 0,
+            averaging_window: 1,
+            average_buffer: VecDeque::new(),
+            show_spread: false,
+            spread: None,
+            spread_band: None,
+            smoothing_width: view.smoothing_width.unwrap_or(1).max(1),
+            paused: false,
+            crosshair: None,
+            clipboard: Clipboard::default(),
+            hidden: HashSet::new(),
+            backend_status: Vec::new(),
+            no_data: HashSet::new(),
+            focused: None,
+            recorder: None,
+            poll_delay,
+            last_data: Instant::now(),
+            stale: false,
+            rfi_bands: config.rfi_bands,
+            markers: vec![],
+            marker_input: MarkerInput::new(),
+            saturation_threshold,
+            median_mode: MedianMode::default(),
+            show_max_hold: false,
+            floor_relative: false,
+            noise_floors: None,
+            max_hold: None,
+            max_hold_view: None,
+            reference: None,
+            reference_mode: ReferenceMode::default(),
+            median: None,
+            show_outliers: false,
+            outlier_sigma: 5.0,
+            outliers: None,
+            show_kurtosis: false,
+            kurtosis_flags: Vec::new(),
+            visibility_state: ListState::default(),
+            compare: None,
+            compare_first: None,
+            compare_state: ListState::default(),
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let size = frame.area();
+        if size.width < ui::MIN_SIZE.0 || size.height < ui::MIN_SIZE.1 {
+            let [message] = Layout::vertical([Constraint::Length(2)])
+                .flex(Flex::Center)
+                .areas(size);
+            frame.render_widget(ui::draw_too_small(size, self.theme), message);
+            return;
+        }
+
+        // Vertical layout
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Min(3),
+                    Constraint::Percentage(80),
+                    Constraint::Percentage(20),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+
+        // Title
+        let timestamp = self.spectra.as_ref().and_then(|spec| spec.timestamp);
+        cfg_if::cfg_if! {
+            if #[cfg(feature="lwa-na")]{
+                let name = match &self.data_backend {
+                    TuiType::File { input_file, .. } => input_file.display().to_string(),
+                    TuiType::Live { data_recorder,..} => data_recorder.join(", "),
+                    TuiType::Replay { input_file, .. } => input_file.display().to_string(),
+                };
+                let (beams, tunings) = self.spectra.as_ref().map_or((&[][..], &[][..]), |spec| {
+                    (spec.beams.as_slice(), spec.tunings.as_slice())
+                });
+                frame.render_widget(ui::draw_title(name, beams, tunings, &self.backend_status, self.paused, timestamp, self.poll_delay, self.stale_for(), self.theme),  chunks[0]);
+
+            }else {
+
+                frame.render_widget(ui::draw_title::<&str>(&self.backend_status, self.paused, timestamp, self.poll_delay, self.stale_for(), self.theme), chunks[0]);
+            }
+        }
+
+        if let Some(log) = self.plot_in_db() {
+            if let Some(spec) = self.spectra.as_mut() {
+                spec.plot_log = log;
+            }
+        }
+
+        match self.show_waterfall {
+            _ if self.spectra.is_none() => {
+                self.chart_view = ChartView::default();
+                frame.render_widget(
+                    ui::draw_waiting(self.ticks, chunks[1].height, self.theme),
+                    chunks[1],
+                );
+            }
+            true => {
+                // the mouse only works on the chart
+                self.chart_view = ChartView::default();
+                frame.render_widget(
+                    ui::draw_waterfall(
+                        &self.history,
+                        &self.ylims,
+                        &self.xlims,
+                        self.plot_in_db().unwrap_or(false),
+                        self.poll_delay,
+                        self.theme,
+                    ),
+                    chunks[1],
+                )
+            }
+            false => {
+                let [plot_area, peak_area] =
+                    Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(chunks[1]);
+                frame.render_widget(
+                    ui::draw_peak(self.spectra.as_ref(), &self.hidden, &self.xlims, self.theme),
+                    peak_area,
+                );
+
+                let [chart_area, legend_area] = match self.show_legend {
+                    true => {
+                        let width = self.spectra.as_ref().map_or(0, |spec| {
+                            spec.ant_names
+                                .iter()
+                                .map(|name| name.len())
+                                .max()
+                                .unwrap_or(0)
+                        }) as u16
+                            + 6;
+                        Layout::horizontal([Constraint::Min(0), Constraint::Length(width.min(30))])
+                            .areas(plot_area)
+                    }
+                    false => [plot_area, Rect::default()],
+                };
+                if self.show_legend {
+                    frame.render_widget(
+                        ui::draw_legend(
+                            self.spectra.as_ref(),
+                            self.palette,
+                            &self.hidden,
+                            &self.no_data,
+                            self.theme,
+                        ),
+                        legend_area,
+                    );
+                }
+
+                // own the name so the charts do not borrow all of self alongside the view
+                let focus = self.focused_name().map(str::to_owned);
+                let chart = |log: bool| {
+                    ui::draw_charts(
+                        self.spectra.as_ref(),
+                        log,
+                        &self.ylims,
+                        &self.xlims,
+                        focus.as_deref(),
+                        self.theme,
+                    )
+                    .legend(!self.show_legend)
+                    .raw_labels(log && self.log_axis && self.log_plot == Some(false))
+                    .palette(self.palette)
+                    .marker(self.trace_marker)
+                    .scatter(self.scatter)
+                    .fit_width(self.fit_width)
+                    .db_reference(self.ref_level.level)
+                    .noise_floor(self.floor_relative)
+                    .crosshair(self.crosshair)
+                    .hidden(&self.hidden)
+                    .no_data(&self.no_data)
+                    .saturation_threshold(self.saturation_threshold)
+                    .median(
+                        self.median.as_ref(),
+                        self.median_mode == MedianMode::WithTraces,
+                    )
+                    .max_hold(self.max_hold_view.as_ref())
+                    .spread(self.spread_band.as_ref())
+                    .outliers(self.outliers.as_ref())
+                    .overlays(&self.kurtosis_flags)
+                    .bands(&self.rfi_bands)
+                    .markers(&self.markers)
+                };
+
+                let log = self.spectra.as_ref().is_some_and(|spec| spec.plot_log);
+                match self.split_scales {
+                    true => {
+                        let [raw_area, db_area] =
+                            Layout::vertical([Constraint::Ratio(1, 2); 2]).areas(chart_area);
+                        // the mouse and limits act on the pane in the current scale
+                        let (active_area, other_area) = match log {
+                            true => (db_area, raw_area),
+                            false => (raw_area, db_area),
+                        };
+                        frame.render_widget(chart(!log), other_area);
+                        frame.render_stateful_widget(chart(log), active_area, &mut self.chart_view);
+                    }
+                    false => {
+                        frame.render_stateful_widget(chart(log), chart_area, &mut self.chart_view)
+                    }
+                }
+
+                // highlight the region being selected
+                if let Some(((col0, row0), (col1, row1))) = self.selection {
+                    let buf = frame.buffer_mut();
+                    for col in col0.min(col1)..=col0.max(col1) {
+                        for row in row0.min(row1)..=row0.max(row1) {
+                            if self.chart_view.contains(col, row) {
+                                buf[(col, row)].set_bg(self.theme.dim());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Logs, then the optional statistics tables, then the key help
+        cfg_if::cfg_if! {
+            if #[cfg(feature="lwa-na")]{
+                let show_saturation = self.show_stats;
+                let show_quality = self.show_quality;
+            } else{
+                let show_saturation = false;
+                let show_quality = false;
+            }
+        }
+        let n_panels = [self.show_stats_panel, show_saturation, show_quality]
+            .into_iter()
+            .filter(|shown| *shown)
+            .count() as u16;
+
+        let mut constraints = vec![Constraint::Percentage(80 - 20 * n_panels)];
+        constraints.extend(std::iter::repeat(Constraint::Min(20)).take(n_panels as usize + 1));
+        let log_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .split(chunks[2]);
+
+        // Logs
+        frame.render_widget(ui::draw_logs(self.log_level, self.theme), log_chunks[0]);
+        let mut next = 1;
+        if self.show_stats_panel {
+            frame.render_widget(
+                ui::draw_stats(self.spectra.as_ref(), &self.xlims, &self.hidden, self.theme),
+                log_chunks[next],
+            );
+            next += 1;
+        }
+        #[cfg(feature = "lwa-na")]
+        if show_saturation {
+            frame.render_widget(
+                self.saturations
+                    .as_ref()
+                    .map(|stats| {
+                        ui::draw_saturation(
+                            stats,
+                            self.saturation_threshold.unwrap_or(0.1),
+                            self.theme,
+                        )
+                    })
+                    .unwrap_or_default(),
+                log_chunks[next],
+            );
+            next += 1;
+        }
+        #[cfg(feature = "lwa-na")]
+        if show_quality {
+            frame.render_widget(
+                ui::draw_quality(self.spectra.as_ref(), self.theme),
+                log_chunks[next],
+            );
+            next += 1;
+        }
+        // Body & Help
+        frame.render_widget(ui::draw_help(self.theme), log_chunks[next]);
+
+        match self.input_mode {
+            InputMode::Normal | InputMode::Crosshair => {}
+            #[cfg(feature = "ovro")]
+            InputMode::AntennaInput => {
+                let (title, border_color) =
+                    match self.input.trim().is_empty() || self.is_known_antenna(&self.input) {
+                        true => ("Enter Antenna Name", self.theme.text()),
+                        false => ("Unknown Antenna", self.theme.error()),
+                    };
+                let input = Paragraph::new(self.input.as_str())
+                    .style(Style::default())
+                    .block(
+                        Block::default()
+                            .title(title)
+                            .border_style(Style::default().fg(border_color))
+                            .borders(Borders::ALL),
+                    );
+
+                let area =
+                    ui::center_popup(chunks[1], Constraint::Length(20), Constraint::Length(3));
+                frame.render_widget(Clear, area); //this clears out the background
+                frame.render_widget(input, area);
+
+                frame.set_cursor_position(Position::new(
+                    // Draw the cursor at the current position in the input field.
+                    // This position is can be controlled via the left and right arrow key
+                    area.x + self.character_index as u16 + 1,
+                    // Move one line down, from the border to the input line
+                    area.y + 1,
+                ));
+            }
+            #[cfg(feature = "ovro")]
+            InputMode::PresetName => {
+                let input = Paragraph::new(self.input.as_str())
+                    .style(Style::default())
+                    .block(
+                        Block::default()
+                            .title("Save Filter as Preset")
+                            .borders(Borders::ALL),
+                    );
+
+                let area =
+                    ui::center_popup(chunks[1], Constraint::Length(26), Constraint::Length(3));
+                frame.render_widget(Clear, area);
+                frame.render_widget(input, area);
+
+                frame.set_cursor_position(Position::new(
+                    area.x + self.character_index as u16 + 1,
+                    area.y + 1,
+                ));
+            }
+            #[cfg(feature = "ovro")]
+            InputMode::RemoveAntenna => {
+                let items: Vec<ListItem> = self
+                    .antenna_filter
+                    .items
+                    .iter()
+                    .map(|todo_item| ListItem::from(todo_item.clone()))
+                    .collect();
+                let n_items = items.len();
+                // render the List in the middle of the screen
+                // the list keeps the selection in view, fit the popup to the items
+                let list = List::new(items)
+                    .highlight_style(self.theme.selected())
+                    .highlight_symbol(">")
+                    .highlight_spacing(HighlightSpacing::Always)
+                    .block(
+                        Block::default()
+                            .title(format!(
+                                "Select Antenna {}/{n_items}",
+                                self.antenna_filter
+                                    .state
+                                    .selected()
+                                    .map_or(0, |index| index.min(n_items.saturating_sub(1)) + 1)
+                            ))
+                            .borders(Borders::ALL),
+                    );
+                let height = (n_items as u16).saturating_add(2).min(chunks[1].height);
+                let area = ui::center_popup(
+                    chunks[1],
+                    Constraint::Length(24),
+                    Constraint::Length(height),
+                );
+                self.antenna_filter.page = area.height.saturating_sub(2).max(1);
+                frame.render_widget(Clear, area); //this clears out the background
+                frame.render_stateful_widget(list, area, &mut self.antenna_filter.state);
+            }
+            InputMode::HideAntenna => {
+                let items: Vec<ListItem> = self
+                    .spectra
+                    .iter()
+                    .flat_map(|spec| spec.ant_names.iter())
+                    .map(|name| {
+                        let mark = match self.hidden.contains(name) {
+                            true => ' ',
+                            false => 'x',
+                        };
+                        ListItem::from(format!("[{mark}] {name}"))
+                    })
+                    .collect();
+                let list = List::new(items)
+                    .highlight_style(self.theme.selected())
+                    .highlight_symbol(">")
+                    .highlight_spacing(HighlightSpacing::Always)
+                    .block(
+                        Block::default()
+                            .title("Visible Antennas")
+                            .borders(Borders::ALL),
+                    );
+                let area = ui::center_popup(chunks[1], Constraint::Length(24), Constraint::Max(20));
+                frame.render_widget(Clear, area); //this clears out the background
+                frame.render_stateful_widget(list, area, &mut self.visibility_state);
+            }
+            InputMode::Compare => {
+                let items: Vec<ListItem> = self
+                    .latest
+                    .iter()
+                    .flat_map(|spec| spec.ant_names.iter())
+                    .map(|name| {
+                        let mark = match self.compare_first.as_ref() == Some(name) {
+                            true => '1',
+                            false => ' ',
+                        };
+                        ListItem::from(format!("[{mark}] {name}"))
+                    })
+                    .collect();
+                let title = match self.compare_first {
+                    Some(_) => "Compare With",
+                    None => "Compare",
+                };
+                let list = List::new(items)
+                    .highlight_style(self.theme.selected())
+                    .highlight_symbol(">")
+                    .highlight_spacing(HighlightSpacing::Always)
+                    .block(Block::default().title(title).borders(Borders::ALL));
+                let area = ui::center_popup(chunks[1], Constraint::Length(24), Constraint::Max(20));
+                frame.render_widget(Clear, area); //this clears out the background
+                frame.render_stateful_widget(list, area, &mut self.compare_state);
+            }
+            InputMode::RefLevel => {
+                let area =
+                    ui::center_popup(chunks[1], Constraint::Length(36), Constraint::Length(3));
+                frame.render_widget(Clear, area);
+                frame.render_widget(&self.ref_level.textarea, area);
+            }
+            InputMode::Marker => {
+                let area =
+                    ui::center_popup(chunks[1], Constraint::Length(36), Constraint::Length(3));
+                frame.render_widget(Clear, area);
+                frame.render_widget(&self.marker_input.textarea, area);
+            }
+            InputMode::ConfirmQuit => {
+                let pending = self.pending_tasks.load(Ordering::SeqCst);
+                let prompt = Paragraph::new(format!("{pending} export(s) still writing."))
+                    .style(Style::default())
+                    .block(
+                        Block::default()
+                            .title("Quit now? (y/n)")
+                            .border_style(Style::default().fg(self.theme.error()))
+                            .borders(Borders::ALL),
+                    );
+
+                let area =
+                    ui::center_popup(chunks[1], Constraint::Length(32), Constraint::Length(3));
+                frame.render_widget(Clear, area);
+                frame.render_widget(prompt, area);
+            }
+            InputMode::Help => {
+                let area = ui::center_popup(
+                    chunks[1],
+                    Constraint::Percentage(80),
+                    Constraint::Percentage(100),
+                );
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    ui::draw_full_help(&mut self.help_scroll, area.height, self.theme),
+                    area,
+                );
+            }
+            InputMode::ChartLims => {
+                let outer_area =
+                    ui::center_popup(chunks[1], Constraint::Length(54), Constraint::Length(5));
+
+                //this clears out the background
+                frame.render_widget(Clear, outer_area);
+
+                let outter_block = Block::default()
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(self.theme.accent()))
+                    .title("Set Y-limits, or a span around the data (Tab to change focus)");
+
+                let area = outter_block.inner(outer_area);
+                frame.render_widget(outter_block, outer_area);
+
+                let text_chunks = self.ylims.layout.split(area);
+
+                for (textarea, chunk) in self.ylims.textareas.iter().zip(text_chunks.iter()) {
+                    frame.render_widget(textarea, *chunk);
+                }
+
+                // frame.render_widget(input, area);
+                // frame.set_cursor_position(Position::new(
+                //     // Draw the cursor at the current position in the input field.
+                //     // This position is can be controlled via the left and right arrow key
+                //     area.x + self.character_index as u16 + 1,
+                //     // Move one line down, from the border to the input line
+                //     area.y + 1,
+                // ));
+                // TODO
+                // Make a pop up
+                // allow text input for limit
+            }
+            InputMode::FreqLims => {
+                let outer_area =
+                    ui::center_popup(chunks[1], Constraint::Length(40), Constraint::Length(5));
+
+                //this clears out the background
+                frame.render_widget(Clear, outer_area);
+
+                let outter_block = Block::default()
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(self.theme.accent()))
+                    .title("Set X-limits (Tab to change focus)");
+
+                let area = outter_block.inner(outer_area);
+                frame.render_widget(outter_block, outer_area);
+
+                let text_chunks = self.xlims.layout.split(area);
+
+                for (textarea, chunk) in self.xlims.textareas.iter().zip(text_chunks.iter()) {
+                    frame.render_widget(textarea, *chunk);
+                }
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    /// Sends a command to the backend without waiting.
+    ///
+    /// Commands are dropped if the backend does not listen for them.
+    fn send_command(&self, command: BackendCommand) {
+        if let Err(err) = self.command_sender.try_send(command) {
+            debug!("Backend did not accept {command:?}: {err}");
+        }
+    }
+
+    /// Doubles or halves the live polling delay.
+    #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+    fn change_poll_delay(&mut self, increase: bool) {
+        let Some(delay) = self.poll_delay else {
+            info!("The poll delay only applies to live data.");
+            return;
+        };
+
+        let delay = match increase {
+            true => delay.saturating_mul(2),
+            false => (delay / 2).max(MIN_POLL_DELAY),
+        };
+        self.poll_delay = Some(delay);
+        info!("Polling every {:.1}s.", delay.as_secs_f64());
+        self.send_command(BackendCommand::SetDelay(delay));
+    }
+
+    /// How long live data has been overdue, `None` while it is arriving on
+    /// time or when reading a file.
+    ///
+    /// Files are never stale: an OVRO file is only reloaded when whatever
+    /// writes it does so, on no schedule this knows about, and North Arm files
+    /// and recordings only advance when stepped or replayed, so a quiet file
+    /// does not mean the backend stalled.
+    fn stale_for(&self) -> Option<Duration> {
+        let delay = self.poll_delay?;
+        let elapsed = self.last_data.elapsed();
+        (elapsed > delay.saturating_mul(STALE_AFTER_POLLS)).then_some(elapsed)
+    }
+
+    /// Warns once when live data stops arriving.
+    fn check_stale(&mut self) {
+        let stale = self.stale_for();
+        if let (Some(elapsed), false) = (stale, self.stale) {
+            log::warn!(
+                "No data for {:.0}s, the backend may have stalled.",
+                elapsed.as_secs_f64()
+            );
+        }
+        self.stale = stale.is_some();
+    }
+
+    /// Processes newly arrived spectra into the spectra to be plotted.
+    fn receive_spectra(&mut self, data: AutoSpectra) {
+        if self.stale {
+            info!(
+                "Data resumed after {:.0}s.",
+                self.last_data.elapsed().as_secs_f64()
+            );
+            self.stale = false;
+        }
+        self.last_data = Instant::now();
+        self.record_spectra(&data);
+
+        #[cfg(feature = "ovro")]
+        self.discover_antennas(&data);
+
+        // keep holding while paused so bursts are not missed
+        if self.show_max_hold {
+            self.update_max_hold(&data);
+        }
+
+        if self.paused {
+            debug!("Display paused, dropping new autospectra.");
+            return;
+        }
+
+        info!("Received New autosprectra.");
+        // every sample zero or NaN, e.g. the antenna is offline
+        let no_data = data
+            .ant_names
+            .iter()
+            .zip(data.log_spectra.iter())
+            .filter(|(_, trace)| trace.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect::<HashSet<_>>();
+        if no_data != self.no_data {
+            let empty = data
+                .ant_names
+                .iter()
+                .filter(|name| no_data.contains(*name))
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            match empty.is_empty() {
+                true => info!("Every antenna has valid data again."),
+                false => log::warn!("No valid data for {}.", empty.join(", ")),
+            }
+            self.no_data = no_data;
+        }
+        if self.log_plot.is_none() {
+            self.log_plot = Some(data.plot_log);
+        }
+        if let Some((min, max)) = self.launch_ylims.take() {
+            self.ylims
+                .set_typed(min, max, self.plot_in_db().unwrap_or(data.plot_log));
+        }
+        self.push_history(&data);
+        let mut data = self.average_spectra(data);
+        if self.show_kurtosis {
+            data.channel_stat = self.kurtosis(&data);
+        }
+        self.latest.replace(data);
+        self.refresh_view();
+    }
+
+    /// Sends a copy of the spectra to the recording, if there is one.
+    fn record_spectra(&mut self, data: &AutoSpectra) {
+        let Some(recorder) = self.recorder.as_ref() else {
+            return;
+        };
+        match recorder.try_send(data.clone()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                log::warn!("Recording is falling behind, dropped a spectrum.")
+            }
+            // the recorder already logged why it stopped
+            Err(TrySendError::Closed(_)) => self.recorder = None,
+        }
+    }
+
+    /// Fills in the antenna list from the first file read when the number of
+    /// spectra was not given on the command line.
+    #[cfg(feature = "ovro")]
+    fn discover_antennas(&mut self, data: &AutoSpectra) {
+        if !matches!(self.data_backend, TuiType::File { nspectra: None, .. })
+            || !self.antenna_filter.items.is_empty()
+        {
+            return;
+        }
+
+        // two polarizations per antenna
+        let n_ants = data.ant_names.len().div_ceil(2);
+        info!("Found {n_ants} antennas in the input file.");
+        self.antenna_filter.items = (0..n_ants).map(|s| s.to_string()).collect();
+    }
+
+    /// Rebuilds the plotted spectra from the latest data with the current
+    /// display settings.
+    fn refresh_view(&mut self) {
+        let Some(latest) = self.latest.as_ref() else {
+            return;
+        };
+        let mut view = self.process(latest);
+
+        // the comparison sets its own dB values, the reference level cancels out
+        if let Some((first, second)) = self.compare.take() {
+            match view.compare(&first, &second) {
+                Some(compared) => {
+                    view = compared;
+                    self.compare = Some((first, second));
+                }
+                None => info!("{first} or {second} is no longer plotted, stopped comparing them."),
+            }
+        }
+
+        self.noise_floors = self
+            .floor_relative
+            .then(|| view.noise_floors(NOISE_FLOOR_QUANTILE));
+        if let Some(floors) = self.noise_floors.as_ref() {
+            view.subtract_floors(floors);
+        }
+
+        // the deviation is in raw units, it does not apply to ratios against a
+        // reference or between two antennas
+        let referenced = self.reference.is_some() && self.reference_mode != ReferenceMode::Off;
+        self.spread_band = self
+            .spread
+            .as_ref()
+            .filter(|_| !referenced && self.compare.is_none())
+            .and_then(|spread| {
+                let mut lower = view.offset_by(spread, -1.0)?;
+                let mut upper = view.offset_by(spread, 1.0)?;
+                self.set_db_scale(&mut lower);
+                self.set_db_scale(&mut upper);
+                Some((lower, upper))
+            });
+
+        self.spectra.replace(view);
+        self.update_max_hold_view();
+        self.update_median();
+        self.update_outliers();
+        self.update_kurtosis_flags();
+    }
+
+    /// Removes the reference from spectra, then smooths them and sets their dB
+    /// values relative to the reference level.
+    fn process(&self, spec: &AutoSpectra) -> AutoSpectra {
+        match (self.reference_mode, self.reference.as_ref()) {
+            // smoothing before subtracting leaves the raw difference the same
+            // and keeps the dB difference computed here, the reference level
+            // cancels out of it
+            (ReferenceMode::Difference, Some(reference)) => spec
+                .smooth(self.smoothing_width)
+                .subtract_reference(&reference.smooth(self.smoothing_width)),
+            (ReferenceMode::Ratio, Some(reference)) => {
+                let mut view = spec
+                    .apply_reference(reference, |val, ref_val| val / ref_val)
+                    .smooth(self.smoothing_width);
+                view.set_db_reference(self.ref_level.level);
+                view
+            }
+            _ => {
+                let mut view = spec.smooth(self.smoothing_width);
+                view.set_db_reference(self.ref_level.level);
+                view
+            }
+        }
+    }
+
+    /// Applies the level typed into the popup to everything on screen.
+    fn apply_ref_level(&mut self) {
+        self.ref_level.update_val();
+        match self.ref_level.level {
+            Some(level) => info!("Plotting dB relative to {level}."),
+            None => info!("Plotting dB relative to 1."),
+        }
+
+        self.refresh_view();
+    }
+
+    /// Puts the dB values of spectra derived from the view on the plotted scale,
+    /// relative to the reference level or to each antenna's noise floor.
+    fn set_db_scale(&self, spec: &mut AutoSpectra) {
+        spec.set_db_reference(self.ref_level.level);
+        if let Some(floors) = self.noise_floors.as_ref() {
+            spec.subtract_floors(floors);
+        }
+    }
+
+    fn toggle_noise_floor(&mut self) {
+        self.floor_relative = !self.floor_relative;
+        match self.floor_relative {
+            true => info!("Plotting dB above each antenna's noise floor."),
+            false => info!("Plotting dB relative to the reference level."),
+        }
+        self.refresh_view();
+    }
+
+    fn add_marker(&mut self) {
+        match self.marker_input.take() {
+            Some(Some((freq, label))) => {
+                info!("Marked {freq} {label}");
+                self.markers.push((freq, label));
+            }
+            Some(None) => {
+                info!("Cleared {} markers.", self.markers.len());
+                self.markers.clear();
+            }
+            None => {}
+        }
+    }
+
+    fn change_smoothing(&mut self, increase: bool) {
+        self.smoothing_width = match increase {
+            true => self.smoothing_width.saturating_add(1),
+            false => self.smoothing_width.saturating_sub(1).max(1),
+        };
+        info!("Smoothing over {} channels.", self.smoothing_width);
+        self.refresh_view();
+    }
+
+    /// Folds new spectra into the max hold, starting over if the antennas
+    /// or channels no longer line up.
+    fn update_max_hold(&mut self, data: &AutoSpectra) {
+        let matches = self.max_hold.as_ref().is_some_and(|held| {
+            held.ant_names == data.ant_names
+                && held
+                    .spectra
+                    .iter()
+                    .zip(data.spectra.iter())
+                    .all(|(a, b)| a.len() == b.len())
+        });
+
+        let held = match (matches, self.max_hold.take()) {
+            (true, Some(held)) => {
+                let spectra = held
+                    .spectra
+                    .iter()
+                    .zip(data.spectra.iter())
+                    .map(|(held, new)| {
+                        held.iter()
+                            .zip(new.iter())
+                            .map(|((freq, a), (_, b))| (*freq, a.max(*b)))
+                            .collect()
+                    })
+                    .collect();
+                let mut out = AutoSpectra::from_pairs(held.ant_names, spectra, data.plot_log);
+                out.x_unit = data.x_unit;
+                out
+            }
+            _ => {
+                debug!("Starting a new max hold.");
+                data.clone()
+            }
+        };
+        self.max_hold = Some(held);
+        self.update_max_hold_view();
+    }
+
+    /// Runs the max hold through the same processing as the plotted spectra,
+    /// it has no counterpart in a comparison.
+    fn update_max_hold_view(&mut self) {
+        self.max_hold_view = self
+            .max_hold
+            .as_ref()
+            .filter(|_| self.compare.is_none())
+            .map(|held| {
+                let mut held = self.process(held);
+                if let Some(floors) = self.noise_floors.as_ref() {
+                    held.subtract_floors(floors);
+                }
+                held
+            });
+    }
+
+    fn toggle_max_hold(&mut self) {
+        self.show_max_hold = !self.show_max_hold;
+        self.max_hold = None;
+        self.max_hold_view = None;
+        if self.show_max_hold {
+            if let Some(latest) = self.latest.clone() {
+                self.update_max_hold(&latest);
+            }
+        }
+    }
+
+    /// Stores the latest spectra as the reference and starts subtracting it.
+    fn capture_reference(&mut self) {
+        let Some(latest) = self.latest.as_ref() else {
+            info!("No spectra to use as a reference yet.");
+            return;
+        };
+
+        self.reference = Some(latest.clone());
+        info!("Captured reference spectra.");
+        if self.reference_mode == ReferenceMode::Off {
+            self.reference_mode = ReferenceMode::Difference;
+        }
+        self.refresh_view();
+    }
+
+    fn cycle_reference_mode(&mut self) {
+        if self.reference.is_none() {
+            info!("No reference captured, press r to capture one.");
+            return;
+        }
+        self.reference_mode = self.reference_mode.next();
+        info!("Reference mode: {:?}", self.reference_mode);
+        self.refresh_view();
+    }
+
+    /// The overlays are drawn on the antennas' own dB scale, so they are off
+    /// while comparing two antennas.
+    fn update_median(&mut self) {
+        self.median = match self.median_mode {
+            MedianMode::Off => None,
+            _ if self.compare.is_some() => None,
+            _ => self.spectra.as_ref().and_then(|spec| {
+                let mut median = spec.median();
+                match median.as_mut() {
+                    Some(median) => self.set_db_scale(median),
+                    None => {
+                        debug!("Unable to compute the median of spectra with different lengths.")
+                    }
+                }
+                median
+            }),
+        };
+    }
+
+    fn update_outliers(&mut self) {
+        self.outliers = self
+            .spectra
+            .as_ref()
+            .filter(|_| self.show_outliers && self.compare.is_none())
+            .map(|spec| {
+                let mut outliers = spec.outliers(self.outlier_sigma);
+                self.set_db_scale(&mut outliers);
+                outliers
+            });
+    }
+
+    /// Spectral kurtosis of the newest polls in the history that have the
+    /// same traces as `data`.
+    fn kurtosis(&self, data: &AutoSpectra) -> Option<Vec<Vec<f64>>> {
+        let mut polls = self
+            .history
+            .iter()
+            .rev()
+            .map(|(_, spec)| spec)
+            .take_while(|spec| spec.ant_names == data.ant_names)
+            .collect::<Vec<_>>();
+        polls.reverse();
+        AutoSpectra::spectral_kurtosis(polls)
+    }
+
+    fn update_kurtosis_flags(&mut self) {
+        self.kurtosis_flags = self
+            .spectra
+            .iter()
+            .filter(|_| self.show_kurtosis && self.compare.is_none())
+            .flat_map(|spec| {
+                [true, false].map(|high| {
+                    let mut flagged = spec.channel_stat_outliers(KURTOSIS_SIGMA, high);
+                    self.set_db_scale(&mut flagged);
+                    (flagged, self.theme.kurtosis(high))
+                })
+            })
+            .collect();
+    }
+
+    fn toggle_kurtosis(&mut self) {
+        self.show_kurtosis = !self.show_kurtosis;
+        let kurtosis = self
+            .latest
+            .as_ref()
+            .filter(|_| self.show_kurtosis)
+            .and_then(|latest| self.kurtosis(latest));
+        if self.show_kurtosis && kurtosis.is_none() {
+            info!("Spectral kurtosis needs at least 3 polls of the same antennas in the history.");
+        }
+        if let Some(latest) = self.latest.as_mut() {
+            latest.channel_stat = kurtosis;
+        }
+        self.refresh_view();
+    }
+
+    fn change_outlier_sigma(&mut self, increase: bool) {
+        self.outlier_sigma = match increase {
+            true => self.outlier_sigma + 0.5,
+            false => (self.outlier_sigma - 0.5).max(0.5),
+        };
+        info!(
+            "Flagging channels {} sigma above the median.",
+            self.outlier_sigma
+        );
+        self.update_outliers();
+    }
+
+    /// Writes the current view settings so they are restored on the next run.
+    fn save_state(&self) {
+        let state = SavedState {
+            ymin: self.ylims.min,
+            ymax: self.ylims.max,
+            log_plot: self.log_plot,
+        };
+        if let Err(err) = state.save() {
+            log::warn!("Unable to save view settings: {err:#}");
+        }
+    }
+
+    /// Writes the current view to the config file as the defaults for future launches.
+    fn save_defaults(&self) {
+        let view = ViewConfig {
+            log_plot: self.log_plot,
+            log_axis: self.log_axis,
+            ymin: self.ylims.min,
+            ymax: self.ylims.max,
+            smoothing_width: Some(self.smoothing_width),
+            palette: Some(self.palette),
+        };
+        match Config::save_view(&view) {
+            Ok(path) => info!("Saved the view as the defaults in {}", path.display()),
+            Err(err) => log::warn!("Unable to save the default view: {err:#}"),
+        }
+        // otherwise the view at the last exit would shadow the new defaults
+        if let Err(err) = SavedState::clear() {
+            log::warn!("Unable to clear the saved view settings: {err:#}");
+        }
+    }
+
+    /// Store a copy of the new spectra for the waterfall,
+    /// dropping the oldest entry when the history is full.
+    fn push_history(&mut self, data: &AutoSpectra) {
+        if self.history_depth == 0 {
+            return;
+        }
+        while self.history.len() >= self.history_depth {
+            self.history.pop_front();
+        }
+        self.history.push_back((Instant::now(), data.clone()));
+    }
+
+    /// Adds the new spectra to the running mean and returns the averaged spectra.
+    ///
+    /// The average restarts whenever the antennas or the shape of the data changes.
+    fn average_spectra(&mut self, data: AutoSpectra) -> AutoSpectra {
+        if self.averaging_window <= 1 {
+            self.average_buffer.clear();
+            return data;
+        }
 
-        self.filter_sender
-            .send(self.antenna_filter.items.clone())
-            .await?;
+        let changed = self.average_buffer.back().is_some_and(|last| {
+            last.ant_names != data.ant_names
+                || last
+                    .spectra
+                    .iter()
+                    .map(Vec::len)
+                    .ne(data.spectra.iter().map(Vec::len))
+        });
+        if changed {
+            debug!("Antennas changed, resetting the running average.");
+            self.average_buffer.clear();
+        }
 
-        self.input.clear();
-        self.reset_cursor();
-        self.input_mode = InputMode::Normal;
+        while self.average_buffer.len() >= self.averaging_window {
+            self.average_buffer.pop_front();
+        }
+        self.average_buffer.push_back(data);
+        self.update_spread();
+
+        AutoSpectra::mean(self.average_buffer.iter().rev()).unwrap_or_else(|| {
+            info!("Unable to average spectra with mismatched frequencies.");
+            let latest = self
+                .average_buffer
+                .pop_back()
+                .expect("Average buffer cannot be empty after a push.");
+            self.average_buffer.clear();
+            self.spread = None;
+            latest
+        })
+    }
 
-        Ok(())
+    fn update_spread(&mut self) {
+        self.spread = match self.show_spread {
+            true => AutoSpectra::std_dev(self.average_buffer.iter()),
+            false => None,
+        };
     }
 
-    // END ratatui example functions
+    fn toggle_spread(&mut self) {
+        self.show_spread = !self.show_spread;
+        match (self.show_spread, self.averaging_window) {
+            (true, 1) => info!("The ±1σ band is drawn once more than one spectrum is averaged."),
+            (true, _) => info!("Showing the ±1σ band of the average."),
+            (false, _) => info!("Hiding the ±1σ band."),
+        }
+        self.update_spread();
+        self.refresh_view();
+    }
 
-    // BEGIN functions pulled from list examples edited for need
-    fn select_next(&mut self) {
-        self.antenna_filter.state.select_next();
+    fn change_averaging(&mut self, increase: bool) {
+        self.averaging_window = match increase {
+            true => self.averaging_window.saturating_add(1),
+            false => self.averaging_window.saturating_sub(1).max(1),
+        };
+        info!("Averaging {} spectra.", self.averaging_window);
     }
 
-    fn select_previous(&mut self) {
-        self.antenna_filter.state.select_previous();
+    /// Returns the axes, scale, processing and display state of the view to
+    /// their defaults and resumes a paused display.
+    ///
+    /// Kept are the antenna selection, the overlays (median, outliers,
+    /// kurtosis, markers), the captured reference (its mode is turned off)
+    /// and the theme and palette.
+    fn reset_view(&mut self) {
+        self.ylims.min = None;
+        self.ylims.max = None;
+        self.ylims_fitted = false;
+        self.ylims_locked = false;
+        self.xlims.min = None;
+        self.xlims.max = None;
+
+        self.log_plot = self.latest.as_ref().map(|spec| spec.plot_log);
+        self.log_axis = false;
+        self.split_scales = false;
+        self.floor_relative = false;
+        self.ref_level.level = None;
+        self.reference_mode = ReferenceMode::Off;
+        self.compare = None;
+        self.compare_first = None;
+        self.hidden.clear();
+        self.paused = false;
+
+        self.smoothing_width = 1;
+        self.averaging_window = 1;
+        self.average_buffer.clear();
+        self.spread = None;
+        self.focused = None;
+        self.show_max_hold = false;
+        self.max_hold = None;
+        self.max_hold_view = None;
+
+        info!("View reset to the defaults.");
+        self.refresh_view();
     }
 
-    async fn remove_antenna(&mut self) -> Result<()> {
-        if let Some(i) = self.antenna_filter.state.selected() {
-            let removed = self.antenna_filter.items.remove(i);
-            info!("Removing: {removed}");
-            self.filter_sender
-                .send(self.antenna_filter.items.clone())
-                .await?;
-        }
+    /// Runs an export on the blocking thread pool, counting it in
+    /// `pending_tasks` until it finishes. The task returns the message to log.
+    fn spawn_export<F>(&self, task: F)
+    where
+        F: FnOnce() -> Result<String> + Send + 'static,
+    {
+        let guard = PendingGuard::new(Arc::clone(&self.pending_tasks));
+
+        tokio::task::spawn_blocking(move || {
+            let _guard = guard;
+            match task() {
+                Ok(message) => info!("{message}"),
+                Err(err) => log::error!("{err:#}"),
+            }
+        });
+    }
 
-        // reset the list state and the input mode
-        self.input_mode = InputMode::Normal;
-        self.antenna_filter.state = ListState::default();
+    /// Writes the current spectra to a CSV in the working directory.
+    fn export_csv(&self) {
+        let Some(spec) = self.spectra.clone() else {
+            info!("No spectra to export yet.");
+            return;
+        };
 
-        Ok(())
+        let path = export::timestamped_path("csv");
+        self.spawn_export(move || {
+            export::write_csv(&spec, &path).map(|_| format!("Saved spectra to {}", path.display()))
+        });
     }
-    // END list examples
-}
 
-#[cfg(feature = "lwa-na")]
-type BackendReturn = Result<Receiver<(AutoSpectra, Option<SaturationStats>)>>;
-#[cfg(not(feature = "lwa-na"))]
-type BackendReturn = Result<Receiver<AutoSpectra>>;
-impl<'a> App<'a> {
-    pub fn new(refresh_rate: Duration, data_backend: TuiType) -> Self {
-        let (filter_sender, filter_recv) = tokio::sync::mpsc::channel(10);
+    /// Writes the current spectra to an npy snapshot in the working directory.
+    #[cfg(feature = "ovro")]
+    fn export_npy(&self) {
+        let Some(spec) = self.spectra.clone() else {
+            info!("No spectra to export yet.");
+            return;
+        };
 
-        #[cfg(feature = "ovro")]
-        let antenna_filter = match &data_backend {
-            TuiType::File { nspectra, .. } => {
-                (0..*nspectra).map(|s| s.to_string()).collect::<Vec<_>>()
+        let path = export::timestamped_path("npy");
+        self.spawn_export(move || {
+            export::write_npy_snapshot(&spec, &path)
+                .map(|_| format!("Saved spectra to {}", path.display()))
+        });
+    }
+
+    /// Renders the current spectra to a PNG in the working directory.
+    fn save_image(&self) {
+        let Some(spec) = self.spectra.clone() else {
+            info!("No spectra to save yet.");
+            return;
+        };
+        let log = spec.plot_log;
+        let xlims = (self.xlims.get_min(), self.xlims.get_max());
+        let ylims = (self.ylims.get_min(log), self.ylims.get_max(log));
+
+        let path = export::timestamped_path("png");
+        self.spawn_export(move || {
+            export::write_png(&spec, &path, xlims, ylims)
+                .map(|_| format!("Saved plot to {}", path.display()))
+        });
+    }
+
+    /// Makes the logger keep more (`true`) or fewer messages.
+    fn change_log_level(&mut self, verbose: bool) {
+        self.log_level = match (verbose, self.log_level) {
+            (true, LevelFilter::Off) => LevelFilter::Error,
+            (true, LevelFilter::Error) => LevelFilter::Warn,
+            (true, LevelFilter::Warn) => LevelFilter::Info,
+            (true, LevelFilter::Info) => LevelFilter::Debug,
+            (true, LevelFilter::Debug | LevelFilter::Trace) => LevelFilter::Trace,
+            (false, LevelFilter::Trace) => LevelFilter::Debug,
+            (false, LevelFilter::Debug) => LevelFilter::Info,
+            (false, LevelFilter::Info) => LevelFilter::Warn,
+            (false, LevelFilter::Warn) => LevelFilter::Error,
+            (false, LevelFilter::Error | LevelFilter::Off) => LevelFilter::Off,
+        };
+        tui_logger::set_default_level(self.log_level);
+        info!("Log level set to {}.", self.log_level);
+    }
+
+    /// Picks the antenna selected in the comparison list, the first pick is
+    /// remembered and the second starts the comparison.
+    ///
+    /// Returns true once both antennas are picked.
+    fn pick_compared(&mut self) -> bool {
+        let Some(name) = self.compare_state.selected().and_then(|index| {
+            self.latest
+                .as_ref()
+                .and_then(|spec| spec.ant_names.get(index))
+                .cloned()
+        }) else {
+            return false;
+        };
+
+        match self.compare_first.take() {
+            None => {
+                self.compare_first = Some(name);
+                false
             }
-            TuiType::Live { antenna, .. } => antenna.clone(),
+            Some(first) if first == name => {
+                info!("Pick a different antenna to compare {first} with.");
+                self.compare_first = Some(first);
+                false
+            }
+            Some(first) => {
+                info!("Comparing {first} with {name}.");
+                self.compare = Some((first, name));
+                self.refresh_view();
+                true
+            }
+        }
+    }
+
+    /// Name of the antenna drawn in color, if any.
+    ///
+    /// While the visibility list is open this is the antenna under its cursor.
+    fn focused_name(&self) -> Option<&str> {
+        let focused = match self.input_mode {
+            InputMode::HideAntenna => self.visibility_state.selected(),
+            _ => self.focused,
         };
+        focused
+            .zip(self.spectra.as_ref())
+            .and_then(|(index, spec)| spec.ant_names.get(index))
+            .map(String::as_str)
+    }
 
-        Self {
-            #[cfg(feature = "ovro")]
-            antenna_filter: AntennaFilter {
-                items: antenna_filter,
-                state: ListState::default(),
-            },
-            spectra: None,
-            refresh_rate,
-            data_backend,
-            input_mode: InputMode::Normal,
-            filter_sender,
-            filter_recv: Some(filter_recv),
-            #[cfg(feature = "ovro")]
-            input: String::new(),
-            #[cfg(feature = "ovro")]
-            character_index: 0,
-            log_plot: None,
-            #[cfg(feature = "lwa-na")]
-            saturations: None,
-            #[cfg(feature = "lwa-na")]
-            show_stats: false,
-            ylims: Ylims::new(),
+    /// Focuses the next (`step` 1) or previous (-1) antenna, starting
+    /// from the first or last when none is focused.
+    fn cycle_focus(&mut self, step: isize) {
+        let n_ants = self.spectra.as_ref().map_or(0, |spec| spec.ant_names.len());
+        if n_ants == 0 {
+            return;
+        }
+
+        self.focused = Some(match self.focused.filter(|index| *index < n_ants) {
+            Some(index) => (index as isize + step).rem_euclid(n_ants as isize) as usize,
+            None if step < 0 => n_ants - 1,
+            None => 0,
+        });
+        if let Some(name) = self.focused_name() {
+            info!("Focusing {name}.");
         }
     }
 
-    pub fn draw(&mut self, frame: &mut Frame) {
-        let size = frame.area();
+    /// Shows or hides the antenna selected in the visibility list.
+    fn toggle_hidden(&mut self) {
+        let Some(name) = self.visibility_state.selected().and_then(|index| {
+            self.spectra
+                .as_ref()
+                .and_then(|spec| spec.ant_names.get(index))
+        }) else {
+            return;
+        };
 
-        // Vertical layout
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Min(3),
-                    Constraint::Percentage(80),
-                    Constraint::Percentage(20),
-                ]
-                .as_ref(),
-            )
-            .split(size);
+        if !self.hidden.remove(name) {
+            debug!("Hiding {name}");
+            self.hidden.insert(name.clone());
+        }
+    }
 
-        // Title
-        cfg_if::cfg_if! {
-            if #[cfg(feature="lwa-na")]{
-                let name = match &self.data_backend {
-                    TuiType::File { input_file, .. } => input_file.display().to_string(),
-                    TuiType::Live { data_recorder,..} => data_recorder.clone(),
-                };
-                frame.render_widget(ui::draw_title(name),  chunks[0]);
+    /// Whether the plotted values are in dB, either because the data is
+    /// transformed or the axis is log scaled. `None` until data arrives.
+    fn plot_in_db(&self) -> Option<bool> {
+        self.log_plot.map(|log| log || self.log_axis)
+    }
 
-            }else {
+    /// Sets both axes to these ranges in plotted units.
+    fn set_view(&mut self, x: (f64, f64), y: (f64, f64)) {
+        let plot_log = self.spectra.as_ref().is_some_and(|spec| spec.plot_log);
+        self.xlims.min = Some(x.0);
+        self.xlims.max = Some(x.1);
+        self.ylims.set_plotted(y.0, y.1, plot_log);
+        self.ylims_fitted = false;
+        self.ylims_locked = false;
+
+        // keep following events consistent until the next draw
+        self.chart_view.x = x;
+        self.chart_view.y = y;
+    }
 
-                frame.render_widget(ui::draw_title(), chunks[0]);
+    /// Left drag selects a region to zoom to, the wheel zooms around the
+    /// cursor and right drag pans.
+    fn handle_mouse(&mut self, event: MouseEvent) {
+        let cell = (event.column, event.row);
+        let view = self.chart_view;
+
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) if view.contains(cell.0, cell.1) => {
+                self.selection = Some((cell, cell));
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((_, end)) = self.selection.as_mut() {
+                    *end = cell;
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                let Some((start, end)) = self.selection.take() else {
+                    return;
+                };
+                let (Some(first), Some(second)) =
+                    (view.to_data(start.0, start.1), view.to_data(end.0, end.1))
+                else {
+                    return;
+                };
+                // a click without dragging keeps that axis as it is
+                let x = match start.0 == end.0 {
+                    true => view.x,
+                    false => (first.0.min(second.0), first.0.max(second.0)),
+                };
+                let y = match start.1 == end.1 {
+                    true => view.y,
+                    false => (first.1.min(second.1), first.1.max(second.1)),
+                };
+                if (x, y) != (view.x, view.y) {
+                    self.set_view(x, y);
+                    debug!("Zoomed to x: {x:?}, y: {y:?}");
+                }
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                if view.contains(cell.0, cell.1) =>
+            {
+                let Some((x, y)) = view.to_data(cell.0, cell.1) else {
+                    return;
+                };
+                let factor = match event.kind {
+                    MouseEventKind::ScrollUp => 0.8,
+                    _ => 1.25,
+                };
+                let zoom = |(min, max): (f64, f64), center: f64| {
+                    (
+                        center - (center - min) * factor,
+                        center + (max - center) * factor,
+                    )
+                };
+                self.set_view(zoom(view.x, x), zoom(view.y, y));
+            }
+            MouseEventKind::Down(MouseButton::Right) if view.contains(cell.0, cell.1) => {
+                self.pan_from = Some(cell);
+            }
+            MouseEventKind::Drag(MouseButton::Right) => {
+                let Some(from) = self.pan_from.replace(cell) else {
+                    return;
+                };
+                let (dx, dy) = view.cell_size();
+                // moving the mouse right drags the data right, the view left
+                let shift_x = -(cell.0 as f64 - from.0 as f64) * dx;
+                let shift_y = (cell.1 as f64 - from.1 as f64) * dy;
+                self.set_view(
+                    (view.x.0 + shift_x, view.x.1 + shift_x),
+                    (view.y.0 + shift_y, view.y.1 + shift_y),
+                );
             }
+            MouseEventKind::Up(MouseButton::Right) => self.pan_from = None,
+            _ => {}
         }
+    }
 
-        if let Some(log) = self.log_plot {
-            if let Some(spec) = self.spectra.as_mut() {
-                spec.plot_log = log;
-            }
+    /// Fits the Y-limits to the visible data in the current X range, or
+    /// returns to automatic limits if they were already fit.
+    fn autofit_y(&mut self) {
+        if self.ylims_fitted {
+            self.ylims.min = None;
+            self.ylims.max = None;
+            self.ylims_fitted = false;
+            info!("Y-limits set back to auto.");
+            return;
         }
 
-        frame.render_widget(
-            ui::draw_charts(self.spectra.as_ref(), &self.ylims),
-            chunks[1],
-        );
+        let Some(spec) = self.spectra.as_ref() else {
+            info!("No spectra to fit yet.");
+            return;
+        };
+        let plot_log = self.plot_in_db().unwrap_or(spec.plot_log);
+        fn plot_data(spec: &AutoSpectra, plot_log: bool) -> &[Vec<(f64, f64)>] {
+            match plot_log {
+                true => &spec.log_spectra,
+                false => &spec.spectra,
+            }
+        }
+        let xmin = self.xlims.get_min().unwrap_or(f64::NEG_INFINITY);
+        let xmax = self.xlims.get_max().unwrap_or(f64::INFINITY);
+
+        let (min, max) = spec
+            .ant_names
+            .iter()
+            .zip(plot_data(spec, plot_log))
+            .filter(|(name, _)| {
+                self.median_mode != MedianMode::Only && !self.hidden.contains(*name)
+            })
+            .map(|(_, trace)| trace)
+            .chain(
+                self.median
+                    .iter()
+                    .flat_map(|median| plot_data(median, plot_log)),
+            )
+            .chain(
+                self.max_hold_view
+                    .iter()
+                    .flat_map(|held| plot_data(held, plot_log)),
+            )
+            .flatten()
+            .filter(|(freq, val)| (xmin..=xmax).contains(freq) && val.is_finite())
+            .fold(
+                (f64::INFINITY, f64::NEG_INFINITY),
+                |(min, max), (_, val)| (min.min(*val), max.max(*val)),
+            );
 
-        cfg_if::cfg_if! {
-            if #[cfg(feature="lwa-na")]{
-                match self.show_stats{
-                    true =>{
-                        let log_chunks=   Layout::default()
-                        .direction(Direction::Horizontal)
-                        .constraints([Constraint::Percentage(60), Constraint::Min(20), Constraint::Min(20)].as_ref())
-                        .split(chunks[2]);
-
-                        // Logs
-                        frame.render_widget(ui::draw_logs(), log_chunks[0]);
-                        // stats
-                        frame.render_widget(self.saturations.as_ref().map(|x| x.as_table()).unwrap_or_default(), log_chunks[1]);
-                        // Body & Help
-                        frame.render_widget(ui::draw_help(), log_chunks[2]);
-                    },
-                    false =>{
-                        let log_chunks=   Layout::default()
-                        .direction(Direction::Horizontal)
-                        .constraints([Constraint::Percentage(80), Constraint::Min(20)].as_ref())
-                        .split(chunks[2]);
-
-                        // Logs
-                        frame.render_widget(ui::draw_logs(), log_chunks[0]);
-                        // Body & Help
-                        frame.render_widget(ui::draw_help(), log_chunks[1]);
+        if min > max {
+            info!("No data in view to fit.");
+            return;
+        }
 
-                    }
-                }
-            } else{
+        self.ylims.set_plotted(min, max, plot_log);
+        self.ylims_fitted = true;
+        self.ylims_locked = false;
+        info!("Y-limits fit to {min:.3} - {max:.3}.");
+    }
 
-                let log_chunks = Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints([Constraint::Percentage(80), Constraint::Min(20)].as_ref())
-                    .split(chunks[2]);
+    /// Holds the Y-axis at the range it is currently drawn with so it stops
+    /// following the data, or returns to automatic limits if already held.
+    fn lock_autoscale(&mut self) {
+        if self.ylims_locked {
+            self.ylims.min = None;
+            self.ylims.max = None;
+            self.ylims_locked = false;
+            info!("Y-limits following the data again.");
+            return;
+        }
 
-                // Logs
-                frame.render_widget(ui::draw_logs(), log_chunks[0]);
-                // Body & Help
-                frame.render_widget(ui::draw_help(), log_chunks[1]);
-            }
+        let Some(spec) = self.spectra.as_ref() else {
+            info!("No spectra to lock the scale to yet.");
+            return;
+        };
+        // the same bounds the chart falls back to for any unset limit
+        let plot_log = spec.plot_log;
+        let min = self
+            .ylims
+            .get_min(plot_log)
+            .unwrap_or_else(|| spec.ymin(plot_log));
+        let max = self
+            .ylims
+            .get_max(plot_log)
+            .unwrap_or_else(|| spec.ymax(plot_log));
+        if !(min.is_finite() && max.is_finite()) {
+            info!("No data in view to lock the scale to.");
+            return;
         }
 
-        match self.input_mode {
-            InputMode::Normal => {}
-            #[cfg(feature = "ovro")]
-            InputMode::AntennaInput => {
-                let input = Paragraph::new(self.input.as_str())
-                    .style(Style::default())
-                    .block(
-                        Block::default()
-                            .title("Enter Antenna Name")
-                            .borders(Borders::ALL),
-                    );
+        self.ylims.set_plotted(min, max, plot_log);
+        self.ylims_fitted = false;
+        self.ylims_locked = true;
+        info!("Y-limits locked at {min:.3} - {max:.3}.");
+    }
 
-                let area =
-                    ui::center_popup(chunks[1], Constraint::Length(20), Constraint::Length(3));
-                frame.render_widget(Clear, area); //this clears out the background
-                frame.render_widget(input, area);
+    /// Median of the plotted samples of the traces shown within the
+    /// frequency limits, `None` without any.
+    fn visible_center(&self) -> Option<f64> {
+        let spec = self.spectra.as_ref()?;
+        let traces = match self.plot_in_db().unwrap_or(spec.plot_log) {
+            true => &spec.log_spectra,
+            false => &spec.spectra,
+        };
+        let xmin = self.xlims.get_min().unwrap_or(f64::NEG_INFINITY);
+        let xmax = self.xlims.get_max().unwrap_or(f64::INFINITY);
+
+        median_of(
+            spec.ant_names
+                .iter()
+                .zip(traces)
+                .filter(|(name, _)| !self.hidden.contains(*name))
+                .flat_map(|(_, trace)| trace.iter())
+                .filter(|(freq, val)| (xmin..=xmax).contains(freq) && val.is_finite())
+                .map(|(_, val)| *val)
+                .collect(),
+        )
+    }
 
-                frame.set_cursor_position(Position::new(
-                    // Draw the cursor at the current position in the input field.
-                    // This position is can be controlled via the left and right arrow key
-                    area.x + self.character_index as u16 + 1,
-                    // Move one line down, from the border to the input line
-                    area.y + 1,
-                ));
-            }
-            #[cfg(feature = "ovro")]
-            InputMode::RemoveAntenna => {
-                let items: Vec<ListItem> = self
-                    .antenna_filter
-                    .items
-                    .iter()
-                    .map(|todo_item| ListItem::from(todo_item.clone()))
-                    .collect();
-                // render the List in the middle of the screen
-                let list = List::new(items)
-                    .highlight_style(SELECTED_STYLE)
-                    .highlight_symbol(">")
-                    .highlight_spacing(HighlightSpacing::Always)
-                    .block(
-                        Block::default()
-                            .title("Select Antenna")
-                            .borders(Borders::ALL),
-                    );
-                let area = ui::center_popup(chunks[1], Constraint::Length(20), Constraint::Max(20));
-                frame.render_widget(Clear, area); //this clears out the background
-                frame.render_stateful_widget(list, area, &mut self.antenna_filter.state);
-            }
-            InputMode::ChartLims => {
-                let outer_area =
-                    ui::center_popup(chunks[1], Constraint::Length(40), Constraint::Length(5));
+    /// Places the crosshair in the middle of the plotted range.
+    fn enter_crosshair(&mut self) {
+        let Some(spec) = self.spectra.as_ref() else {
+            info!("No spectra to place the crosshair on yet.");
+            return;
+        };
+        let xmin = self.xlims.get_min().unwrap_or(spec.freq_min);
+        let xmax = self.xlims.get_max().unwrap_or(spec.freq_max);
 
-                //this clears out the background
-                frame.render_widget(Clear, outer_area);
+        self.crosshair = Some(0.5 * (xmin + xmax));
+        self.move_crosshair(0);
 
-                let outter_block = Block::default()
-                    .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::LightCyan))
-                    .title("Set Y-limits (Tab to change focus)");
+        debug!("Entering crosshair mode.");
+        self.input_mode = InputMode::Crosshair;
+    }
 
-                let area = outter_block.inner(outer_area);
-                frame.render_widget(outter_block, outer_area);
+    /// Moves the crosshair by a number of channels of the trace it is on,
+    /// snapping it to the nearest sample.
+    fn move_crosshair(&mut self, channels: isize) {
+        let (Some(freq), Some(spec)) = (self.crosshair, self.spectra.as_ref()) else {
+            return;
+        };
 
-                let text_chunks = self.ylims.layout.split(area);
+        // traces can cover different bands (e.g. DR tunings)
+        // so step along the one under the crosshair
+        let Some(trace) = spec
+            .spectra
+            .iter()
+            .find(|trace| {
+                trace
+                    .first()
+                    .zip(trace.last())
+                    .is_some_and(|(first, last)| first.0 <= freq && freq <= last.0)
+            })
+            .or_else(|| spec.spectra.first())
+        else {
+            return;
+        };
 
-                for (textarea, chunk) in self.ylims.textareas.iter().zip(text_chunks.iter()) {
-                    frame.render_widget(textarea, *chunk);
-                }
+        if let Some(index) = nearest_index(trace, freq) {
+            let index = index.saturating_add_signed(channels).min(trace.len() - 1);
+            self.crosshair = Some(trace[index].0);
+        }
+    }
 
-                // frame.render_widget(input, area);
-                // frame.set_cursor_position(Position::new(
-                //     // Draw the cursor at the current position in the input field.
-                //     // This position is can be controlled via the left and right arrow key
-                //     area.x + self.character_index as u16 + 1,
-                //     // Move one line down, from the border to the input line
-                //     area.y + 1,
-                // ));
-                // TODO
-                // Make a pop up
-                // allow text input for limit
-            }
+    /// Copies the crosshair frequency, or the peak outside crosshair mode,
+    /// to the system clipboard, logging it when there is no clipboard.
+    fn copy_frequency(&mut self) {
+        let Some(spec) = self.spectra.as_ref() else {
+            info!("No spectra to read a frequency from yet.");
+            return;
+        };
+        let Some(freq) = self
+            .crosshair
+            .or_else(|| ui::find_peak(spec, &self.hidden, &self.xlims).map(|(_, freq, _)| freq))
+        else {
+            info!("No visible samples to read a frequency from.");
+            return;
+        };
+        let text = ui::format_x(freq, spec.x_unit);
+
+        match self.clipboard.set_text(&text) {
+            Ok(()) => info!("Copied {text} to the clipboard."),
+            Err(err) => info!("Frequency: {text} (unable to copy to the clipboard: {err})"),
         }
     }
 
@@ -693,42 +2916,60 @@ impl<'a> App<'a> {
         #[allow(unused_mut)]
         #[allow(unused_variables)]
         mut filter_recv: Receiver<Vec<String>>,
+        #[allow(unused_mut)]
+        #[allow(unused_variables)]
+        mut command_recv: Receiver<BackendCommand>,
+        #[cfg(feature = "ovro")] known_antennas: watch::Sender<Vec<String>>,
     ) -> BackendReturn {
         let (sender, recvr) = tokio::sync::mpsc::channel(30);
+        // only live sources report their status
+        #[allow(unused_variables)]
+        let (status_sender, status_recv) = tokio::sync::mpsc::channel(10);
 
-        match backend {
+        let task = match backend {
             #[cfg(not(any(feature = "ovro", feature = "lwa-na")))]
-            TuiType::Noop => {
-                tokio::spawn(async move {
-                    sender
-                        .send(AutoSpectra::new(
-                            vec!["Test".to_owned()],
-                            Array::linspace(0.0, 200.0, 5),
-                            arr2(&[[5.0, 3.0, 1.0, 4.0, 0.33]]),
-                            false,
-                        ))
-                        .await?;
-                    Ok::<(), Error>(())
-                });
-            }
+            TuiType::Noop => tokio::spawn(async move {
+                sender
+                    .send(AutoSpectra::new(
+                        vec!["Test".to_owned()],
+                        Array::linspace(0.0, 200.0, 5),
+                        arr2(&[[5.0, 3.0, 1.0, 4.0, 0.33]]),
+                        false,
+                    ))
+                    .await?;
+                Ok::<(), Error>(())
+            }),
             #[cfg(any(feature = "ovro", feature = "lwa-na"))]
             TuiType::File {
                 #[cfg(feature = "ovro")]
                 nspectra,
                 input_file,
+                #[cfg(feature = "ovro")]
+                x_unit,
+                #[cfg(feature = "ovro")]
+                freq_range,
+                #[cfg(feature = "lwa-na")]
+                pols,
+                #[cfg(feature = "lwa-na")]
+                center_freq,
+                ..
             } => {
                 cfg_if::cfg_if! {
                     if #[cfg(feature = "ovro")]{
-                        let mut data_loader = OvroDiskLoader::new(input_file);
-                        data_loader.filter_antenna(
-                            (0..nspectra)
-                                .map(|s| format!("{s}"))
-                                .collect::<Vec<_>>()
-                                .as_slice(),
-                        )?;
+                        let mut data_loader = OvroDiskLoader::new(input_file, x_unit)
+                            .with_freq_range(freq_range.map(|range| (range[0], range[1])))?;
+                        if let Some(nspectra) = nspectra {
+                            data_loader.filter_antenna(
+                                (0..nspectra)
+                                    .map(|s| format!("{s}"))
+                                    .collect::<Vec<_>>()
+                                    .as_slice(),
+                            )?;
+                        }
 
                     } else if #[cfg(feature = "lwa-na")] {
-                        let mut data_loader = NADiskLoader::new(input_file);
+                        let mut data_loader = NADiskLoader::new(input_file).with_center_freq(center_freq);
+                        data_loader.filter_antenna(&pols)?;
 
                     }
                 }
@@ -743,15 +2984,62 @@ impl<'a> App<'a> {
                         }
                     }
 
+                    // re-read the file whenever it is rewritten
                     #[cfg(feature = "ovro")]
-                    while let Some(filter) = filter_recv.recv().await {
-                        data_loader.filter_antenna(&filter)?;
-                        if let Some(spec) = data_loader.get_data().await {
-                            sender.send(spec).await?;
+                    {
+                        let mut file_check = tokio::time::interval(FILE_CHECK_INTERVAL);
+                        loop {
+                            tokio::select! {
+                                _ = file_check.tick() => {
+                                    if data_loader.has_changed() {
+                                        info!("{} changed, reloading.", data_loader.file().display());
+                                        if let Some(spec) = data_loader.get_data().await {
+                                            sender.send(spec).await?;
+                                        }
+                                    }
+                                }
+                                Some(filter) = filter_recv.recv() => {
+                                    data_loader.filter_antenna(&filter)?;
+                                    if let Some(spec) = data_loader.get_data().await {
+                                        sender.send(spec).await?;
+                                    }
+                                }
+                                else => break,
+                            }
+                        }
+                    }
+
+                    #[cfg(feature = "lwa-na")]
+                    while let Some(command) = command_recv.recv().await {
+                        match command {
+                            BackendCommand::Step(step) => {
+                                data_loader.step(step);
+                                if let Some(spec) = data_loader.get_data().await {
+                                    sender.send((spec, data_loader.get_stats())).await?;
+                                }
+                            }
+                            BackendCommand::CycleTuning => {
+                                data_loader.cycle_tunings();
+                                // re-read the current spectrum
+                                data_loader.step(0);
+                                if let Some(spec) = data_loader.get_data().await {
+                                    sender.send((spec, data_loader.get_stats())).await?;
+                                }
+                            }
+                            BackendCommand::ToggleStokes => {
+                                data_loader.toggle_stokes();
+                                data_loader.step(0);
+                                if let Some(spec) = data_loader.get_data().await {
+                                    sender.send((spec, data_loader.get_stats())).await?;
+                                }
+                            }
+                            BackendCommand::SetDelay(_) => {
+                                debug!("The poll delay only applies to live data.");
+                            }
                         }
                     }
                     Ok::<(), Error>(())
-                });
+                })
             }
             #[cfg(any(feature = "ovro", feature = "lwa-na"))]
             TuiType::Live {
@@ -761,17 +3049,50 @@ impl<'a> App<'a> {
                 data_recorder,
                 #[cfg(feature = "lwa-na")]
                 identity_file,
+                #[cfg(feature = "lwa-na")]
+                username,
+                #[cfg(feature = "lwa-na")]
+                auth,
+                #[cfg(feature = "lwa-na")]
+                pols,
+                #[cfg(feature = "lwa-na")]
+                center_freq,
                 delay,
+                #[cfg(feature = "ovro")]
+                x_unit,
+                #[cfg(feature = "ovro")]
+                freq_range,
+                #[cfg(feature = "ovro")]
+                etcd_endpoint,
+                #[cfg(feature = "ovro")]
+                spectra_shape,
+                ..
             } => {
                 cfg_if::cfg_if! {
                     if #[cfg(feature = "ovro")]{
-                        let mut data_loader = EtcdLoader::new("etcdv3service:2379").await?;
-                        data_loader.filter_antenna(&antenna)?;
+                        let spectra_shape = spectra_shape
+                            .map(|shape| SpectraShape::new(shape[0], shape[1], shape[2]))
+                            .transpose()?;
+                        let freq_range = freq_range.map(|range| (range[0], range[1]));
 
                     } else if #[cfg(feature = "lwa-na")] {
-                        let mut data_loader = DRLoader::new(&data_recorder, identity_file).with_context(|| {
-                            format!("Error Connecting to data recorder {data_recorder}")
-                        })?;
+                        let mut data_loaders = data_recorder
+                            .iter()
+                            .map(|host| {
+                                DRLoader::new(host, &identity_file, &username, auth)
+                                    .map(|loader| {
+                                        loader
+                                            .with_center_freq(center_freq)
+                                            .with_status(StatusReporter::new(host, status_sender.clone()))
+                                    })
+                                    .with_context(|| {
+                                        format!("Error Connecting to data recorder {host}")
+                                    })
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        for data_loader in data_loaders.iter_mut() {
+                            data_loader.filter_antenna(&pols)?;
+                        }
 
                     }
                 }
@@ -780,6 +3101,27 @@ impl<'a> App<'a> {
 
                     cfg_if::cfg_if! {
                         if #[cfg(feature = "ovro")]{
+                            // connect here so the interface is already up
+                            // while the configuration is fetched
+                            let loader = async {
+                                let mut loader = EtcdLoader::new(&etcd_endpoint, x_unit)
+                                    .await?
+                                    .with_freq_range(freq_range)?
+                                    .with_spectra_shape(spectra_shape);
+                                loader.filter_antenna(&antenna)?;
+                                Ok::<_, Error>(loader)
+                            }
+                            .await;
+                            let mut status = StatusReporter::new("etcd", status_sender);
+                            let mut data_loader = match loader {
+                                Ok(loader) => loader.with_status(status),
+                                Err(err) => {
+                                    log::error!("Unable to start the etcd source: {err:#}");
+                                    status.set(BackendStatus::Error);
+                                    return Err(err);
+                                }
+                            };
+                            known_antennas.send_replace(data_loader.antenna_names());
 
                             loop {
                                 tokio::select! {
@@ -793,22 +3135,56 @@ impl<'a> App<'a> {
                                         // force a tick now to update the data
                                         interval.reset_immediately();
                                     }
+                                    Some(command) = command_recv.recv() => {
+                                        match command {
+                                            BackendCommand::SetDelay(delay) => {
+                                                interval = tokio::time::interval_at(
+                                                    tokio::time::Instant::now() + delay,
+                                                    delay,
+                                                );
+                                            }
+                                        }
+                                    }
                                     else => break,
                                 }
                             }
                         } else  if #[cfg(feature="lwa-na")]{
+                            let mut axes_differ = false;
                             loop {
                                 tokio::select! {
                                     _ = interval.tick() => {
-                                        if let Some(spec) = data_loader.get_data().await {
-                                            sender.send((spec, data_loader.get_stats())).await?;
+                                        if let Some(data) = poll_recorders(&mut data_loaders, &mut axes_differ).await {
+                                            sender.send(data).await?;
                                         }
                                     },
                                     Some(filter) = filter_recv.recv() => {
-                                        data_loader.filter_antenna(&filter)?;
+                                        for data_loader in data_loaders.iter_mut() {
+                                            data_loader.filter_antenna(&filter)?;
+                                        }
                                         // force a tick now to update the data
                                         interval.reset_immediately();
                                     }
+                                    Some(command) = command_recv.recv() => {
+                                        match command {
+                                            BackendCommand::Step(_) => {
+                                                debug!("Stepping is only available when reading files.");
+                                            }
+                                            BackendCommand::CycleTuning => {
+                                                data_loaders.iter_mut().for_each(DRLoader::cycle_tunings);
+                                                interval.reset_immediately();
+                                            }
+                                            BackendCommand::ToggleStokes => {
+                                                data_loaders.iter_mut().for_each(DRLoader::toggle_stokes);
+                                                interval.reset_immediately();
+                                            }
+                                            BackendCommand::SetDelay(delay) => {
+                                                interval = tokio::time::interval_at(
+                                                    tokio::time::Instant::now() + delay,
+                                                    delay,
+                                                );
+                                            }
+                                        }
+                                    }
                                     else => break,
                                 }
                             }
@@ -831,22 +3207,179 @@ impl<'a> App<'a> {
                         }
                     }
                     Ok::<(), Error>(())
-                });
+                })
+            }
+            TuiType::Replay {
+                input_file,
+                speed,
+                looping,
+            } => {
+                let mut data_loader = ReplayLoader::new(&input_file, speed)?.with_looping(looping);
+                tokio::spawn(async move {
+                    loop {
+                        tokio::select! {
+                            spec = data_loader.get_data() => {
+                                let Some(spec) = spec else {
+                                    info!("Reached the end of the recording.");
+                                    break;
+                                };
+                                cfg_if::cfg_if! {
+                                    if #[cfg(feature = "lwa-na")]{
+                                        sender.send((spec, None)).await?;
+                                    } else {
+                                        sender.send(spec).await?;
+                                    }
+                                }
+                            }
+                            Some(filter) = filter_recv.recv() => {
+                                data_loader.filter_antenna(&filter)?;
+                            }
+                            Some(_) = command_recv.recv() => {
+                                debug!("Recordings are played back as they were received.");
+                            }
+                            else => break,
+                        }
+                    }
+                    Ok::<(), Error>(())
+                })
+            }
+        };
+        Ok((recvr, status_recv, task))
+    }
+
+    /// Colors the interface for a dark or light terminal background.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    /// Colors the interface and every popup with `theme`.
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.ylims.set_theme(theme);
+        self.xlims.set_theme(theme);
+        self.ref_level.set_theme(theme);
+        self.marker_input.set_theme(theme);
+        // the flags carry their colors
+        self.update_kurtosis_flags();
+    }
+
+    /// Starts with these y-axis limits, in plotted units with `None` for auto,
+    /// and plotting in dB (`log` true) or linear units instead of the data's default.
+    ///
+    /// The limits are only applied if either is given, and the data's default
+    /// decides their units when `log` is not.
+    pub fn plot_limits(mut self, ymin: Option<f64>, ymax: Option<f64>, log: Option<bool>) -> Self {
+        if log.is_some() {
+            self.log_plot = log;
+        }
+        if ymin.is_none() && ymax.is_none() {
+            return self;
+        }
+        match self.plot_in_db() {
+            Some(plot_log) => self.ylims.set_typed(ymin, ymax, plot_log),
+            None => self.launch_ylims = Some((ymin, ymax)),
+        }
+        self
+    }
+
+    /// Appends the saturation of every poll to `path` as it arrives, if given.
+    #[cfg(feature = "lwa-na")]
+    pub fn saturation_log(mut self, path: Option<PathBuf>) -> Result<Self> {
+        if let Some(path) = path {
+            let sat_log = SaturationLog::create(path)?;
+            info!("Logging saturation to {}.", sat_log.path().display());
+            self.saturation_log = Some(sat_log);
+        }
+        Ok(self)
+    }
+
+    /// Records every spectrum received to `path`, if given.
+    pub fn record(mut self, path: Option<PathBuf>) -> Result<Self> {
+        if let Some(path) = path {
+            info!("Recording spectra to {}.", path.display());
+            self.recorder = Some(replay::spawn_recorder(path)?);
+        }
+        Ok(self)
+    }
+
+    /// Loads the first spectra from the backend and renders them to a PNG
+    /// at `output`, or a timestamped file, without touching the terminal.
+    ///
+    /// Returns where the image was written.
+    pub async fn oneshot(mut self, output: Option<PathBuf>) -> Result<PathBuf> {
+        let (mut data_recv, _status_recv, task) = Self::spawn_backend(
+            self.data_backend.clone(),
+            self.filter_recv.take().context("Antenna Filter missing.")?,
+            self.command_recv
+                .take()
+                .context("Backend command channel missing.")?,
+            #[cfg(feature = "ovro")]
+            self.known_sender
+                .take()
+                .context("Known antenna channel missing.")?,
+        )
+        .await?;
+
+        self.backend_task = Some(task);
+        let data = tokio::time::timeout(ONESHOT_TIMEOUT, data_recv.recv()).await;
+        // only the first spectra are needed
+        self.stop_backend().await;
+        let data = data
+            .with_context(|| {
+                format!(
+                    "No spectra received within {} s.",
+                    ONESHOT_TIMEOUT.as_secs()
+                )
+            })?
+            .context("The backend stopped before sending any spectra.")?;
+        cfg_if::cfg_if! {
+            if #[cfg(feature="lwa-na")]{
+                let (data, _) = data;
             }
         }
-        Ok(recvr)
+        self.receive_spectra(data);
+
+        let plot_in_db = self.plot_in_db();
+        let spec = self
+            .spectra
+            .as_mut()
+            .context("No spectra to plot were received.")?;
+        if let Some(log) = plot_in_db {
+            spec.plot_log = log;
+        }
+        let log = spec.plot_log;
+
+        let path = output.unwrap_or_else(|| export::timestamped_path("png"));
+        export::write_png(
+            spec,
+            &path,
+            (self.xlims.get_min(), self.xlims.get_max()),
+            (self.ylims.get_min(log), self.ylims.get_max(log)),
+        )?;
+        Ok(path)
     }
 
     async fn init_streams(
         data_backend: TuiType,
         refresh_rate: Duration,
         filter_recv: Receiver<Vec<String>>,
-    ) -> Result<StreamMap<&'static str, Pin<Box<dyn Stream<Item = StreamReturn> + Send>>>> {
+        command_recv: Receiver<BackendCommand>,
+        #[cfg(feature = "ovro")] known_antennas: watch::Sender<Vec<String>>,
+    ) -> Result<(EventStreams, BackendTask)> {
         let mut stream = tokio_stream::StreamMap::new();
 
-        let data_recv = Self::spawn_backend(data_backend, filter_recv).await?;
+        let (data_recv, status_recv, task) = Self::spawn_backend(
+            data_backend,
+            filter_recv,
+            command_recv,
+            #[cfg(feature = "ovro")]
+            known_antennas,
+        )
+        .await?;
 
         let data_stream = Box::pin(ReceiverStream::new(data_recv).map(StreamReturn::Data));
+        let status_stream = Box::pin(ReceiverStream::new(status_recv).map(StreamReturn::Status));
 
         let tick_stream = {
             let mut tmp = tokio::time::interval(refresh_rate);
@@ -861,21 +3394,56 @@ impl<'a> App<'a> {
 
         stream.insert("input", reader);
         stream.insert("data", data_stream);
+        stream.insert("status", status_stream);
         stream.insert("tick", tick_stream);
-        Ok(stream)
+        Ok((stream, task))
+    }
+
+    /// Aborts the backend task and waits for it to wind down, dropping its
+    /// connections before the terminal is handed back.
+    async fn stop_backend(&mut self) {
+        let Some(task) = self.backend_task.take() else {
+            return;
+        };
+        task.abort();
+        match task.await {
+            Ok(Err(err)) => debug!("Backend stopped with an error: {err:#}"),
+            Err(err) if !err.is_cancelled() => debug!("Backend task failed: {err}"),
+            _ => debug!("Backend stopped."),
+        }
     }
 
     pub async fn run<W: Write>(
         mut self,
         terminal: &mut Terminal<CrosstermBackend<W>>,
     ) -> Result<()> {
-        let mut stream = Self::init_streams(
+        let (mut stream, task) = Self::init_streams(
             self.data_backend.clone(),
             self.refresh_rate,
             self.filter_recv.take().context("Antenna Filter missing.")?,
+            self.command_recv
+                .take()
+                .context("Backend command channel missing.")?,
+            #[cfg(feature = "ovro")]
+            self.known_sender
+                .take()
+                .context("Known antenna channel missing.")?,
         )
         .await?;
+        self.backend_task = Some(task);
+
+        // the backend is stopped however the session ends
+        let result = self.event_loop(terminal, &mut stream).await;
+        self.stop_backend().await;
+        result
+    }
 
+    /// Handles input and data until the user quits or an error ends the session.
+    async fn event_loop<W: Write>(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<W>>,
+        stream: &mut EventStreams,
+    ) -> Result<()> {
         'plotting_loop: while let Some((_key, event)) = stream.next().await {
             match event {
                 StreamReturn::Action(maybe_event) => {
@@ -887,7 +3455,17 @@ impl<'a> App<'a> {
                             InputMode::Normal => {
                                 if let Some(action) = Action::from_event(event) {
                                     match action {
-                                        Action::Break => break 'plotting_loop,
+                                        Action::Break
+                                            if self.pending_tasks.load(Ordering::SeqCst) > 0 =>
+                                        {
+                                            debug!("Exports still running, asking to quit.");
+                                            self.input_mode = InputMode::ConfirmQuit;
+                                        }
+                                        Action::Break => {
+                                            self.save_state();
+                                            break 'plotting_loop;
+                                        }
+                                        Action::SaveDefaults => self.save_defaults(),
                                         #[cfg(feature = "ovro")]
                                         Action::NewAnt => {
                                             debug!("Entering New Antenna mode.");
@@ -898,18 +3476,180 @@ impl<'a> App<'a> {
                                             debug!("Entering Delete antenna mode.");
                                             self.input_mode = InputMode::RemoveAntenna
                                         }
+                                        #[cfg(feature = "ovro")]
+                                        Action::SavePreset => {
+                                            debug!("Entering preset name mode.");
+                                            self.input.clear();
+                                            self.reset_cursor();
+                                            self.input_mode = InputMode::PresetName;
+                                        }
+                                        #[cfg(feature = "ovro")]
+                                        Action::NextPreset => self.next_filter_preset().await?,
                                         Action::ToggleLog => {
                                             // toggle the switch
                                             if let Some(log) = self.log_plot.as_mut() {
                                                 *log = !*log;
                                             }
                                         }
+                                        Action::SplitScales => {
+                                            self.split_scales = !self.split_scales
+                                        }
+                                        Action::ToggleLogAxis => {
+                                            self.log_axis = !self.log_axis;
+                                            if self.log_plot == Some(true) {
+                                                info!("The log axis only applies to raw data, press l to switch.");
+                                            }
+                                        }
                                         #[cfg(feature = "lwa-na")]
                                         Action::ToggleStats => self.show_stats = !self.show_stats,
+                                        #[cfg(feature = "lwa-na")]
+                                        Action::ToggleQuality => {
+                                            self.show_quality = !self.show_quality
+                                        }
+                                        Action::MoreVerbose => self.change_log_level(true),
+                                        Action::LessVerbose => self.change_log_level(false),
+                                        Action::ToggleStatsPanel => {
+                                            self.show_stats_panel = !self.show_stats_panel
+                                        }
+                                        Action::AutofitY => self.autofit_y(),
+                                        Action::ResetView => self.reset_view(),
+                                        Action::LockAutoscale => self.lock_autoscale(),
                                         Action::ChangeYLims => {
                                             debug!("Entering Ylimit changing mode.");
                                             self.input_mode = InputMode::ChartLims
                                         }
+                                        #[cfg(feature = "lwa-na")]
+                                        Action::NextSpectrum => {
+                                            self.send_command(BackendCommand::Step(1))
+                                        }
+                                        #[cfg(feature = "lwa-na")]
+                                        Action::PreviousSpectrum => {
+                                            self.send_command(BackendCommand::Step(-1))
+                                        }
+                                        #[cfg(feature = "lwa-na")]
+                                        Action::CycleTuning => {
+                                            self.send_command(BackendCommand::CycleTuning)
+                                        }
+                                        #[cfg(feature = "lwa-na")]
+                                        Action::ToggleStokes => {
+                                            self.send_command(BackendCommand::ToggleStokes)
+                                        }
+                                        Action::TogglePause => {
+                                            self.paused = !self.paused;
+                                            info!(
+                                                "Display {}.",
+                                                if self.paused { "paused" } else { "resumed" }
+                                            );
+                                        }
+                                        Action::ToggleSpread => self.toggle_spread(),
+                                        Action::IncreaseAveraging => self.change_averaging(true),
+                                        Action::DecreaseAveraging => self.change_averaging(false),
+                                        Action::ToggleWaterfall => {
+                                            self.show_waterfall = !self.show_waterfall
+                                        }
+                                        Action::ChangeXLims => {
+                                            debug!("Entering Xlimit changing mode.");
+                                            self.input_mode = InputMode::FreqLims
+                                        }
+                                        Action::Crosshair => self.enter_crosshair(),
+                                        Action::CopyFrequency => self.copy_frequency(),
+                                        Action::ExportCsv => self.export_csv(),
+                                        Action::SaveImage => self.save_image(),
+                                        #[cfg(feature = "ovro")]
+                                        Action::ExportNpy => self.export_npy(),
+                                        #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+                                        Action::IncreaseDelay => self.change_poll_delay(true),
+                                        #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+                                        Action::DecreaseDelay => self.change_poll_delay(false),
+                                        Action::ToggleKurtosis => self.toggle_kurtosis(),
+                                        Action::ToggleOutliers => {
+                                            self.show_outliers = !self.show_outliers;
+                                            self.update_outliers();
+                                        }
+                                        Action::IncreaseOutlierSigma => {
+                                            self.change_outlier_sigma(true)
+                                        }
+                                        Action::DecreaseOutlierSigma => {
+                                            self.change_outlier_sigma(false)
+                                        }
+                                        Action::IncreaseSmoothing => self.change_smoothing(true),
+                                        Action::DecreaseSmoothing => self.change_smoothing(false),
+                                        Action::ToggleMaxHold => self.toggle_max_hold(),
+                                        Action::ResetMaxHold => {
+                                            info!("Resetting the max hold.");
+                                            self.max_hold = None;
+                                            self.max_hold_view = None;
+                                        }
+                                        Action::CaptureReference => self.capture_reference(),
+                                        Action::CycleReference => self.cycle_reference_mode(),
+                                        Action::CycleMedian => {
+                                            self.median_mode = self.median_mode.next();
+                                            self.update_median();
+                                        }
+                                        Action::SetRefLevel => {
+                                            debug!("Entering reference level mode.");
+                                            self.input_mode = InputMode::RefLevel
+                                        }
+                                        Action::AddMarker => {
+                                            debug!("Entering marker mode.");
+                                            self.input_mode = InputMode::Marker
+                                        }
+                                        Action::ToggleLegend => {
+                                            self.show_legend = !self.show_legend
+                                        }
+                                        Action::ToggleTheme => {
+                                            self.set_theme(self.theme.next());
+                                            info!("Using the {:?} theme.", self.theme);
+                                        }
+                                        Action::CyclePalette => {
+                                            self.palette = self.palette.next();
+                                            info!("Using the {:?} palette.", self.palette);
+                                        }
+                                        Action::CycleMarker => {
+                                            self.trace_marker = self.trace_marker.next();
+                                            info!("Drawing traces with {:?}.", self.trace_marker);
+                                        }
+                                        Action::ToggleScatter => {
+                                            self.scatter = !self.scatter;
+                                            match self.scatter {
+                                                true => info!("Drawing traces as points."),
+                                                false => info!("Drawing traces as lines."),
+                                            }
+                                        }
+                                        Action::ToggleFitWidth => {
+                                            self.fit_width = !self.fit_width;
+                                            match self.fit_width {
+                                                true => info!("Thinning the traces to the chart width, keeping the peaks."),
+                                                false => info!("Drawing every sample."),
+                                            }
+                                        }
+                                        Action::ToggleNoiseFloor => self.toggle_noise_floor(),
+                                        Action::ShowHelp => {
+                                            self.help_scroll = 0;
+                                            self.input_mode = InputMode::Help
+                                        }
+                                        Action::SoloNext => self.cycle_focus(1),
+                                        Action::SoloPrevious => self.cycle_focus(-1),
+                                        Action::ShowAll => {
+                                            info!("Coloring all antennas.");
+                                            self.focused = None;
+                                        }
+                                        Action::Compare if self.compare.is_some() => {
+                                            info!("Comparison off.");
+                                            self.compare = None;
+                                            self.refresh_view();
+                                        }
+                                        Action::Compare => {
+                                            debug!("Entering comparison mode.");
+                                            self.compare_first = None;
+                                            self.compare_state.select_first();
+                                            self.input_mode = InputMode::Compare
+                                        }
+                                        Action::HideAntennas => {
+                                            debug!("Entering antenna visibility mode.");
+                                            self.visibility_state.select_first();
+                                            self.input_mode = InputMode::HideAntenna
+                                        }
                                     }
                                 }
                             }
@@ -929,6 +3669,21 @@ impl<'a> App<'a> {
                             // ignore other inputs in text mode
                             InputMode::AntennaInput => {}
 
+                            #[cfg(feature = "ovro")]
+                            InputMode::PresetName if event.kind == KeyEventKind::Press => {
+                                match event.code {
+                                    KeyCode::Enter => self.save_filter_preset(),
+                                    KeyCode::Char(to_insert) => self.enter_char(to_insert),
+                                    KeyCode::Backspace => self.delete_char(),
+                                    KeyCode::Left => self.move_cursor_left(),
+                                    KeyCode::Right => self.move_cursor_right(),
+                                    KeyCode::Esc => self.input_mode = InputMode::Normal,
+                                    _ => {}
+                                }
+                            }
+                            #[cfg(feature = "ovro")]
+                            InputMode::PresetName => {}
+
                             #[cfg(feature = "ovro")]
                             // Remove an antenna from the filter
                             InputMode::RemoveAntenna if event.kind == KeyEventKind::Press => {
@@ -936,6 +3691,16 @@ impl<'a> App<'a> {
                                     KeyCode::Esc => self.input_mode = InputMode::Normal,
                                     KeyCode::Char('j') | KeyCode::Down => self.select_next(),
                                     KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
+                                    KeyCode::PageDown => self
+                                        .antenna_filter
+                                        .state
+                                        .scroll_down_by(self.antenna_filter.page),
+                                    KeyCode::PageUp => self
+                                        .antenna_filter
+                                        .state
+                                        .scroll_up_by(self.antenna_filter.page),
+                                    KeyCode::Home => self.antenna_filter.state.select_first(),
+                                    KeyCode::End => self.antenna_filter.state.select_last(),
                                     KeyCode::Enter => {
                                         self.remove_antenna().await?;
                                     }
@@ -962,7 +3727,17 @@ impl<'a> App<'a> {
                                             self.input_mode = InputMode::Normal;
                                         }
                                         KeyCode::Enter if self.ylims.is_valid => {
-                                            self.ylims.update_vals(self.log_plot.unwrap_or(false));
+                                            let center = self
+                                                .ylims
+                                                .has_span()
+                                                .then(|| self.visible_center())
+                                                .flatten();
+                                            self.ylims.update_vals(
+                                                self.plot_in_db().unwrap_or(false),
+                                                center,
+                                            );
+                                            self.ylims_fitted = false;
+                                            self.ylims_locked = false;
                                             self.ylims.reset_blocks();
                                             debug!("Returning to normal mode.");
 
@@ -981,18 +3756,192 @@ impl<'a> App<'a> {
                                     }
                                 }
                             }
+                            InputMode::FreqLims => {
+                                if event.kind == KeyEventKind::Press {
+                                    match event.code {
+                                        KeyCode::Tab => {
+                                            // switch focus between min and max boxes
+                                            self.xlims.change_focus();
+                                        }
+                                        KeyCode::Esc => {
+                                            // return to normal mode don't do anything
+                                            self.xlims.clear();
+                                            self.xlims.reset_blocks();
+
+                                            debug!("Returning to normal mode.");
+                                            self.input_mode = InputMode::Normal;
+                                        }
+                                        KeyCode::Enter if self.xlims.is_valid => {
+                                            // if valid input update the limits
+                                            self.xlims.update_vals();
+                                            self.xlims.reset_blocks();
+                                            debug!("Returning to normal mode.");
+
+                                            self.input_mode = InputMode::Normal;
+                                        }
+                                        _ => {
+                                            if self.xlims.input(event) {
+                                                self.xlims.validate();
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            InputMode::Crosshair if event.kind == KeyEventKind::Press => {
+                                // shift moves in larger steps
+                                let step = match event.modifiers.contains(KeyModifiers::SHIFT) {
+                                    true => 10,
+                                    false => 1,
+                                };
+                                match event.code {
+                                    KeyCode::Left => self.move_crosshair(-step),
+                                    KeyCode::Right => self.move_crosshair(step),
+                                    KeyCode::Char('X') => self.copy_frequency(),
+                                    KeyCode::Esc | KeyCode::Char('c') => {
+                                        self.crosshair = None;
+                                        debug!("Returning to normal mode.");
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            // ignore key releases in crosshair mode
+                            InputMode::Crosshair => {}
+
+                            InputMode::HideAntenna if event.kind == KeyEventKind::Press => {
+                                match event.code {
+                                    KeyCode::Esc | KeyCode::Char('v') => {
+                                        debug!("Returning to normal mode.");
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    KeyCode::Char('j') | KeyCode::Down => {
+                                        self.visibility_state.select_next()
+                                    }
+                                    KeyCode::Char('k') | KeyCode::Up => {
+                                        self.visibility_state.select_previous()
+                                    }
+                                    KeyCode::Enter | KeyCode::Char(' ') => self.toggle_hidden(),
+                                    _ => {}
+                                }
+                            }
+                            InputMode::HideAntenna => {}
+
+                            InputMode::Compare if event.kind == KeyEventKind::Press => {
+                                match event.code {
+                                    KeyCode::Esc | KeyCode::Char('D') => {
+                                        debug!("Returning to normal mode.");
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    KeyCode::Char('j') | KeyCode::Down => {
+                                        self.compare_state.select_next()
+                                    }
+                                    KeyCode::Char('k') | KeyCode::Up => {
+                                        self.compare_state.select_previous()
+                                    }
+                                    KeyCode::Enter | KeyCode::Char(' ') => {
+                                        if self.pick_compared() {
+                                            self.input_mode = InputMode::Normal;
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            InputMode::Compare => {}
+
+                            InputMode::Help if event.kind == KeyEventKind::Press => {
+                                match event.code {
+                                    KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    KeyCode::Char('j') | KeyCode::Down => self.help_scroll += 1,
+                                    KeyCode::Char('k') | KeyCode::Up => {
+                                        self.help_scroll = self.help_scroll.saturating_sub(1)
+                                    }
+                                    KeyCode::PageDown => self.help_scroll += 10,
+                                    KeyCode::PageUp => {
+                                        self.help_scroll = self.help_scroll.saturating_sub(10)
+                                    }
+                                    KeyCode::Home => self.help_scroll = 0,
+                                    _ => {}
+                                }
+                            }
+                            InputMode::Help => {}
+
+                            InputMode::RefLevel if event.kind == KeyEventKind::Press => {
+                                match event.code {
+                                    KeyCode::Esc => {
+                                        self.ref_level.clear();
+                                        debug!("Returning to normal mode.");
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    KeyCode::Enter if self.ref_level.is_valid => {
+                                        self.apply_ref_level();
+                                        debug!("Returning to normal mode.");
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    _ => {
+                                        if self.ref_level.input(event) {
+                                            self.ref_level.validate();
+                                        }
+                                    }
+                                }
+                            }
+                            InputMode::RefLevel => {}
+
+                            InputMode::Marker if event.kind == KeyEventKind::Press => {
+                                match event.code {
+                                    KeyCode::Esc => {
+                                        self.marker_input.clear();
+                                        debug!("Returning to normal mode.");
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    KeyCode::Enter if self.marker_input.is_valid => {
+                                        self.add_marker();
+                                        debug!("Returning to normal mode.");
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    _ => {
+                                        if self.marker_input.input(event) {
+                                            self.marker_input.validate();
+                                        }
+                                    }
+                                }
+                            }
+                            InputMode::Marker => {}
+
+                            InputMode::ConfirmQuit if event.kind == KeyEventKind::Press => {
+                                match event.code {
+                                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                                        self.save_state();
+                                        break 'plotting_loop;
+                                    }
+                                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                                        self.input_mode = InputMode::Normal;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            InputMode::ConfirmQuit => {}
                         },
-                        // we are not interested in Focuses and mouse movements
+                        Ok(Event::Mouse(event)) if self.input_mode == InputMode::Normal => {
+                            self.handle_mouse(event)
+                        }
+                        Ok(Event::Resize(width, height)) => {
+                            debug!("Terminal resized to {width}x{height}.");
+                            // mouse positions from before no longer line up with the chart
+                            self.selection = None;
+                            self.pan_from = None;
+                            // repaint everything now instead of diffing against the old size
+                            terminal.clear()?;
+                        }
+                        // we are not interested in Focuses and other mouse events
                         Ok(_) => {}
                     }
                 }
                 #[cfg(feature = "lwa-na")]
                 StreamReturn::Data((data, new_stats)) => {
-                    info!("Received New autosprectra.");
-                    if self.log_plot.is_none() {
-                        self.log_plot = Some(data.plot_log);
-                    }
-                    self.spectra.replace(data);
+                    let timestamp = data.timestamp;
+                    self.receive_spectra(data);
 
                     if let Some(new_stats) = new_stats {
                         match self.saturations.as_mut() {
@@ -1001,17 +3950,36 @@ impl<'a> App<'a> {
                                 self.saturations.replace(new_stats);
                             }
                         }
+
+                        if let (Some(stats), Some(sat_log)) =
+                            (self.saturations.as_ref(), self.saturation_log.as_mut())
+                        {
+                            let epoch = timestamp
+                                .or_else(|| hifitime::Epoch::now().ok())
+                                .unwrap_or_default();
+                            if let Err(err) = sat_log.append(stats, epoch) {
+                                log::warn!("Stopped the saturation log: {err:#}");
+                                self.saturation_log = None;
+                            }
+                        }
                     }
                 }
                 #[cfg(not(feature = "lwa-na"))]
-                StreamReturn::Data(data) => {
-                    info!("Received New autosprectra.");
-                    if self.log_plot.is_none() {
-                        self.log_plot = Some(data.plot_log);
+                StreamReturn::Data(data) => self.receive_spectra(data),
+                StreamReturn::Status((source, status)) => {
+                    match self
+                        .backend_status
+                        .iter_mut()
+                        .find(|(name, _)| *name == source)
+                    {
+                        Some((_, current)) => *current = status,
+                        None => self.backend_status.push((source, status)),
                     }
-                    self.spectra.replace(data);
                 }
-                StreamReturn::Tick => {}
+                StreamReturn::Tick => {
+                    self.ticks = self.ticks.wrapping_add(1);
+                    self.check_stale();
+                }
             }
 
             terminal.draw(|frame| self.draw(frame))?;
@@ -1020,3 +3988,63 @@ impl<'a> App<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(expected: f64, val: Option<f64>) {
+        let val = val.expect("limit should be set");
+        assert!(
+            (expected - val).abs() < 1e-9,
+            "expected {expected}, got {val}"
+        );
+    }
+
+    #[test]
+    fn ylims_db_round_trip() {
+        let mut ylims = Ylims::new();
+        ylims.textareas[0].insert_str("-10");
+        ylims.textareas[1].insert_str("20");
+        ylims.update_vals(true, None);
+
+        assert_close(-10.0, ylims.get_min(true));
+        assert_close(20.0, ylims.get_max(true));
+        assert_close(0.1, ylims.get_min(false));
+        assert_close(100.0, ylims.get_max(false));
+
+        // entering the linear values shown brings back the same dB limits
+        let (min, max) = (ylims.get_min(false), ylims.get_max(false));
+        ylims.set_typed(min, max, false);
+        assert_close(-10.0, ylims.get_min(true));
+        assert_close(20.0, ylims.get_max(true));
+    }
+
+    #[test]
+    fn ylims_swapped() {
+        let mut ylims = Ylims::new();
+        ylims.set_typed(Some(20.0), Some(-10.0), true);
+        for plot_log in [true, false] {
+            assert!(ylims.get_min(plot_log) <= ylims.get_max(plot_log));
+        }
+        assert_close(-10.0, ylims.get_min(true));
+        assert_close(20.0, ylims.get_max(true));
+
+        // limits with no dB equivalent stay in order
+        ylims.set_typed(Some(0.0), Some(-1.0), false);
+        assert_eq!(Some(f64::NEG_INFINITY), ylims.get_min(true));
+        assert!(ylims.get_min(true) <= ylims.get_max(true));
+    }
+
+    #[test]
+    fn ylims_one_sided() {
+        let mut ylims = Ylims::new();
+        ylims.set_typed(Some(5.0), None, true);
+        assert_close(5.0, ylims.get_min(true));
+        assert_eq!(None, ylims.get_max(true));
+
+        ylims.set_typed(None, Some(-3.0), true);
+        assert_eq!(None, ylims.get_min(true));
+        assert_close(-3.0, ylims.get_max(true));
+    }
+}
@@ -0,0 +1,92 @@
+use ratatui::style::Color;
+
+/// Distinct colors for a handful of traces
+const CATEGORICAL: [(u8, u8, u8); 8] = [
+    (31, 119, 180),
+    (255, 127, 14),
+    (44, 160, 44),
+    (214, 39, 40),
+    (148, 103, 189),
+    (23, 190, 207),
+    (227, 119, 194),
+    (188, 189, 34),
+];
+
+/// Color stops of a viridis-like ramp, from first to last trace.
+const VIRIDIS: [(u8, u8, u8); 5] = [
+    (72, 40, 120),
+    (62, 74, 137),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+
+/// How trace colors are picked from the antenna index.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    /// Categorical colors for up to 8 traces, the ramp for more
+    #[default]
+    Auto,
+    /// A fixed set of distinct colors, repeated after 8 traces
+    Categorical,
+    /// A viridis-like ramp spread over all traces
+    Viridis,
+    /// Terminal color indices spread over all traces
+    Indexed,
+}
+impl Palette {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::Categorical,
+            Self::Categorical => Self::Viridis,
+            Self::Viridis => Self::Indexed,
+            Self::Indexed => Self::Auto,
+        }
+    }
+
+    /// Color of the trace at `index` out of `count` traces.
+    pub fn color(&self, index: usize, count: usize) -> Color {
+        match self {
+            Self::Auto if count <= CATEGORICAL.len() => Self::Categorical.color(index, count),
+            Self::Auto => Self::Viridis.color(index, count),
+            Self::Categorical => {
+                let (r, g, b) = CATEGORICAL[index % CATEGORICAL.len()];
+                Color::Rgb(r, g, b)
+            }
+            Self::Viridis => {
+                let frac = match count {
+                    0 | 1 => 0.0,
+                    _ => index as f64 / (count - 1) as f64,
+                };
+                ramp(&VIRIDIS, frac)
+            }
+            Self::Indexed => {
+                Color::Indexed((((index + 1) as f32 / count.max(1) as f32) * 255.0) as u8)
+            }
+        }
+    }
+}
+
+/// Linearly interpolates between the color stops for a value in [0, 1].
+pub(crate) fn ramp(stops: &[(u8, u8, u8)], frac: f64) -> Color {
+    let scaled = frac.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let index = (scaled.floor() as usize).min(stops.len() - 2);
+    let t = scaled - index as f64;
+
+    let (r0, g0, b0) = stops[index];
+    let (r1, g1, b1) = stops[index + 1];
+    let lerp = |a: u8, b: u8| (a as f64 + t * (b as f64 - a as f64)).round() as u8;
+
+    Color::Rgb(lerp(r0, r1), lerp(g0, g1), lerp(b0, b1))
+}
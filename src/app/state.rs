@@ -0,0 +1,74 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::config_dir;
+
+/// View settings that are remembered between runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SavedState {
+    /// Lower Y-limit in absolute units
+    pub ymin: Option<f64>,
+    /// Upper Y-limit in absolute units
+    pub ymax: Option<f64>,
+    pub log_plot: Option<bool>,
+}
+impl SavedState {
+    /// Location of the state file, `state.toml` in the [config_dir].
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("state.toml"))
+    }
+
+    /// Reads the saved state, falling back to the defaults if the file
+    /// is missing or cannot be parsed.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read {}", path.display()))
+            .and_then(|text| {
+                toml::from_str(&text).with_context(|| format!("Unable to parse {}", path.display()))
+            }) {
+            Ok(state) => state,
+            Err(err) => {
+                log::warn!("{err:#}. Using default settings.");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().context("Unable to determine the config directory.")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Unable to create {}", parent.display()))?;
+        }
+
+        let text = toml::to_string(self).context("Unable to serialize view settings.")?;
+        fs::write(&path, text).with_context(|| format!("Unable to write {}", path.display()))?;
+
+        Ok(())
+    }
+
+    /// Removes the state file, if there is one.
+    pub fn clear() -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Unable to remove {}", path.display()))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use ratatui::style::{Color, Modifier, Style};
+
+/// Colors of everything but the traces, for dark or light terminal backgrounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Theme {
+    /// Light text on a dark background
+    #[default]
+    Dark,
+    /// Dark text on a light background
+    Light,
+}
+impl Theme {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Dark,
+        }
+    }
+
+    /// Body text, borders and lines drawn over the traces like the median.
+    pub fn text(self) -> Color {
+        match self {
+            Self::Dark => Color::White,
+            Self::Light => Color::Black,
+        }
+    }
+
+    /// Secondary text, e.g. help descriptions, values and axis labels.
+    pub fn muted(self) -> Color {
+        match self {
+            Self::Dark => Color::Gray,
+            Self::Light => Color::DarkGray,
+        }
+    }
+
+    /// Inactive elements, like dimmed traces and hidden antennas.
+    pub fn dim(self) -> Color {
+        match self {
+            Self::Dark => Color::DarkGray,
+            Self::Light => Color::Gray,
+        }
+    }
+
+    /// Key names, table labels and popup borders.
+    pub fn accent(self) -> Color {
+        match self {
+            Self::Dark => Color::LightCyan,
+            Self::Light => Color::Blue,
+        }
+    }
+
+    /// Panel titles.
+    pub fn heading(self) -> Style {
+        let color = match self {
+            Self::Dark => Color::Cyan,
+            Self::Light => Color::Magenta,
+        };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    /// Warnings, yellow is unreadable on a light background.
+    pub fn warning(self) -> Color {
+        match self {
+            Self::Dark => Color::Yellow,
+            Self::Light => Color::Rgb(160, 100, 0),
+        }
+    }
+
+    /// Valid input, healthy sources and good data.
+    pub fn good(self) -> Color {
+        match self {
+            Self::Dark => Color::LightGreen,
+            Self::Light => Color::Green,
+        }
+    }
+
+    /// Invalid input, failures and bad data.
+    pub fn error(self) -> Color {
+        match self {
+            Self::Dark => Color::LightRed,
+            Self::Light => Color::Red,
+        }
+    }
+
+    /// Frequency markers and their labels.
+    pub fn marker(self) -> Color {
+        self.warning()
+    }
+
+    /// Background of the shaded RFI bands.
+    pub fn band(self) -> Color {
+        match self {
+            Self::Dark => Color::Rgb(60, 30, 70),
+            Self::Light => Color::Rgb(235, 220, 240),
+        }
+    }
+
+    /// Channels with spectral kurtosis far above or below the median.
+    pub fn kurtosis(self, high: bool) -> Color {
+        match (self, high) {
+            (_, true) => Color::Magenta,
+            (Self::Dark, false) => Color::Cyan,
+            (Self::Light, false) => Color::Blue,
+        }
+    }
+
+    /// Background of the log pane.
+    pub fn background(self) -> Color {
+        match self {
+            Self::Dark => Color::Black,
+            Self::Light => Color::Reset,
+        }
+    }
+
+    /// The highlighted row of a list.
+    pub fn selected(self) -> Style {
+        let background = match self {
+            Self::Dark => Color::Gray,
+            Self::Light => Color::LightBlue,
+        };
+        Style::new().bg(background).add_modifier(Modifier::BOLD)
+    }
+}
@@ -1,54 +1,135 @@
-use ndarray::Array;
+use std::{
+    collections::{HashSet, VecDeque},
+    time::Instant,
+};
+
+use hifitime::{Duration, Epoch};
+use log::LevelFilter;
 use ratatui::layout::{Flex, Layout, Rect};
 use ratatui::{
     layout::{Alignment, Constraint},
     style::{Color, Modifier, Style},
-    symbols,
-    text::Span,
-    widgets::{Axis, Block, BorderType, Borders, Chart, Dataset, GraphType, Paragraph, Table},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Cell, Padding, Paragraph, Row, Table},
 };
 use tui_logger::TuiLoggerWidget;
 
-use crate::{app::Ylims, loader::AutoSpectra, Action};
+use crate::{
+    app::{
+        chart::SpectrumChart, palette::Palette, theme::Theme, waterfall::Waterfall, Xlims, Ylims,
+    },
+    loader::{AutoSpectra, BackendStatus, StatusUpdate, TraceStats, XUnit},
+    Action,
+};
+
+#[cfg(feature = "lwa-na")]
+use crate::loader::{
+    north_arm::{SaturationStats, Stats},
+    DataQuality,
+};
 
-pub(crate) fn draw_title<'a, P: AsRef<str>>(#[cfg(feature = "lwa-na")] name: P) -> Paragraph<'a> {
+/// The title bar, flashing a warning while live data is `stale`.
+pub(crate) fn draw_title<'a, P: AsRef<str>>(
+    #[cfg(feature = "lwa-na")] name: P,
+    #[cfg(feature = "lwa-na")] beams: &[u8],
+    #[cfg(feature = "lwa-na")] tunings: &[(u8, f64)],
+    status: &[StatusUpdate],
+    paused: bool,
+    timestamp: Option<Epoch>,
+    poll_delay: Option<std::time::Duration>,
+    stale: Option<std::time::Duration>,
+    theme: Theme,
+) -> Paragraph<'a> {
     cfg_if::cfg_if! {
         if #[cfg(feature="lwa-na")]{
-            let text = format!("Spectrum Tui! {}", name.as_ref());
+            let mut text = format!("Spectrum Tui! {}", name.as_ref());
+            match beams {
+                [] => {}
+                [beam] => text.push_str(&format!(" (beam {beam})")),
+                beams => {
+                    let beams = beams.iter().map(u8::to_string).collect::<Vec<_>>();
+                    text.push_str(&format!(" (beams {})", beams.join(", ")))
+                }
+            }
+            // the band each tuning covers, by its center frequency
+            for (number, center) in tunings {
+                text.push_str(&format!(" | T{number} {center:.3} MHz"));
+            }
         } else{
-            let text = "Spectrum Tui!!".to_owned();
+            let mut text = "Spectrum Tui!!".to_owned();
         }
     }
-    Paragraph::new(text)
-        .style(Style::default().fg(Color::LightCyan))
+    if let Some(timestamp) = timestamp {
+        text.push_str(&format!(
+            " | {}",
+            timestamp.round(Duration::from_seconds(1.0))
+        ));
+    }
+    if let Some(delay) = poll_delay {
+        text.push_str(&format!(" | every {:.1}s", delay.as_secs_f64()));
+    }
+    if paused {
+        text.push_str(" [PAUSED]");
+    }
+    let style = match stale {
+        Some(stale) => {
+            text.push_str(&format!(" | STALE DATA ({:.0}s)", stale.as_secs_f64()));
+            // alternate every half second, the title is redrawn every tick
+            match (stale.as_millis() / 500) % 2 {
+                0 => Style::default()
+                    .fg(theme.error())
+                    .add_modifier(Modifier::BOLD),
+                _ => Style::default()
+                    .fg(theme.error())
+                    .add_modifier(Modifier::REVERSED),
+            }
+        }
+        None => Style::default().fg(theme.accent()),
+    };
+
+    let mut spans = vec![Span::raw(text)];
+    for (source, status) in status {
+        let color = match status {
+            BackendStatus::Connected => theme.good(),
+            BackendStatus::Reconnecting => theme.warning(),
+            BackendStatus::Error => theme.error(),
+        };
+        spans.push(Span::raw(format!(" | {source}: ")));
+        spans.push(Span::styled(status.label(), Style::default().fg(color)));
+    }
+
+    Paragraph::new(Line::from(spans))
+        .style(style)
         .alignment(Alignment::Center)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(theme.text()))
                 .border_type(BorderType::Plain),
         )
 }
 
-pub(crate) fn draw_logs<'a>() -> TuiLoggerWidget<'a> {
+/// The captured log records, titled with the level currently shown.
+pub(crate) fn draw_logs<'a>(level: LevelFilter, theme: Theme) -> TuiLoggerWidget<'a> {
+    let style = Style::default().fg(theme.text()).bg(theme.background());
     TuiLoggerWidget::default()
-        .style_error(Style::default().fg(Color::Red))
-        .style_debug(Style::default().fg(Color::Green))
-        .style_warn(Style::default().fg(Color::Yellow))
-        .style_trace(Style::default().fg(Color::Gray))
-        .style_info(Style::default().fg(Color::Blue))
+        .style_error(Style::default().fg(theme.error()))
+        .style_debug(Style::default().fg(theme.good()))
+        .style_warn(Style::default().fg(theme.warning()))
+        .style_trace(Style::default().fg(theme.muted()))
+        .style_info(Style::default().fg(theme.accent()))
         .block(
             Block::default()
-                .title("Logs")
-                .border_style(Style::default().fg(Color::White).bg(Color::Black))
+                .title(format!("Logs [{}]", level.as_str().to_lowercase()))
+                .border_style(style)
                 .borders(Borders::ALL),
         )
-        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .style(style)
 }
 
-pub(crate) fn draw_help<'a>() -> Table<'a> {
-    let key_style = Style::default().fg(Color::LightCyan);
-    let help_style = Style::default().fg(Color::Gray);
+pub(crate) fn draw_help<'a>(theme: Theme) -> Table<'a> {
+    let key_style = Style::default().fg(theme.accent());
+    let help_style = Style::default().fg(theme.muted());
 
     let rows = Action::gen_help(key_style, help_style);
 
@@ -63,93 +144,468 @@ pub(crate) fn draw_help<'a>() -> Table<'a> {
         .column_spacing(1)
 }
 
-pub(crate) fn draw_charts<'a>(data: Option<&'a AutoSpectra>, lims: &'a Ylims<'a>) -> Chart<'a> {
-    let (datasets, log) = data.map_or((vec![], false), |specs| {
-        let n_spectra = specs.spectra.len();
-        let plot_data = match specs.plot_log {
-            true => specs.log_spectra.iter(),
-            false => specs.spectra.iter(),
-        };
-        (
-            plot_data
-                .zip(specs.ant_names.iter())
-                .enumerate()
-                .map(|(cnt, (x, name))| {
-                    let fraction = ((cnt + 1) as f32 / n_spectra as f32) * 255.0;
-
-                    Dataset::default()
-                        .name(name.clone())
-                        .marker(symbols::Marker::Braille)
-                        .style(Style::default().fg(Color::Indexed(fraction as u8)))
-                        .graph_type(GraphType::Line)
-                        .data(x.as_slice())
-                })
-                .collect::<Vec<_>>(),
-            specs.plot_log,
-        )
-    });
+/// Every key binding grouped by input mode.
+///
+/// `scroll` is the number of rows skipped from the top, it is clamped so the
+/// last rows stay on screen for a popup of the given height.
+pub(crate) fn draw_full_help<'a>(scroll: &mut usize, height: u16, theme: Theme) -> Table<'a> {
+    let key_style = Style::default().fg(theme.accent());
+    let help_style = Style::default().fg(theme.muted());
+    let header_style = theme.heading();
 
-    let xmin = data.map_or(0.0, |x| x.freq_min);
-    let xmax = data.map_or(10.0, |x| x.freq_max);
+    let sections = std::iter::once(("Normal", Action::gen_help(key_style, help_style)))
+        .chain(Action::gen_mode_help(key_style, help_style));
 
-    let ymin = lims
-        .get_min(log)
-        .or_else(|| data.map(|x| x.ymin()))
-        .unwrap_or(-120.0);
+    let mut rows = vec![];
+    for (cnt, (mode, section)) in sections.enumerate() {
+        if cnt > 0 {
+            rows.push(Row::default());
+        }
+        rows.push(Row::new(vec![Cell::from(Span::styled(mode, header_style))]));
+        rows.extend(section);
+    }
+
+    // borders take up two rows
+    let visible = height.saturating_sub(2) as usize;
+    *scroll = (*scroll).min(rows.len().saturating_sub(visible));
+
+    Table::new(
+        rows.into_iter().skip(*scroll),
+        &[Constraint::Length(17), Constraint::Min(20)],
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .title("Help (j/k to scroll, Esc to close)"),
+    )
+    .column_spacing(1)
+}
 
-    let ymax = lims
-        .get_max(log)
-        .or_else(|| data.map(|x| x.ymax()))
-        .unwrap_or(-20.0);
+/// Lists every antenna next to a swatch of its trace color, hidden antennas
+/// are grayed out and those without data struck through.
+pub(crate) fn draw_legend<'a>(
+    data: Option<&'a AutoSpectra>,
+    palette: Palette,
+    hidden: &HashSet<String>,
+    no_data: &HashSet<String>,
+    theme: Theme,
+) -> Paragraph<'a> {
+    let names = data.map_or(&[][..], |spec| spec.ant_names.as_slice());
 
-    let ylabels = Array::linspace(ymin, ymax, 11)
+    let lines = names
         .iter()
-        .map(|x| Span::raw(format!("{:.3}", x)))
+        .enumerate()
+        .map(|(cnt, name)| {
+            let color = match hidden.contains(name) {
+                true => theme.dim(),
+                false => palette.color(cnt, names.len()),
+            };
+            let (name_style, note) = match no_data.contains(name) {
+                true => (
+                    Style::default()
+                        .fg(theme.dim())
+                        .add_modifier(Modifier::CROSSED_OUT),
+                    " (no data)",
+                ),
+                false => (Style::default().fg(theme.muted()), ""),
+            };
+            Line::from(vec![
+                Span::styled("██ ", Style::default().fg(color)),
+                Span::styled(name.as_str(), name_style),
+                Span::styled(note, Style::default().fg(theme.dim())),
+            ])
+        })
         .collect::<Vec<_>>();
 
-    let labels = Array::linspace(xmin, xmax, 11)
+    Paragraph::new(lines).block(
+        Block::default()
+            .title("Legend")
+            .borders(Borders::ALL)
+            .style(Style::default()),
+    )
+}
+
+/// The highest visible sample in the plotted range as (antenna, x, y).
+pub(crate) fn find_peak<'a>(
+    spec: &'a AutoSpectra,
+    hidden: &HashSet<String>,
+    xlims: &Xlims,
+) -> Option<(&'a String, f64, f64)> {
+    let xmin = xlims.get_min().unwrap_or(f64::NEG_INFINITY);
+    let xmax = xlims.get_max().unwrap_or(f64::INFINITY);
+
+    let traces = match spec.plot_log {
+        true => &spec.log_spectra,
+        false => &spec.spectra,
+    };
+    spec.ant_names
         .iter()
-        .map(|x| Span::raw(format!("{:.3}", x)))
+        .zip(traces)
+        .filter(|(name, _)| !hidden.contains(*name))
+        .flat_map(|(name, trace)| trace.iter().map(move |(x, y)| (name, *x, *y)))
+        .filter(|(_, x, y)| (xmin..=xmax).contains(x) && y.is_finite())
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+}
+
+/// Formats a position on the X-axis with its unit.
+pub(crate) fn format_x(x: f64, unit: XUnit) -> String {
+    match unit {
+        XUnit::Mhz => format!("{x:.3} MHz"),
+        XUnit::Channel => format!("channel {x}"),
+    }
+}
+
+/// One line naming the highest plotted sample among the traces not `hidden`,
+/// within the frequency limits.
+pub(crate) fn draw_peak<'a>(
+    data: Option<&AutoSpectra>,
+    hidden: &HashSet<String>,
+    xlims: &Xlims,
+    theme: Theme,
+) -> Paragraph<'a> {
+    let text = data
+        .and_then(|spec| {
+            let (name, x, y) = find_peak(spec, hidden, xlims)?;
+
+            let x = format_x(x, spec.x_unit);
+            let y = match spec.plot_log {
+                true => format!("{y:.1} dB"),
+                false => format!("{y:.3e}"),
+            };
+            Some(format!("Peak: {x}, {y} ({name})"))
+        })
+        .unwrap_or_default();
+
+    Paragraph::new(text)
+        .style(Style::default().fg(theme.muted()))
+        .alignment(Alignment::Right)
+}
+
+/// Smallest terminal, in columns and rows, the normal layout is drawn in
+pub(crate) const MIN_SIZE: (u16, u16) = (60, 16);
+
+/// Shown in place of everything else when the terminal is smaller than [MIN_SIZE].
+pub(crate) fn draw_too_small<'a>(area: Rect, theme: Theme) -> Paragraph<'a> {
+    Paragraph::new(vec![
+        Line::from("Terminal too small"),
+        Line::from(format!(
+            "{}x{}, need at least {}x{}",
+            area.width, area.height, MIN_SIZE.0, MIN_SIZE.1
+        )),
+    ])
+    .style(Style::default().fg(theme.warning()))
+    .alignment(Alignment::Center)
+}
+
+/// Frames of the spinner shown while waiting for data, one per tick
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Placeholder for the chart before the first spectra arrive, vertically
+/// centered in an area `height` rows tall.
+pub(crate) fn draw_waiting<'a>(tick: usize, height: u16, theme: Theme) -> Paragraph<'a> {
+    Paragraph::new(format!(
+        "{} Waiting for data…",
+        SPINNER[tick % SPINNER.len()]
+    ))
+    .style(Style::default().fg(theme.muted()))
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .title(Span::styled("AutoSpectra", theme.heading()))
+            .borders(Borders::ALL)
+            // borders take up two rows
+            .padding(Padding::top(height.saturating_sub(3) / 2)),
+    )
+}
+
+/// Min, max, mean and standard deviation of each visible trace in the
+/// plotted units, followed by all of them together.
+pub(crate) fn draw_stats<'a>(
+    data: Option<&'a AutoSpectra>,
+    xlims: &Xlims,
+    hidden: &HashSet<String>,
+    theme: Theme,
+) -> Table<'a> {
+    let key_style = Style::default().fg(theme.accent());
+    let val_style = Style::default().fg(theme.muted());
+
+    let (xmin, xmax) = (xlims.get_min(), xlims.get_max());
+    let log = data.is_some_and(|spec| spec.plot_log);
+    let fmt_val = |val: f64| match log {
+        true => format!("{val:.2}"),
+        false => format!("{val:.3e}"),
+    };
+
+    let visible = data
+        .into_iter()
+        .flat_map(|spec| spec.ant_names.iter().enumerate())
+        .filter(|(_, name)| !hidden.contains(*name))
         .collect::<Vec<_>>();
 
-    let title = data.map_or("Power [dB]", |spec| match spec.plot_log {
-        true => "Power [dB]",
-        false => "Power [Absolute]",
+    let traces = visible.iter().map(|(index, name)| {
+        let stats = TraceStats::from_values(
+            data.into_iter()
+                .flat_map(|spec| spec.plotted_values(*index, xmin, xmax)),
+        );
+        (name.as_str(), stats)
     });
+    let all = TraceStats::from_values(visible.iter().flat_map(|(index, _)| {
+        data.into_iter()
+            .flat_map(|spec| spec.plotted_values(*index, xmin, xmax))
+    }));
+
+    let label_width = visible
+        .iter()
+        .map(|(_, name)| name.len() as u16)
+        .max()
+        .unwrap_or_default()
+        .max(3);
+
+    let header = ["", "min", "max", "mean", "std"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(key_style);
+
+    let rows = traces
+        .chain((visible.len() > 1).then_some(("all", all)))
+        .map(|(name, stats)| {
+            let vals = match stats {
+                Some(stats) => [stats.min, stats.max, stats.mean, stats.std].map(fmt_val),
+                None => std::array::from_fn(|_| "-".to_owned()),
+            };
+            std::iter::once(Cell::from(Span::styled(name, key_style)))
+                .chain(vals.map(|val| Cell::from(Span::styled(val, val_style))))
+                .collect::<Row>()
+        })
+        .collect::<Vec<_>>();
+
+    let unit = match log {
+        true => " [dB]",
+        false => "",
+    };
+
+    Table::new(
+        rows,
+        [
+            Constraint::Length(label_width),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(9),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .title(format!("Statistics{unit}")),
+    )
+    .column_spacing(1)
+}
+
+/// Fill fraction and error bits of each trace as reported by the recorder,
+/// green when complete, yellow for a few dropped integrations and red for
+/// more or any errors.
+#[cfg(feature = "lwa-na")]
+pub(crate) fn draw_quality<'a>(data: Option<&'a AutoSpectra>, theme: Theme) -> Table<'a> {
+    let key_style = Style::default().fg(theme.accent());
+
+    let severity = |quality: &DataQuality| match quality {
+        DataQuality { errors: 0, fill } if *fill >= 1.0 => theme.good(),
+        DataQuality { errors: 0, fill } if *fill >= 0.99 => theme.warning(),
+        _ => theme.error(),
+    };
+
+    let rows = data
+        .into_iter()
+        .flat_map(|spec| spec.ant_names.iter().zip(spec.quality.iter().flatten()))
+        .map(|(name, quality)| {
+            let style = Style::default().fg(severity(quality));
+            let errors = match quality.errors {
+                0 => "-".to_owned(),
+                bits => format!("{bits:08b}"),
+            };
+            Row::new(vec![
+                Cell::from(Span::styled(name.as_str(), key_style)),
+                Cell::from(Span::styled(format!("{:.2}%", 100.0 * quality.fill), style)),
+                Cell::from(Span::styled(errors, style)),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let header = ["", "fill", "errors"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(key_style);
+
+    Table::new(
+        rows,
+        [
+            Constraint::Min(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .title("Data Quality"),
+    )
+    .column_spacing(1)
+}
+
+/// Table of the rolling saturation averages for every pol/tuning, followed
+/// by the worst and the mean of each column. The worst row turns red once any
+/// of it exceeds `threshold`.
+#[cfg(feature = "lwa-na")]
+pub(crate) fn draw_saturation(stats: &SaturationStats, threshold: f64, theme: Theme) -> Table<'_> {
+    let label_width = stats
+        .pols()
+        .iter()
+        .map(|pol| pol.len() as u16 + 2)
+        .max()
+        .unwrap_or_default()
+        .max(7);
+
+    let header = ["pol", "1min", "5min", "10min", ""]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .height(1);
+
+    // the trend is colored by how saturated the latest sample is
+    let trend = |stat: &Stats| {
+        let color = match stat.latest() {
+            sat if sat >= 0.1 => theme.error(),
+            sat if sat >= 0.01 => theme.warning(),
+            _ => theme.good(),
+        };
+        Cell::from(Span::styled(stat.trend(), color))
+    };
+    let value_row = |label: String, vals: [f64; 3], color: Color| {
+        std::iter::once(Cell::from(Span::styled(label, color))).chain(
+            vals.map(|val| Cell::from(Span::styled(format!("{:0>5.2}", val * 100.0), color))),
+        )
+    };
 
-    Chart::new(datasets)
+    let rows = stats
+        .rows()
+        .map(|(label, stat)| {
+            value_row(label, stat.averages(), theme.muted())
+                .chain([trend(stat)])
+                .collect::<Row>()
+        })
+        .collect::<Vec<_>>();
+
+    let all = stats
+        .rows()
+        .map(|(_, stat)| stat.averages())
+        .collect::<Vec<_>>();
+    let worst = all
+        .iter()
+        .copied()
+        .reduce(|acc, vals| std::array::from_fn(|i| acc[i].max(vals[i])));
+    let mean = all
+        .iter()
+        .copied()
+        .reduce(|acc, vals| std::array::from_fn(|i| acc[i] + vals[i]))
+        .map(|sums| sums.map(|sum| sum / all.len() as f64));
+
+    let summary = worst
+        .map(|vals| {
+            let color = match vals.iter().any(|val| *val > threshold) {
+                true => theme.error(),
+                false => theme.text(),
+            };
+            value_row("max".to_owned(), vals, color).collect::<Row>()
+        })
+        .into_iter()
+        .chain(mean.map(|vals| value_row("mean".to_owned(), vals, theme.text()).collect::<Row>()));
+
+    Table::new(
+        rows.into_iter().chain(summary),
+        [
+            Constraint::Length(label_width),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(1),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .title("Saturation Statistics")
+            .borders(Borders::ALL),
+    )
+}
+
+pub(crate) fn draw_charts<'a>(
+    data: Option<&'a AutoSpectra>,
+    log: bool,
+    lims: &'a Ylims<'a>,
+    xlims: &'a Xlims<'a>,
+    focus: Option<&'a str>,
+    theme: Theme,
+) -> SpectrumChart<'a> {
+    let title = match focus {
+        Some(name) => format!("AutoSpectra: {name}"),
+        None => "AutoSpectra".to_owned(),
+    };
+
+    SpectrumChart::new(data)
+        .log(log)
+        .xlims(xlims.get_min(), xlims.get_max())
+        .ylims(lims.get_min(log), lims.get_max(log))
+        .focus(focus)
+        .theme(theme)
+        .block(
+            Block::default()
+                .title(Span::styled(title, theme.heading()))
+                .borders(Borders::ALL)
+                .style(Style::default()),
+        )
+}
+
+pub(crate) fn draw_waterfall<'a>(
+    history: &'a VecDeque<(Instant, AutoSpectra)>,
+    lims: &'a Ylims<'a>,
+    xlims: &'a Xlims<'a>,
+    plot_log: bool,
+    cadence: Option<std::time::Duration>,
+    theme: Theme,
+) -> Waterfall<'a> {
+    let cadence = cadence
+        .map(|delay| format!(", every {:.1}s", delay.as_secs_f64()))
+        .unwrap_or_default();
+
+    Waterfall::new(history, plot_log)
+        .xlims(xlims.get_min(), xlims.get_max())
+        .zlims(lims.get_min(plot_log), lims.get_max(plot_log))
+        .label_color(theme.muted())
         .block(
             Block::default()
                 .title(Span::styled(
-                    "AutoSpectra",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
+                    format!("Waterfall (last {} spectra{cadence})", history.len()),
+                    theme.heading(),
                 ))
                 .borders(Borders::ALL)
                 .style(Style::default()),
         )
-        .x_axis(
-            Axis::default()
-                .title("Freq [MHz]")
-                .style(Style::default().fg(Color::Gray))
-                .bounds([xmin, xmax])
-                .labels(labels),
-        )
-        .y_axis(
-            Axis::default()
-                .title(title)
-                .style(Style::default().fg(Color::Gray))
-                .bounds([ymin, ymax])
-                .labels(ylabels),
-        )
 }
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 pub(crate) fn center_popup(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
-    let [area] = Layout::horizontal([horizontal])
+    let [popup] = Layout::horizontal([horizontal])
         .flex(Flex::Center)
         .areas(area);
-    let [area] = Layout::vertical([vertical]).flex(Flex::Center).areas(area);
-    area
+    let [popup] = Layout::vertical([vertical]).flex(Flex::Center).areas(popup);
+    // never draw past the edge of the terminal, whatever the constraints asked for
+    popup.intersection(area)
 }
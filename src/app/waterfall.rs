@@ -0,0 +1,243 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Widget},
+};
+
+use super::palette::ramp;
+use crate::loader::AutoSpectra;
+
+/// Color stops used for the intensity ramp, from weakest to strongest.
+const RAMP: [(u8, u8, u8); 5] = [
+    (0, 0, 64),
+    (0, 96, 255),
+    (0, 200, 100),
+    (255, 220, 0),
+    (255, 0, 0),
+];
+
+/// Number of rows between time labels
+const LABEL_SPACING: usize = 4;
+
+/// Formats the age of a row, e.g. `-45s`, `-2m30s` or `-1h05m`.
+fn format_offset(offset: Duration) -> String {
+    let secs = offset.as_secs_f64().round() as u64;
+    match secs {
+        0..=59 => format!("-{secs}s"),
+        60..=3599 => format!("-{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("-{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Finds the value to display for the frequency bin [f_lo, f_hi).
+///
+/// The maximum inside the bin is used so narrow features are not lost when
+/// there are more channels than terminal cells. If no sample falls inside
+/// the bin the nearest sample is used instead.
+fn bin_value(spec: &[(f64, f64)], f_lo: f64, f_hi: f64) -> Option<f64> {
+    let first = spec.first()?.0;
+    let last = spec.last()?.0;
+    if f_hi < first || f_lo > last {
+        return None;
+    }
+
+    let start = spec.partition_point(|(freq, _)| *freq < f_lo);
+    let stop = spec.partition_point(|(freq, _)| *freq < f_hi);
+
+    if stop > start {
+        spec[start..stop]
+            .iter()
+            .map(|(_, val)| *val)
+            .reduce(f64::max)
+    } else {
+        let center = 0.5 * (f_lo + f_hi);
+        [start.saturating_sub(1), start.min(spec.len() - 1)]
+            .into_iter()
+            .min_by(|a, b| {
+                (spec[*a].0 - center)
+                    .abs()
+                    .total_cmp(&(spec[*b].0 - center).abs())
+            })
+            .map(|index| spec[index].1)
+    }
+}
+
+/// A time-frequency image of the most recent spectra.
+///
+/// Each antenna gets its own column with the newest spectrum at the top, the
+/// rows are labelled on the left with their age relative to the newest one.
+pub(crate) struct Waterfall<'a> {
+    history: &'a VecDeque<(Instant, AutoSpectra)>,
+    plot_log: bool,
+    xlims: (Option<f64>, Option<f64>),
+    zlims: (Option<f64>, Option<f64>),
+    label_color: Color,
+    block: Option<Block<'a>>,
+}
+impl<'a> Waterfall<'a> {
+    pub fn new(history: &'a VecDeque<(Instant, AutoSpectra)>, plot_log: bool) -> Self {
+        Self {
+            history,
+            plot_log,
+            xlims: (None, None),
+            zlims: (None, None),
+            label_color: Color::Gray,
+            block: None,
+        }
+    }
+
+    /// Color of the time labels.
+    pub fn label_color(mut self, color: Color) -> Self {
+        self.label_color = color;
+        self
+    }
+
+    /// Restrict the frequency range displayed, `None` uses the data bounds.
+    pub fn xlims(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.xlims = (min, max);
+        self
+    }
+
+    /// Fix the range of the color scale, `None` uses the data bounds.
+    pub fn zlims(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.zlims = (min, max);
+        self
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+impl Widget for Waterfall<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = match self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+
+        let Some((newest, latest)) = self.history.back() else {
+            return;
+        };
+        if latest.ant_names.is_empty() || area.height < 2 || area.width == 0 {
+            return;
+        }
+
+        let labels = self
+            .history
+            .iter()
+            .rev()
+            .take(area.height as usize - 1)
+            .enumerate()
+            .filter(|(cnt, _)| cnt % LABEL_SPACING == 0)
+            .map(|(cnt, (arrived, _))| (cnt, format_offset(newest.duration_since(*arrived))))
+            .collect::<Vec<_>>();
+        let label_width = labels
+            .iter()
+            .map(|(_, label)| label.len() as u16 + 1)
+            .max()
+            .unwrap_or(0)
+            .min(area.width / 4);
+
+        let [gutter, area] =
+            Layout::horizontal([Constraint::Length(label_width), Constraint::Min(0)]).areas(area);
+        for (cnt, label) in labels {
+            buf.set_stringn(
+                gutter.x,
+                gutter.y + 1 + cnt as u16,
+                label,
+                gutter.width as usize,
+                Style::default().fg(self.label_color),
+            );
+        }
+
+        let xmin = self.xlims.0.unwrap_or(latest.freq_min);
+        let xmax = self.xlims.1.unwrap_or(latest.freq_max);
+
+        let n_ants = latest.ant_names.len() as u32;
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                latest
+                    .ant_names
+                    .iter()
+                    .map(|_| Constraint::Ratio(1, n_ants)),
+            )
+            .split(area);
+
+        for (name, column) in latest.ant_names.iter().zip(columns.iter()) {
+            if column.width == 0 {
+                continue;
+            }
+            buf.set_stringn(
+                column.x,
+                column.y,
+                name,
+                column.width as usize,
+                Style::default().add_modifier(Modifier::BOLD),
+            );
+
+            let bin_width = (xmax - xmin) / column.width as f64;
+
+            let rows =
+                self.history
+                    .iter()
+                    .rev()
+                    .take(column.height as usize - 1)
+                    .map(|(_, spec)| {
+                        let data = spec.ant_names.iter().position(|x| x == name).map(|index| {
+                            match self.plot_log {
+                                true => &spec.log_spectra[index],
+                                false => &spec.spectra[index],
+                            }
+                        });
+
+                        (0..column.width)
+                            .map(|x| {
+                                let f_lo = xmin + x as f64 * bin_width;
+                                data.and_then(|spec| bin_value(spec, f_lo, f_lo + bin_width))
+                                    .filter(|val| val.is_finite())
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+            // each antenna is scaled independently unless the limits are fixed
+            let vmin = self.zlims.0.unwrap_or_else(|| {
+                rows.iter()
+                    .flatten()
+                    .flatten()
+                    .fold(f64::INFINITY, |a, &b| a.min(b))
+            });
+            let vmax = self.zlims.1.unwrap_or_else(|| {
+                rows.iter()
+                    .flatten()
+                    .flatten()
+                    .fold(f64::NEG_INFINITY, |a, &b| a.max(b))
+            });
+
+            for (row_cnt, row) in rows.iter().enumerate() {
+                for (col_cnt, val) in row.iter().enumerate() {
+                    if let Some(val) = val {
+                        let frac = match vmax > vmin {
+                            true => (val - vmin) / (vmax - vmin),
+                            false => 0.5,
+                        };
+                        buf[(column.x + col_cnt as u16, column.y + 1 + row_cnt as u16)]
+                            .set_bg(ramp(&RAMP, frac));
+                    }
+                }
+            }
+        }
+    }
+}
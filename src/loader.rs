@@ -2,7 +2,9 @@ use core::f64;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use hifitime::Epoch;
 use ndarray::{Array, Ix1, Ix2, Zip};
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "ovro")]
 pub mod ovro;
@@ -10,6 +12,26 @@ pub mod ovro;
 #[cfg(feature = "lwa-na")]
 pub mod north_arm;
 
+pub mod replay;
+
+/// Units used for the x-axis of the spectra
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Serialize, Deserialize)]
+pub enum XUnit {
+    /// Raw channel index
+    Channel,
+    /// Frequency in MHz
+    #[default]
+    Mhz,
+}
+impl XUnit {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Channel => "Channel",
+            Self::Mhz => "Freq [MHz]",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AutoSpectra {
     pub(crate) freq_min: f64,
@@ -18,6 +40,19 @@ pub struct AutoSpectra {
     pub(crate) spectra: Vec<Vec<(f64, f64)>>,
     pub(crate) log_spectra: Vec<Vec<(f64, f64)>>,
     pub(crate) plot_log: bool,
+    pub(crate) x_unit: XUnit,
+    /// Fraction of saturated samples for each trace, if the source reports it
+    pub(crate) saturation: Option<Vec<f64>>,
+    /// Fill fraction and error bits for each trace, if the source reports them
+    pub(crate) quality: Option<Vec<DataQuality>>,
+    /// A statistic derived for every sample of each trace, aligned with `spectra`
+    pub(crate) channel_stat: Option<Vec<Vec<f64>>>,
+    /// When the spectra were recorded, or requested if the source doesn't say
+    pub(crate) timestamp: Option<Epoch>,
+    /// Distinct beams the spectra were recorded from, empty if the source has none
+    pub(crate) beams: Vec<u8>,
+    /// Number and center frequency in MHz of each DR tuning in the spectra
+    pub(crate) tunings: Vec<(u8, f64)>,
 }
 impl AutoSpectra {
     pub fn new(
@@ -27,31 +62,48 @@ impl AutoSpectra {
         data: Array<f64, Ix2>,
         plot_log: bool,
     ) -> Self {
-        let freq_min = freqs.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let freq_max = freqs.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        // every trace shares the same frequencies
+        let freqs = freqs
+            .broadcast(data.raw_dim())
+            .expect("Frequencies must have one entry per channel of the spectra.")
+            .to_owned();
+        Self::with_freqs(ant_names, freqs, data, plot_log)
+    }
 
-        let log_spectra = data
+    /// Builds spectra where each trace has its own frequencies.
+    ///
+    /// `freqs` and `data` must both be (ant_names, nfreqs) arrays.
+    pub fn with_freqs(
+        ant_names: Vec<String>,
+        freqs: Array<f64, Ix2>,
+        data: Array<f64, Ix2>,
+        plot_log: bool,
+    ) -> Self {
+        let spectra = freqs
             .outer_iter()
-            .map(|inner| {
-                Zip::from(inner)
-                    .and(&freqs)
-                    .map_collect(|y, x| (*x, 10.0 * y.log10()))
-                    .to_vec()
-                    .into_iter()
-                    .filter(|(_freq, val)| val.is_finite())
-                    .collect::<Vec<_>>()
-            })
+            .zip(data.outer_iter())
+            .map(|(xs, ys)| Zip::from(xs).and(ys).map_collect(|x, y| (*x, *y)).to_vec())
             .collect::<Vec<_>>();
 
-        let spectra = data
-            .outer_iter()
-            .map(|inner| {
-                Zip::from(inner)
-                    .and(&freqs)
-                    .map_collect(|y, x| (*x, *y))
-                    .to_vec()
-            })
-            .collect::<Vec<_>>();
+        Self::from_pairs(ant_names, spectra, plot_log)
+    }
+
+    /// Builds spectra from (freq, val) pairs, allowing each antenna to have its own frequencies.
+    pub fn from_pairs(
+        ant_names: Vec<String>,
+        spectra: Vec<Vec<(f64, f64)>>,
+        plot_log: bool,
+    ) -> Self {
+        let freq_min = spectra
+            .iter()
+            .flatten()
+            .fold(f64::INFINITY, |a, &(b, _)| a.min(b));
+        let freq_max = spectra
+            .iter()
+            .flatten()
+            .fold(f64::NEG_INFINITY, |a, &(b, _)| a.max(b));
+
+        let log_spectra = to_db(&spectra, 0.0);
 
         Self {
             freq_min,
@@ -60,36 +112,749 @@ impl AutoSpectra {
             spectra,
             log_spectra,
             plot_log,
+            x_unit: XUnit::default(),
+            saturation: None,
+            quality: None,
+            channel_stat: None,
+            timestamp: None,
+            beams: Vec::new(),
+            tunings: Vec::new(),
+        }
+    }
+
+    /// Combines several spectra into one, each trace keeps its own frequencies.
+    ///
+    /// The plotting state is taken from the first item.
+    /// Saturation, quality and channel statistics are only kept if every item has them.
+    pub fn stack<I: IntoIterator<Item = AutoSpectra>>(specs: I) -> Option<Self> {
+        specs.into_iter().reduce(|mut acc, spec| {
+            acc.freq_min = acc.freq_min.min(spec.freq_min);
+            acc.freq_max = acc.freq_max.max(spec.freq_max);
+            acc.ant_names.extend(spec.ant_names);
+            acc.spectra.extend(spec.spectra);
+            acc.log_spectra.extend(spec.log_spectra);
+            acc.saturation = acc.saturation.zip(spec.saturation).map(|(mut a, b)| {
+                a.extend(b);
+                a
+            });
+            acc.quality = acc.quality.zip(spec.quality).map(|(mut a, b)| {
+                a.extend(b);
+                a
+            });
+            acc.channel_stat = acc.channel_stat.zip(spec.channel_stat).map(|(mut a, b)| {
+                a.extend(b);
+                a
+            });
+            for beam in spec.beams {
+                if !acc.beams.contains(&beam) {
+                    acc.beams.push(beam);
+                }
+            }
+            for tuning in spec.tunings {
+                if !acc.tunings.contains(&tuning) {
+                    acc.tunings.push(tuning);
+                }
+            }
+            acc
+        })
+    }
+
+    /// The distinct frequency grids of the traces as the number of channels
+    /// and the first and last frequency, in the order they first appear.
+    pub fn freq_grids(&self) -> Vec<(usize, f64, f64)> {
+        let mut grids = vec![];
+        for trace in self.spectra.iter() {
+            if let (Some(first), Some(last)) = (trace.first(), trace.last()) {
+                let grid = (trace.len(), first.0, last.0);
+                if !grids.contains(&grid) {
+                    grids.push(grid);
+                }
+            }
+        }
+        grids
+    }
+
+    /// Computes the element-wise mean of the raw spectra.
+    ///
+    /// The antenna names, frequencies, and plotting state are taken from the first item.
+    /// Returns `None` if the iterator is empty or the shapes do not agree.
+    pub fn mean<'a, I: IntoIterator<Item = &'a AutoSpectra>>(specs: I) -> Option<Self> {
+        let mut specs = specs.into_iter();
+        let first = specs.next()?;
+
+        let mut sums = first
+            .spectra
+            .iter()
+            .map(|inner| inner.iter().map(|(_, val)| *val).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut count = 1.0;
+        let mut saturation = first.saturation.clone();
+        let mut quality = first.quality.clone();
+        let mut timestamp = first.timestamp;
+
+        for spec in specs {
+            if spec.spectra.len() != sums.len() {
+                return None;
+            }
+            // keep the worst saturation seen in any of the averaged spectra
+            saturation = saturation
+                .zip(spec.saturation.as_ref())
+                .map(|(a, b)| a.iter().zip(b).map(|(x, y)| x.max(*y)).collect());
+            quality = quality
+                .zip(spec.quality.as_ref())
+                .map(|(a, b)| a.iter().zip(b).map(|(x, y)| x.worst(y)).collect());
+            // label the average with the newest time
+            timestamp = timestamp.max(spec.timestamp);
+            for (sum, inner) in sums.iter_mut().zip(spec.spectra.iter()) {
+                if sum.len() != inner.len() {
+                    return None;
+                }
+                sum.iter_mut()
+                    .zip(inner.iter())
+                    .for_each(|(total, (_, val))| *total += val);
+            }
+            count += 1.0;
+        }
+
+        let spectra = first
+            .spectra
+            .iter()
+            .zip(sums)
+            .map(|(inner, sum)| {
+                inner
+                    .iter()
+                    .zip(sum)
+                    .map(|((freq, _), total)| (*freq, total / count))
+                    .collect()
+            })
+            .collect();
+
+        let mut out = Self::from_pairs(first.ant_names.clone(), spectra, first.plot_log);
+        out.x_unit = first.x_unit;
+        out.saturation = saturation;
+        out.quality = quality;
+        out.timestamp = timestamp;
+        out.beams = first.beams.clone();
+        out.tunings = first.tunings.clone();
+        Some(out)
+    }
+
+    /// Computes the element-wise sample standard deviation of the raw spectra
+    /// from the running sums of the values and their squares.
+    ///
+    /// Returns `None` with fewer than two items or if the shapes do not agree.
+    pub fn std_dev<'a, I: IntoIterator<Item = &'a AutoSpectra>>(specs: I) -> Option<Vec<Vec<f64>>> {
+        let mut specs = specs.into_iter();
+        let first = specs.next()?;
+
+        let mut sums = first
+            .spectra
+            .iter()
+            .map(|inner| {
+                inner
+                    .iter()
+                    .map(|(_, val)| (*val, val * val))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut count = 1.0;
+
+        for spec in specs {
+            if spec.spectra.len() != sums.len() {
+                return None;
+            }
+            for (sum, inner) in sums.iter_mut().zip(spec.spectra.iter()) {
+                if sum.len() != inner.len() {
+                    return None;
+                }
+                sum.iter_mut()
+                    .zip(inner.iter())
+                    .for_each(|((total, squares), (_, val))| {
+                        *total += val;
+                        *squares += val * val;
+                    });
+            }
+            count += 1.0;
+        }
+        if count < 2.0 {
+            return None;
+        }
+
+        Some(
+            sums.into_iter()
+                .map(|sum| {
+                    sum.into_iter()
+                        .map(|(total, squares)| {
+                            let variance = (squares - total * total / count) / (count - 1.0);
+                            // rounding can leave a tiny negative variance,
+                            // `max` would also turn a NaN sample into zero
+                            match variance < 0.0 {
+                                true => 0.0,
+                                false => variance.sqrt(),
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Shifts every raw sample by `scale` times its offset, e.g. a standard deviation.
+    ///
+    /// Returns `None` if the offsets do not match the shape of the spectra.
+    pub fn offset_by(&self, offsets: &[Vec<f64>], scale: f64) -> Option<Self> {
+        if offsets.len() != self.spectra.len()
+            || offsets
+                .iter()
+                .zip(self.spectra.iter())
+                .any(|(offset, trace)| offset.len() != trace.len())
+        {
+            return None;
+        }
+
+        let spectra = self
+            .spectra
+            .iter()
+            .zip(offsets)
+            .map(|(trace, offset)| {
+                trace
+                    .iter()
+                    .zip(offset)
+                    .map(|((freq, val), offset)| (*freq, val + scale * offset))
+                    .collect()
+            })
+            .collect();
+
+        let mut out = Self::from_pairs(self.ant_names.clone(), spectra, self.plot_log);
+        out.copy_metadata(self);
+        Some(out)
+    }
+
+    /// Packs the raw spectra into a (n_ant, nfreqs) array.
+    ///
+    /// Returns `None` if the traces differ in length.
+    #[cfg_attr(not(feature = "ovro"), allow(dead_code))]
+    pub fn as_array(&self) -> Option<Array<f64, Ix2>> {
+        let nfreqs = self.spectra.first().map_or(0, |inner| inner.len());
+        if self.spectra.iter().any(|inner| inner.len() != nfreqs) {
+            return None;
+        }
+
+        Array::from_shape_vec(
+            (self.spectra.len(), nfreqs),
+            self.spectra.iter().flatten().map(|(_, val)| *val).collect(),
+        )
+        .ok()
+    }
+
+    /// Computes the per-frequency median of the raw spectra across all antennas
+    /// as a single trace.
+    ///
+    /// Returns `None` if there are no spectra or the traces differ in length.
+    pub fn median(&self) -> Option<Self> {
+        let first = self.spectra.first()?;
+        if self.spectra.iter().any(|inner| inner.len() != first.len()) {
+            return None;
+        }
+
+        let median = first
+            .iter()
+            .enumerate()
+            .map(|(chan, (freq, _))| {
+                let mut vals = self
+                    .spectra
+                    .iter()
+                    .map(|inner| inner[chan].1)
+                    .filter(|val| !val.is_nan())
+                    .collect::<Vec<_>>();
+                vals.sort_by(f64::total_cmp);
+
+                let n_vals = vals.len();
+                let val = match n_vals {
+                    0 => f64::NAN,
+                    n if n % 2 == 1 => vals[n / 2],
+                    n => 0.5 * (vals[n / 2 - 1] + vals[n / 2]),
+                };
+                (*freq, val)
+            })
+            .collect();
+
+        let mut out = Self::from_pairs(vec!["Median".to_owned()], vec![median], self.plot_log);
+        out.x_unit = self.x_unit;
+        out.timestamp = self.timestamp;
+        out.beams = self.beams.clone();
+        out.tunings = self.tunings.clone();
+        Some(out)
+    }
+
+    /// The values of one plotted trace, raw or dB per `plot_log`, with
+    /// frequencies between `xmin` and `xmax` when given.
+    pub fn plotted_values(
+        &self,
+        index: usize,
+        xmin: Option<f64>,
+        xmax: Option<f64>,
+    ) -> impl Iterator<Item = f64> + '_ {
+        let traces = match self.plot_log {
+            true => &self.log_spectra,
+            false => &self.spectra,
+        };
+        traces
+            .get(index)
+            .into_iter()
+            .flatten()
+            .filter(move |(freq, _)| {
+                xmin.is_none_or(|min| *freq >= min) && xmax.is_none_or(|max| *freq <= max)
+            })
+            .map(|(_, val)| *val)
+    }
+
+    /// Marks the channels of each raw trace that sit more than `sigma`
+    /// robust standard deviations above the trace's median.
+    ///
+    /// The spread is estimated from the median absolute deviation so the
+    /// outliers themselves don't inflate it. Non-finite samples are never flagged.
+    pub fn outlier_mask(&self, sigma: f64) -> Vec<Vec<bool>> {
+        self.spectra
+            .iter()
+            .map(|trace| {
+                let finite = trace
+                    .iter()
+                    .map(|(_, val)| *val)
+                    .filter(|val| val.is_finite())
+                    .collect::<Vec<_>>();
+                let Some(center) = median_of(finite.clone()) else {
+                    return vec![false; trace.len()];
+                };
+                let mad = median_of(finite.iter().map(|val| (val - center).abs()).collect())
+                    .unwrap_or_default();
+                // scales the MAD to a standard deviation for Gaussian noise
+                let threshold = center + sigma * 1.4826 * mad;
+
+                trace
+                    .iter()
+                    .map(|(_, val)| val.is_finite() && *val > threshold)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Only the samples flagged by [Self::outlier_mask], for drawing on top of the traces.
+    pub fn outliers(&self, sigma: f64) -> Self {
+        let spectra = self
+            .spectra
+            .iter()
+            .zip(self.outlier_mask(sigma))
+            .map(|(trace, mask)| {
+                trace
+                    .iter()
+                    .zip(mask)
+                    .filter(|(_, flagged)| *flagged)
+                    .map(|(sample, _)| *sample)
+                    .collect()
+            })
+            .collect();
+
+        let mut out = Self::from_pairs(self.ant_names.clone(), spectra, self.plot_log);
+        out.copy_metadata(self);
+        out
+    }
+
+    /// Estimates the spectral kurtosis of every channel from a series of polls,
+    /// oldest first, which must all have the same traces and channels.
+    ///
+    /// Uses SK = (M + 1) / (M - 1) (M S2 / S1^2 - 1) over the M polls, where S1
+    /// and S2 are the sums of the raw values and their squares. The polls are
+    /// already integrated so noise-like channels sit well below the textbook 1,
+    /// compare channels with each other rather than with 1.
+    /// Returns `None` for fewer than three polls or if the shapes do not agree.
+    pub fn spectral_kurtosis<'a, I: IntoIterator<Item = &'a AutoSpectra>>(
+        specs: I,
+    ) -> Option<Vec<Vec<f64>>> {
+        let specs = specs.into_iter().collect::<Vec<_>>();
+        let first = specs.first()?;
+        if specs.len() < 3
+            || specs.iter().any(|spec| {
+                spec.spectra.len() != first.spectra.len()
+                    || spec
+                        .spectra
+                        .iter()
+                        .zip(&first.spectra)
+                        .any(|(a, b)| a.len() != b.len())
+            })
+        {
+            return None;
+        }
+
+        let m = specs.len() as f64;
+        let kurtosis = first
+            .spectra
+            .iter()
+            .enumerate()
+            .map(|(trace, samples)| {
+                (0..samples.len())
+                    .map(|chan| {
+                        let (s1, s2) = specs.iter().fold((0.0, 0.0), |(s1, s2), spec| {
+                            let val = spec.spectra[trace][chan].1;
+                            (s1 + val, s2 + val * val)
+                        });
+                        (m + 1.0) / (m - 1.0) * (m * s2 / (s1 * s1) - 1.0)
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(kurtosis)
+    }
+
+    /// The samples whose [Self::channel_stat] is more than `sigma` robust
+    /// standard deviations above (`high`) or below the trace's median value
+    /// of the statistic.
+    ///
+    /// Empty traces if no statistic is attached.
+    pub fn channel_stat_outliers(&self, sigma: f64, high: bool) -> Self {
+        let empty = vec![vec![]; self.spectra.len()];
+        let stats = self.channel_stat.as_ref().unwrap_or(&empty);
+
+        let spectra = self
+            .spectra
+            .iter()
+            .zip(stats)
+            .map(|(trace, stat)| {
+                let finite = stat
+                    .iter()
+                    .copied()
+                    .filter(|val| val.is_finite())
+                    .collect::<Vec<_>>();
+                let Some(center) = median_of(finite.clone()) else {
+                    return vec![];
+                };
+                let mad = median_of(finite.iter().map(|val| (val - center).abs()).collect())
+                    .unwrap_or_default();
+                let spread = sigma * 1.4826 * mad;
+
+                trace
+                    .iter()
+                    .zip(stat)
+                    .filter(|(_, val)| match high {
+                        true => **val > center + spread,
+                        false => **val < center - spread,
+                    })
+                    .map(|(sample, _)| *sample)
+                    .collect()
+            })
+            .collect();
+
+        let mut out = Self::from_pairs(self.ant_names.clone(), spectra, self.plot_log);
+        out.x_unit = self.x_unit;
+        out
+    }
+
+    /// Combines each trace with the trace of the same name in `reference` as
+    /// `op(value, reference_value)` for samples at matching frequencies.
+    ///
+    /// Traces without a counterpart in the reference, and samples whose
+    /// frequency is not in the reference trace, are left unchanged.
+    pub fn apply_reference<F: Fn(f64, f64) -> f64>(&self, reference: &AutoSpectra, op: F) -> Self {
+        let spectra = self
+            .ant_names
+            .iter()
+            .zip(self.spectra.iter())
+            .map(|(name, trace)| {
+                let Some(ref_trace) = reference
+                    .ant_names
+                    .iter()
+                    .position(|ref_name| ref_name == name)
+                    .map(|index| &reference.spectra[index])
+                else {
+                    return trace.clone();
+                };
+
+                trace
+                    .iter()
+                    .map(|&(freq, val)| match matching_sample(ref_trace, freq) {
+                        Some(ref_val) => (freq, op(val, ref_val)),
+                        None => (freq, val),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut out = Self::from_pairs(self.ant_names.clone(), spectra, self.plot_log);
+        out.copy_metadata(self);
+        out
+    }
+
+    /// Subtracts the trace of the same name in `reference`, matching samples
+    /// as [Self::apply_reference] does.
+    ///
+    /// The raw values are the linear difference and the dB values are the
+    /// difference in dB, channels below the reference would otherwise have
+    /// a negative power with no dB value.
+    pub fn subtract_reference(&self, reference: &AutoSpectra) -> Self {
+        let mut out = self.apply_reference(reference, |val, ref_val| val - ref_val);
+        out.log_spectra = self
+            .apply_reference(reference, |val, ref_val| val / ref_val)
+            .log_spectra;
+        out
+    }
+
+    /// A single trace comparing two antennas at the frequencies they share.
+    ///
+    /// The raw values are `first - second` and the dB values are the ratio
+    /// `first / second` in dB, so the log plot shows the difference in dB.
+    /// Returns `None` if either antenna is missing.
+    pub fn compare(&self, first: &str, second: &str) -> Option<Self> {
+        let trace = |name: &str| {
+            self.ant_names
+                .iter()
+                .position(|ant| ant == name)
+                .map(|index| &self.spectra[index])
+        };
+        let (first_trace, second_trace) = (trace(first)?, trace(second)?);
+
+        // only keep the frequencies on both grids
+        let pairs = first_trace
+            .iter()
+            .filter_map(|&(freq, val)| {
+                matching_sample(second_trace, freq).map(|other| (freq, val, other))
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = Self::from_pairs(
+            vec![format!("{first} vs {second}")],
+            vec![pairs.iter().map(|(freq, a, b)| (*freq, a - b)).collect()],
+            self.plot_log,
+        );
+        out.log_spectra = vec![pairs
+            .iter()
+            .map(|(freq, a, b)| (*freq, 10.0 * (a / b).log10()))
+            .filter(|(_, val)| val.is_finite())
+            .collect()];
+        out.x_unit = self.x_unit;
+        out.timestamp = self.timestamp;
+        out.beams = self.beams.clone();
+        out.tunings = self.tunings.clone();
+        Some(out)
+    }
+
+    /// Smooths each trace with a centered running mean `width` channels wide.
+    ///
+    /// The window shrinks at the band edges instead of wrapping around.
+    /// A width of 0 or 1 leaves the spectra unchanged.
+    pub fn smooth(&self, width: usize) -> Self {
+        if width <= 1 {
+            return self.clone();
+        }
+        let before = (width - 1) / 2;
+        let after = width / 2;
+
+        let spectra = self
+            .spectra
+            .iter()
+            .map(|trace| {
+                trace
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (freq, _))| {
+                        let start = index.saturating_sub(before);
+                        let stop = (index + after + 1).min(trace.len());
+
+                        // skip flagged (NaN) channels rather than spreading them
+                        let (total, count) = trace[start..stop]
+                            .iter()
+                            .filter(|(_, val)| !val.is_nan())
+                            .fold((0.0, 0), |(total, count), (_, val)| {
+                                (total + val, count + 1)
+                            });
+                        (*freq, total / count as f64)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut out = Self::from_pairs(self.ant_names.clone(), spectra, self.plot_log);
+        out.copy_metadata(self);
+        out
+    }
+
+    /// Recomputes the dB values relative to a reference level given in raw units,
+    /// `None` goes back to dB relative to 1.0.
+    ///
+    /// The raw spectra are left untouched.
+    pub fn set_db_reference(&mut self, reference: Option<f64>) {
+        let offset = reference.map_or(0.0, |level| 10.0 * level.log10());
+        self.log_spectra = to_db(&self.spectra, offset);
+    }
+
+    /// Estimates the noise floor of each trace in dB as the `quantile`
+    /// (0 to 1) of its dB values over frequency, keyed by antenna name.
+    ///
+    /// Traces with no finite values have no floor.
+    pub fn noise_floors(&self, quantile: f64) -> Vec<(String, f64)> {
+        self.ant_names
+            .iter()
+            .zip(self.log_spectra.iter())
+            .filter_map(|(name, trace)| {
+                quantile_of(trace.iter().map(|(_, val)| *val).collect(), quantile)
+                    .map(|floor| (name.clone(), floor))
+            })
+            .collect()
+    }
+
+    /// Shifts the dB values of each trace down by the floor of the antenna with
+    /// the same name, so they read as dB above the noise floor.
+    ///
+    /// Traces without a floor of their own, like the median, use the median of
+    /// all the floors. The raw spectra are left untouched.
+    pub fn subtract_floors(&mut self, floors: &[(String, f64)]) {
+        let fallback =
+            median_of(floors.iter().map(|(_, floor)| *floor).collect()).unwrap_or_default();
+        for (name, trace) in self.ant_names.iter().zip(self.log_spectra.iter_mut()) {
+            let floor = floors
+                .iter()
+                .find(|(ant, _)| ant == name)
+                .map_or(fallback, |(_, floor)| *floor);
+            trace.iter_mut().for_each(|(_, val)| *val -= floor);
         }
     }
 
-    pub fn ymin(&self) -> f64 {
-        let data_to_min = match self.plot_log {
+    /// Carries over everything but the data from spectra this was derived from.
+    fn copy_metadata(&mut self, other: &AutoSpectra) {
+        self.x_unit = other.x_unit;
+        self.saturation = other.saturation.clone();
+        self.quality = other.quality.clone();
+        self.channel_stat = other.channel_stat.clone();
+        self.timestamp = other.timestamp;
+        self.beams = other.beams.clone();
+        self.tunings = other.tunings.clone();
+    }
+
+    /// Lowest finite value in dB (`plot_log`) or raw units, less a margin.
+    pub fn ymin(&self, plot_log: bool) -> f64 {
+        let data_to_min = match plot_log {
             true => &self.log_spectra,
             false => &self.spectra,
         };
 
         let tmp = data_to_min.iter().fold(f64::INFINITY, |a, b| {
-            a.min(b.iter().fold(f64::INFINITY, |c, &d| c.min(d.1)))
+            a.min(
+                b.iter()
+                    .filter(|(_, val)| val.is_finite())
+                    .fold(f64::INFINITY, |c, &d| c.min(d.1)),
+            )
         });
         //  give a 10% margin
         tmp - 0.1 * tmp.abs()
     }
 
-    pub fn ymax(&self) -> f64 {
-        let data_to_max = match self.plot_log {
+    /// Highest finite value in dB (`plot_log`) or raw units, plus a margin.
+    pub fn ymax(&self, plot_log: bool) -> f64 {
+        let data_to_max = match plot_log {
             true => &self.log_spectra,
             false => &self.spectra,
         };
 
         let tmp = data_to_max.iter().fold(f64::NEG_INFINITY, |a, b| {
-            a.max(b.iter().fold(f64::NEG_INFINITY, |c, &d| c.max(d.1)))
+            a.max(
+                b.iter()
+                    .filter(|(_, val)| val.is_finite())
+                    .fold(f64::NEG_INFINITY, |c, &d| c.max(d.1)),
+            )
         });
         // give a 10% margin
         tmp + 0.1 * tmp.abs()
     }
 }
 
+/// Converts raw (freq, val) traces to dB less `offset`, dropping samples
+/// that have no finite log.
+fn to_db(spectra: &[Vec<(f64, f64)>], offset: f64) -> Vec<Vec<(f64, f64)>> {
+    spectra
+        .iter()
+        .map(|inner| {
+            inner
+                .iter()
+                .map(|(x, y)| (*x, 10.0 * y.log10() - offset))
+                .filter(|(_freq, val)| val.is_finite())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+}
+
+/// Median of the values, `None` if there are none.
+pub(crate) fn median_of(mut vals: Vec<f64>) -> Option<f64> {
+    vals.sort_by(f64::total_cmp);
+    let n = vals.len();
+    match n {
+        0 => None,
+        n if n % 2 == 1 => Some(vals[n / 2]),
+        n => Some(0.5 * (vals[n / 2 - 1] + vals[n / 2])),
+    }
+}
+
+/// The `quantile` (0 to 1) of the finite values by nearest rank, `None` if there are none.
+fn quantile_of(vals: Vec<f64>, quantile: f64) -> Option<f64> {
+    let mut vals = vals
+        .into_iter()
+        .filter(|val| val.is_finite())
+        .collect::<Vec<_>>();
+    vals.sort_by(f64::total_cmp);
+    let last = vals.len().checked_sub(1)?;
+    let rank = (quantile.clamp(0.0, 1.0) * last as f64).round() as usize;
+    Some(vals[rank])
+}
+
+/// Value of the sample at `freq` in a trace sorted by frequency, if there is
+/// one at that frequency.
+fn matching_sample(data: &[(f64, f64)], freq: f64) -> Option<f64> {
+    nearest_index(data, freq)
+        .map(|index| data[index])
+        .filter(|(other, _)| (other - freq).abs() <= 1e-9 * freq.abs().max(1.0))
+        .map(|(_, val)| val)
+}
+
+/// Index of the sample closest to `freq` in a trace sorted by frequency.
+pub fn nearest_index(data: &[(f64, f64)], freq: f64) -> Option<usize> {
+    let index = data.partition_point(|(x, _)| *x < freq);
+    [index.checked_sub(1), (index < data.len()).then_some(index)]
+        .into_iter()
+        .flatten()
+        .min_by(|a, b| {
+            (data[*a].0 - freq)
+                .abs()
+                .total_cmp(&(data[*b].0 - freq).abs())
+        })
+}
+
+/// Thins a trace sorted by frequency to at most `n_bins` samples between
+/// `xmin` and `xmax`, keeping the largest sample of each run so narrow spikes
+/// survive. The samples either side of the range are kept so lines still
+/// reach the edges of the plot. A reversed range is treated as its mirror.
+pub fn decimate_max(data: &[(f64, f64)], xmin: f64, xmax: f64, n_bins: usize) -> Vec<(f64, f64)> {
+    let (xmin, xmax) = match xmin <= xmax {
+        true => (xmin, xmax),
+        false => (xmax, xmin),
+    };
+    let start = data.partition_point(|(x, _)| *x < xmin).saturating_sub(1);
+    let stop = (data.partition_point(|(x, _)| *x <= xmax) + 1).min(data.len());
+    let visible = &data[start..stop.max(start)];
+    if n_bins == 0 || visible.len() <= n_bins {
+        return visible.to_vec();
+    }
+
+    visible
+        .chunks(visible.len().div_ceil(n_bins))
+        .map(|run| {
+            run.iter()
+                .copied()
+                .filter(|(_, y)| !y.is_nan())
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                // a run of flagged samples stays a gap in the line
+                .unwrap_or(run[0])
+        })
+        .collect()
+}
+
 #[async_trait]
 // allow dead code or complains in the test compilation mode (no-op)
 #[allow(dead_code)]
@@ -101,3 +866,294 @@ pub trait SpectrumLoader {
     /// Filters the antennas to be plotted based on their string names.
     fn filter_antenna(&mut self, antenna_number: &[String]) -> Result<()>;
 }
+
+/// Health of the link to a live data source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BackendStatus {
+    Connected,
+    Reconnecting,
+    /// Unable to reach the source, it is tried again on the next poll
+    Error,
+}
+impl BackendStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Connected => "connected",
+            Self::Reconnecting => "reconnecting",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// The status of one source, e.g. a data recorder, sent to the interface.
+pub(crate) type StatusUpdate = (String, BackendStatus);
+
+/// Sends the status of a source to the interface whenever it changes.
+#[cfg(any(feature = "ovro", feature = "lwa-na"))]
+#[derive(Debug, Clone)]
+pub(crate) struct StatusReporter {
+    source: String,
+    sender: tokio::sync::mpsc::Sender<StatusUpdate>,
+    last: Option<BackendStatus>,
+}
+#[cfg(any(feature = "ovro", feature = "lwa-na"))]
+impl StatusReporter {
+    pub fn new<S: Into<String>>(
+        source: S,
+        sender: tokio::sync::mpsc::Sender<StatusUpdate>,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            sender,
+            last: None,
+        }
+    }
+
+    pub fn set(&mut self, status: BackendStatus) {
+        if self.last == Some(status) {
+            return;
+        }
+        // the interface may be gone or busy, the same status is retried next time
+        match self.sender.try_send((self.source.clone(), status)) {
+            Ok(()) => self.last = Some(status),
+            Err(err) => log::debug!("Unable to report the status of {}: {err}", self.source),
+        }
+    }
+}
+
+/// Summary statistics of a set of spectrum values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TraceStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std: f64,
+}
+impl TraceStats {
+    /// Computes the statistics of the finite values.
+    ///
+    /// Returns `None` if there are none.
+    pub fn from_values<I: IntoIterator<Item = f64>>(values: I) -> Option<Self> {
+        let values = values
+            .into_iter()
+            .filter(|val| val.is_finite())
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            return None;
+        }
+
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let var = values.iter().map(|val| (val - mean).powi(2)).sum::<f64>() / count;
+
+        Some(Self {
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std: var.sqrt(),
+        })
+    }
+}
+
+/// Data quality reported by the recorder for one trace.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct DataQuality {
+    /// Fraction of the integrations that were filled with data, dropped
+    /// packets bring this below one
+    pub fill: f64,
+    /// Error bits set by the recorder, zero if there were none
+    pub errors: u8,
+}
+impl DataQuality {
+    /// Combines two reports keeping the smaller fill and every error bit.
+    pub fn worst(&self, other: &Self) -> Self {
+        Self {
+            fill: self.fill.min(other.fill),
+            errors: self.errors | other.errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ndarray::{arr1, arr2};
+
+    fn assert_close(expected: f64, val: f64) {
+        assert!(
+            (expected - val).abs() < 1e-9,
+            "expected {expected}, got {val}"
+        );
+    }
+
+    #[test]
+    fn subtract_reference_in_db() {
+        let names = vec!["LWA-001a".to_owned()];
+        let latest = AutoSpectra::new(
+            names.clone(),
+            arr1(&[1.0, 2.0, 3.0]),
+            arr2(&[[4.0, 1.0, 2.0]]),
+            true,
+        );
+        let reference = AutoSpectra::new(names, arr1(&[1.0, 2.0]), arr2(&[[2.0, 2.0]]), true);
+
+        let diff = latest.subtract_reference(&reference);
+        assert_eq!(vec![(1.0, 2.0), (2.0, -1.0), (3.0, 2.0)], diff.spectra[0]);
+
+        // below the reference the dB difference is negative instead of missing
+        let db = &diff.log_spectra[0];
+        assert_eq!(3, db.len());
+        assert_close(10.0 * 2.0_f64.log10(), db[0].1);
+        assert_close(-10.0 * 2.0_f64.log10(), db[1].1);
+        // no reference sample at 3.0, the value is left as is
+        assert_close(10.0 * 2.0_f64.log10(), db[2].1);
+    }
+
+    #[test]
+    fn std_dev() {
+        let spec = |vals: [f64; 3]| {
+            AutoSpectra::new(
+                vec!["a".to_owned()],
+                arr1(&[1.0, 2.0, 3.0]),
+                arr2(&[vals]),
+                false,
+            )
+        };
+
+        assert_eq!(None, AutoSpectra::std_dev(Vec::<&AutoSpectra>::new()));
+        assert_eq!(None, AutoSpectra::std_dev([&spec([1.0, 2.0, 3.0])]));
+        let short = AutoSpectra::new(vec!["a".to_owned()], arr1(&[1.0]), arr2(&[[1.0]]), false);
+        assert_eq!(None, AutoSpectra::std_dev([&spec([1.0, 2.0, 3.0]), &short]));
+
+        let std = AutoSpectra::std_dev([
+            &spec([1.0, 2.0, f64::NAN]),
+            &spec([3.0, 2.0, 1.0]),
+            &spec([5.0, 2.0, 1.0]),
+        ])
+        .unwrap();
+        assert_close(2.0, std[0][0]);
+        // a constant channel has no spread, a NaN sample leaves it unknown
+        assert_eq!(0.0, std[0][1]);
+        assert!(std[0][2].is_nan());
+    }
+
+    #[test]
+    fn offset_by() {
+        let spec = AutoSpectra::new(
+            vec!["a".to_owned()],
+            arr1(&[1.0, 2.0, 3.0]),
+            arr2(&[[10.0, 20.0, 30.0]]),
+            false,
+        );
+
+        assert!(spec.offset_by(&[], 1.0).is_none());
+        assert!(spec.offset_by(&[vec![1.0, 2.0]], 1.0).is_none());
+
+        let lower = spec.offset_by(&[vec![1.0, 20.0, f64::NAN]], -1.0).unwrap();
+        assert_eq!(spec.ant_names, lower.ant_names);
+        assert_eq!((1.0, 9.0), lower.spectra[0][0]);
+        assert_eq!((2.0, 0.0), lower.spectra[0][1]);
+        assert!(lower.spectra[0][2].1.is_nan());
+        // only the positive, finite values have a dB value
+        assert_eq!(vec![(1.0, 10.0 * 9.0_f64.log10())], lower.log_spectra[0]);
+    }
+
+    #[test]
+    fn quantiles() {
+        assert_eq!(None, quantile_of(vec![], 0.1));
+        assert_eq!(None, quantile_of(vec![f64::NAN, f64::INFINITY], 0.1));
+
+        let vals = vec![3.0, f64::NAN, 1.0, 2.0, f64::NEG_INFINITY];
+        assert_eq!(Some(1.0), quantile_of(vals.clone(), 0.0));
+        assert_eq!(Some(2.0), quantile_of(vals.clone(), 0.5));
+        assert_eq!(Some(3.0), quantile_of(vals.clone(), 1.0));
+        // out of range quantiles are clamped
+        assert_eq!(Some(3.0), quantile_of(vals, 2.0));
+    }
+
+    #[test]
+    fn noise_floors() {
+        let spec = AutoSpectra::new(
+            vec!["a".to_owned(), "b".to_owned(), "dead".to_owned()],
+            arr1(&[1.0, 2.0, 3.0]),
+            arr2(&[
+                [1.0, 10.0, 100.0],
+                [f64::NAN, 100.0, 10.0],
+                [0.0, f64::NAN, 0.0],
+            ]),
+            true,
+        );
+
+        // NaN samples are skipped and a trace with no dB values has no floor
+        let floors = spec.noise_floors(0.0);
+        assert_eq!(vec![("a".to_owned(), 0.0), ("b".to_owned(), 10.0)], floors);
+
+        let mut above = spec.clone();
+        above.subtract_floors(&[("a".to_owned(), 10.0)]);
+        assert_eq!(
+            vec![(1.0, -10.0), (2.0, 0.0), (3.0, 10.0)],
+            above.log_spectra[0]
+        );
+        // traces without a floor use the median of the others
+        assert_eq!(vec![(2.0, 10.0), (3.0, 0.0)], above.log_spectra[1]);
+        assert!(above.log_spectra[2].is_empty());
+        // only the dB values move
+        assert_eq!(spec.spectra[0], above.spectra[0]);
+
+        // without any floors nothing changes
+        let mut unchanged = spec.clone();
+        unchanged.subtract_floors(&[]);
+        assert_eq!(spec.log_spectra, unchanged.log_spectra);
+    }
+
+    #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+    #[test]
+    fn status_retried_when_dropped() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        let mut reporter = StatusReporter::new("dr1", sender);
+
+        reporter.set(BackendStatus::Connected);
+        // the channel is full, so this change is dropped
+        reporter.set(BackendStatus::Error);
+        assert_eq!(
+            ("dr1".to_owned(), BackendStatus::Connected),
+            receiver.try_recv().unwrap()
+        );
+
+        reporter.set(BackendStatus::Error);
+        assert_eq!(
+            ("dr1".to_owned(), BackendStatus::Error),
+            receiver.try_recv().unwrap()
+        );
+        // unchanged status is not sent again
+        reporter.set(BackendStatus::Error);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn decimate_max_bins() {
+        let mut data: Vec<(f64, f64)> = (0..100).map(|x| (x as f64, 0.0)).collect();
+        data[37].1 = 10.0;
+        for sample in &mut data[60..70] {
+            sample.1 = f64::NAN;
+        }
+
+        assert!(decimate_max(&[], 0.0, 1.0, 10).is_empty());
+
+        let thinned = decimate_max(&data, f64::NEG_INFINITY, f64::INFINITY, 10);
+        assert_eq!(10, thinned.len());
+        // the spike survives the thinning
+        assert_eq!((37.0, 10.0), thinned[3]);
+        // a run of flagged samples stays flagged
+        assert_eq!(60.0, thinned[6].0);
+        assert!(thinned[6].1.is_nan());
+
+        // only the nearest sample is kept outside of the data
+        assert_eq!(vec![(99.0, 0.0)], decimate_max(&data, 200.0, 300.0, 10));
+        assert_eq!(vec![(0.0, 0.0)], decimate_max(&data, -20.0, -10.0, 10));
+
+        let reversed = decimate_max(&data, 50.0, 20.0, 100);
+        assert_eq!(33, reversed.len());
+        assert_eq!(decimate_max(&data, 20.0, 50.0, 100), reversed);
+    }
+}
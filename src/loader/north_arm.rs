@@ -1,8 +1,8 @@
 #![allow(dead_code)]
 
 use std::{
-    fs,
-    io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom},
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
     net::TcpStream,
     path::{Path, PathBuf},
     time::Duration,
@@ -12,17 +12,12 @@ use std::{
 use anyhow::{anyhow, bail, ensure, Context, Result};
 use async_trait::async_trait;
 use byteorder::{LittleEndian, ReadBytesExt};
-use hifitime::Epoch;
+use hifitime::{Epoch, TimeScale};
 use ndarray::{Array, Axis, Ix1, Ix2, Ix3};
-use ratatui::{
-    layout::Constraint,
-    style::{Color, Style},
-    text::Text,
-    widgets::{Cell, Row, Table},
-};
+use serde::Serialize;
 use ssh2::{ErrorCode, Session, Sftp};
 
-use crate::loader::{AutoSpectra, SpectrumLoader};
+use crate::loader::{AutoSpectra, BackendStatus, DataQuality, SpectrumLoader, StatusReporter};
 
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -93,6 +88,25 @@ impl PolarizationType {
     }
 }
 
+/// Which of the two DR tunings to plot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Tunings {
+    #[default]
+    Both,
+    Tuning1,
+    Tuning2,
+}
+impl Tunings {
+    /// Cycles Both -> Tuning 1 -> Tuning 2 -> Both
+    pub fn next(self) -> Self {
+        match self {
+            Self::Both => Self::Tuning1,
+            Self::Tuning1 => Self::Tuning2,
+            Self::Tuning2 => Self::Both,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 /// 1, 5, and 10 minute rolling averages
 /// used for providing updating statisics on saturation
@@ -100,13 +114,40 @@ pub(crate) struct Stats {
     avg1: f64,
     avg5: f64,
     avg10: f64,
+    /// The most recent saturation fraction
+    latest: f64,
+    /// The saturation fraction of the poll before that
+    previous: Option<f64>,
 }
 impl Stats {
+    /// Changes in the saturated fraction smaller than this are shown as steady
+    const TREND_TOLERANCE: f64 = 1e-4;
+
     pub fn new(saturation: f64) -> Self {
         Self {
             avg1: saturation,
             avg5: saturation,
             avg10: saturation,
+            latest: saturation,
+            previous: None,
+        }
+    }
+
+    /// The 1, 5 and 10 minute averages.
+    pub fn averages(&self) -> [f64; 3] {
+        [self.avg1, self.avg5, self.avg10]
+    }
+
+    pub fn latest(&self) -> f64 {
+        self.latest
+    }
+
+    /// An arrow showing if saturation went up or down since the previous poll.
+    pub fn trend(&self) -> &'static str {
+        match self.previous.map(|previous| self.latest - previous) {
+            Some(delta) if delta > Self::TREND_TOLERANCE => "↑",
+            Some(delta) if delta < -Self::TREND_TOLERANCE => "↓",
+            _ => "→",
         }
     }
     /// Update the rolling stats with the new data point
@@ -117,6 +158,69 @@ impl Stats {
         self.avg1 = self.avg1 + (saturation - self.avg1) / n_per_min;
         self.avg5 = self.avg5 + (saturation - self.avg5) / (5.0 * n_per_min);
         self.avg10 = self.avg10 + (saturation - self.avg10) / (10.0 * n_per_min);
+        self.previous = Some(self.latest);
+        self.latest = saturation;
+    }
+}
+
+/// The saturated fraction of one pol/tuning at one time, a row of the saturation log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct SaturationSample {
+    /// When the spectra were recorded, in UTC
+    epoch: String,
+    pol: String,
+    tuning: u8,
+    saturation: f64,
+}
+
+/// The saturation of every poll, appended and flushed as it arrives so an
+/// unclean exit loses nothing.
+#[derive(Debug)]
+pub(crate) struct SaturationLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    /// One JSON object per line instead of CSV
+    json: bool,
+}
+impl SaturationLog {
+    /// Creates the log at `path`, as JSON lines if it ends in `.json` or
+    /// `.jsonl` and as CSV otherwise.
+    pub fn create(path: PathBuf) -> Result<Self> {
+        let file =
+            File::create(&path).with_context(|| format!("Unable to create {}", path.display()))?;
+        let json = path
+            .extension()
+            .is_some_and(|ext| ext == "json" || ext == "jsonl");
+        let mut writer = BufWriter::new(file);
+        if !json {
+            writeln!(writer, "epoch,pol,tuning,saturation")?;
+        }
+        Ok(Self { path, writer, json })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the latest saturation of every pol/tuning in `stats`, taken at
+    /// `epoch`, and writes it to disk.
+    pub fn append(&mut self, stats: &SaturationStats, epoch: Epoch) -> Result<()> {
+        for sample in stats.samples(epoch) {
+            match self.json {
+                true => {
+                    serde_json::to_writer(&mut self.writer, &sample)?;
+                    writeln!(self.writer)?;
+                }
+                false => writeln!(
+                    self.writer,
+                    "{},{},{},{}",
+                    sample.epoch, sample.pol, sample.tuning, sample.saturation
+                )?,
+            }
+        }
+        self.writer
+            .flush()
+            .with_context(|| format!("Unable to write {}", self.path.display()))
     }
 }
 
@@ -130,6 +234,12 @@ pub(crate) struct SaturationStats {
 }
 impl SaturationStats {
     pub fn update(&mut self, other: Self, rate: f64) {
+        // the set of polarizations (or data recorders) changed, start over
+        if self.pols != other.pols {
+            *self = other;
+            return;
+        }
+
         self.tuning1
             .iter_mut()
             .zip(other.tuning1.iter())
@@ -141,80 +251,57 @@ impl SaturationStats {
             .for_each(|(stat, new)| stat.update(new.avg1, rate));
     }
 
-    pub fn as_table(&self) -> Table {
-        let header = ["pol", "1min", "5min", "10min"]
-            .into_iter()
-            .map(Cell::from)
-            .collect::<Row>()
-            .style(Style::default())
-            .height(1);
+    /// Labels every polarization with the data recorder it came from.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.pols
+            .iter_mut()
+            .for_each(|pol| *pol = format!("{host}:{pol}"));
+        self
+    }
 
-        let rows = self
-            .pols
-            .iter()
-            .zip(self.tuning1.iter())
-            .map(|(pol, stat)| {
-                // iterate over pol/stats and collect into a row
-                Row::new(vec![
-                    Cell::from(Text::styled(format!("{:6< }{}", pol, 0), Color::Gray)),
-                    Cell::from(Text::styled(
-                        format!("{:0>5.2}", stat.avg1 * 100.0),
-                        Color::Gray,
-                    )),
-                    Cell::from(Text::styled(
-                        format!("{:0>5.2}", stat.avg5 * 100.0),
-                        Color::Gray,
-                    )),
-                    Cell::from(Text::styled(
-                        format!("{:0>5.2}", stat.avg10 * 100.0),
-                        Color::Gray,
-                    )),
-                ])
+    /// The latest saturated fraction of every pol/tuning, taken at `epoch`.
+    pub fn samples(&self, epoch: Epoch) -> Vec<SaturationSample> {
+        let epoch = epoch.to_time_scale(TimeScale::UTC).to_string();
+        [(1, &self.tuning1), (2, &self.tuning2)]
+            .into_iter()
+            .flat_map(|(tuning, stats)| {
+                self.pols
+                    .iter()
+                    .zip(stats)
+                    .map(move |(pol, stat)| SaturationSample {
+                        epoch: epoch.clone(),
+                        pol: pol.clone(),
+                        tuning,
+                        saturation: stat.latest,
+                    })
             })
-            .chain(
+            .collect()
+    }
+
+    /// Combines the statistics of several data recorders into one table.
+    pub fn stack<I: IntoIterator<Item = SaturationStats>>(stats: I) -> Option<Self> {
+        stats.into_iter().reduce(|mut acc, stat| {
+            acc.tuning1.extend(stat.tuning1);
+            acc.tuning2.extend(stat.tuning2);
+            acc.pols.extend(stat.pols);
+            acc
+        })
+    }
+
+    /// Label and statistics of every pol/tuning, the first tuning first.
+    pub fn rows(&self) -> impl Iterator<Item = (String, &Stats)> {
+        [(0, &self.tuning1), (1, &self.tuning2)]
+            .into_iter()
+            .flat_map(|(tuning, stats)| {
                 self.pols
                     .iter()
-                    .zip(self.tuning2.iter())
-                    .map(|(pol, stat)| {
-                        // iterate over pol/stats and collect into a row
-                        Row::new(vec![
-                            Cell::from(Text::styled(format!("{:6< }{}", pol, 1), Color::Gray)),
-                            Cell::from(Text::styled(
-                                format!("{:0>5.2}", stat.avg1 * 100.0),
-                                Color::Gray,
-                            )),
-                            Cell::from(Text::styled(
-                                format!("{:0>5.2}", stat.avg5 * 100.0),
-                                Color::Gray,
-                            )),
-                            Cell::from(Text::styled(
-                                format!("{:0>5.2}", stat.avg10 * 100.0),
-                                Color::Gray,
-                            )),
-                        ])
-                    }),
-            );
+                    .zip(stats)
+                    .map(move |(pol, stat)| (format!("{:6< }{}", pol, tuning), stat))
+            })
+    }
 
-        Table::new(
-            rows,
-            [
-                Constraint::Length(7),
-                Constraint::Length(5),
-                Constraint::Length(5),
-                Constraint::Length(5),
-            ],
-        )
-        .header(header)
-        .style(Style::default())
-        .block(
-            ratatui::widgets::Block::default()
-                .title(ratatui::text::Span::styled(
-                    "Saturation Statistics",
-                    Style::default(),
-                ))
-                .borders(ratatui::widgets::Borders::ALL)
-                .style(Style::default()),
-        )
+    pub fn pols(&self) -> &[String] {
+        &self.pols
     }
 }
 
@@ -272,7 +359,10 @@ impl DRHeader {
     const SYNC_FOOTER: u32 = 0xED0CED0C_u32;
     const LEN: usize = 76;
 
-    const CLOCK_SPEED: f64 = 196.0e6;
+    /// DP sampling clock in Hz, time tags count ticks of this clock
+    pub const CLOCK_SPEED: f64 = 196.0e6;
+    /// [Self::CLOCK_SPEED] as a whole number of ticks per second
+    const TICKS_PER_SECOND: u64 = 196_000_000;
 
     pub fn from_bytes<R: Read>(buffer: &mut R) -> Result<Self> {
         let header = buffer.read_u32::<LittleEndian>()?;
@@ -350,6 +440,38 @@ impl DRHeader {
         Self::from_bytes(&mut buffer)
     }
 
+    /// Fraction of samples saturated for a polarization product on one tuning.
+    ///
+    /// Cross products and Stokes parameters depend on both inputs so the
+    /// worse of X and Y is used.
+    pub fn saturation_fraction(&self, pol: &str, tuning: usize) -> f64 {
+        let n_samples = self.n_ints as f64 * self.n_freqs as f64;
+        let x = self.saturation_count[2 * tuning] as f64 / n_samples;
+        let y = self.saturation_count[2 * tuning + 1] as f64 / n_samples;
+        match pol {
+            "XX" => x,
+            "YY" => y,
+            _ => x.max(y),
+        }
+    }
+
+    /// Fill fraction and error bits for a polarization product on one tuning.
+    ///
+    /// As with [Self::saturation_fraction] products of X and Y report the
+    /// worse of the two inputs.
+    pub fn data_quality(&self, pol: &str, tuning: usize) -> DataQuality {
+        let input = |index: usize| DataQuality {
+            fill: self.fills[index] as f64 / self.n_ints.max(1) as f64,
+            errors: self.errors[index],
+        };
+        let (x, y) = (input(2 * tuning), input(2 * tuning + 1));
+        match pol {
+            "XX" => x,
+            "YY" => y,
+            _ => x.worst(&y),
+        }
+    }
+
     /// Calculate the % of integrations that are saturated per pol per tuning
     pub fn calc_saturation(&self) -> SaturationStats {
         let tmp_sats = self
@@ -452,28 +574,67 @@ impl DRHeader {
         }
     }
 
-    fn calc_freq(tunings: u32) -> f64 {
+    /// Center frequency in Hz of a DP tuning word.
+    pub fn calc_freq(tunings: u32) -> f64 {
         tunings as f64 * Self::CLOCK_SPEED / 2_f64.powi(32)
     }
 
-    fn calc_tuning(freq: f64) -> u32 {
-        (freq * 2_f64.powi(32) / Self::CLOCK_SPEED).round() as u32
+    /// DP tuning word closest to a center frequency in Hz, the inverse of [Self::calc_freq].
+    ///
+    /// Returns `None` for frequencies no tuning word reaches,
+    /// below 0 or at and above [Self::CLOCK_SPEED].
+    pub fn calc_tuning(freq: f64) -> Option<u32> {
+        let word = (freq * 2_f64.powi(32) / Self::CLOCK_SPEED).round();
+        (0.0..=u32::MAX as f64)
+            .contains(&word)
+            .then_some(word as u32)
     }
 
-    fn calc_epoch(time_tag: u64, offset: u16) -> Epoch {
-        let tt = time_tag - offset as u64;
-        Epoch::from_unix_seconds(tt as f64 / Self::CLOCK_SPEED)
+    /// The tuning whose center frequency is closest to `freq` in Hz.
+    pub fn nearest_tuning(&self, freq: f64) -> Tunings {
+        match (self.frequencies[0] - freq).abs() <= (self.frequencies[1] - freq).abs() {
+            true => Tunings::Tuning1,
+            false => Tunings::Tuning2,
+        }
     }
 
-    fn calc_timetag(&self) -> u64 {
-        let seconds = self.timestamp.to_unix_seconds().floor() as u64;
+    /// Converts a count of DP clock ticks since the unix epoch to an [Epoch].
+    ///
+    /// Whole seconds and the remaining ticks are converted separately so the
+    /// time is exact to the nanosecond, well within one tick (~5.1 ns).
+    pub fn clock_to_epoch(ticks: u64) -> Epoch {
+        let seconds = ticks / Self::TICKS_PER_SECOND;
+        let remainder = ticks % Self::TICKS_PER_SECOND;
+        // nearest nanosecond, this fits easily in a u64
+        let nanos =
+            (remainder * 1_000_000_000 + Self::TICKS_PER_SECOND / 2) / Self::TICKS_PER_SECOND;
+
+        Epoch::from_unix_duration(hifitime::Duration::from_total_nanoseconds(
+            seconds as i128 * 1_000_000_000 + nanos as i128,
+        ))
+    }
 
-        let sec_frac = self.timestamp - hifitime::Epoch::from_unix_seconds(seconds as f64);
+    /// Converts an [Epoch] to the nearest count of DP clock ticks since the unix epoch.
+    ///
+    /// Times before the unix epoch give 0.
+    pub fn epoch_to_clock(epoch: Epoch) -> u64 {
+        let unix = epoch.to_utc_duration()
+            - Epoch::from_unix_duration(hifitime::Duration::ZERO).to_utc_duration();
+        let nanos = u64::try_from(unix.total_nanoseconds()).unwrap_or_default();
+
+        let seconds = nanos / 1_000_000_000;
+        let remainder = nanos % 1_000_000_000;
+        seconds * Self::TICKS_PER_SECOND
+            + (remainder * Self::TICKS_PER_SECOND + 500_000_000) / 1_000_000_000
+    }
 
-        let mut tt = seconds * Self::CLOCK_SPEED as u64;
-        tt += (sec_frac.to_unit(hifitime::Unit::Millisecond) * Self::CLOCK_SPEED).floor() as u64
-            / 1000;
-        tt
+    fn calc_epoch(time_tag: u64, offset: u16) -> Epoch {
+        Self::clock_to_epoch(time_tag - offset as u64)
+    }
+
+    /// The time tag as written in the file, the inverse of [Self::calc_epoch].
+    fn calc_timetag(&self) -> u64 {
+        Self::epoch_to_clock(self.timestamp) + self.time_offset as u64
     }
 
     fn len_bytes(&self) -> usize {
@@ -499,6 +660,42 @@ impl DRHeader {
     }
 }
 
+/// Where the fixed size spectra are in a data recorder file, used to read
+/// only the newest spectrum without scanning the file again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileLayout {
+    /// Offset of the first spectrum
+    start: u64,
+    /// Size of one header and its spectrum
+    record_len: u64,
+}
+impl FileLayout {
+    /// Finds the first spectrum in the file and the size of each spectrum.
+    pub fn from_reader<R: Read + Seek>(buffer: &mut BufReader<R>) -> Result<Self> {
+        DRSpectrum::find_next_spectra(buffer)?;
+        let start = buffer.stream_position()?;
+        let header = DRHeader::from_bytes(buffer)?;
+        log::info!(
+            "Reading beam {} from spectrometer version {} data.",
+            header.beam,
+            header.specrometer_version
+        );
+
+        Ok(Self {
+            start,
+            record_len: (header.len_bytes() + DRHeader::LEN) as u64,
+        })
+    }
+
+    /// Offset of the last complete spectrum in a file of `size` bytes.
+    pub fn last_record(&self, size: u64) -> Option<u64> {
+        let n_records = size.checked_sub(self.start)? / self.record_len;
+        n_records
+            .checked_sub(1)
+            .map(|last| self.start + last * self.record_len)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct DRSpectrum {
     /// Metadata information about this spectrum
@@ -639,31 +836,132 @@ impl DRSpectrum {
                 .insert_axis(Axis(1))
         };
 
+        // a zero fill means nothing was integrated, flag those channels as NaN
+        // instead of dividing by zero
+        let dead_norms = data_norms.iter().filter(|norm| **norm == 0.0).count();
+        if dead_norms > 0 {
+            log::warn!(
+                "{} channels have no integrations (zero fill), flagging them.",
+                dead_norms * header.n_freqs as usize
+            );
+        }
+        let data_norms = data_norms.mapv(|norm| match norm == 0.0 {
+            true => f64::NAN,
+            false => norm,
+        });
+
         // divide out the normalization factors
         data = data / data_norms;
 
         Ok(Self { header, data })
     }
 
-    pub fn into_autospectra(self) -> AutoSpectra {
+    /// Packages the spectrum for plotting, keeping only the polarizations in `pols`.
+    ///
+    /// Polarizations are matched case-insensitively against [PolarizationType::desription].
+    /// An empty `pols` keeps every polarization.
+    ///
+    /// Each tuning is emitted as its own trace so the gap between them is preserved.
+    pub fn into_autospectra(self, pols: &[String], tunings: Tunings) -> AutoSpectra {
         // package the data up
         // transform to MHz
         let Self { header, data } = self;
-        let descriptions = header.stokes_format.desription();
         let freqs = header.get_freqs().map(|x| x / 1e6);
 
-        let mut data_out =
-            Array::<f64, Ix2>::zeros((descriptions.len(), 2 * header.n_freqs as usize));
+        let (descriptions, pol_data): (Vec<_>, Vec<_>) = header
+            .stokes_format
+            .desription()
+            .into_iter()
+            .zip(data.axis_iter(Axis(2)))
+            .filter(|(name, _)| {
+                pols.is_empty() || pols.iter().any(|pol| pol.eq_ignore_ascii_case(name))
+            })
+            .unzip();
+
+        let tunings: &[usize] = match tunings {
+            Tunings::Both => &[0, 1],
+            Tunings::Tuning1 => &[0],
+            Tunings::Tuning2 => &[1],
+        };
 
-        for (mut inner_data_out, polarization_data) in
-            data_out.outer_iter_mut().zip(data.axis_iter(Axis(2)))
+        // pol_data are (n_tunings, nfreqs) views, split them back up by tuning
+        // each trace gets the frequencies of its own tuning
+        let n_traces = tunings.len() * descriptions.len();
+        let mut freqs_out = Array::<f64, Ix2>::zeros((n_traces, header.n_freqs as usize));
+        let mut data_out = Array::<f64, Ix2>::zeros((n_traces, header.n_freqs as usize));
+        let mut names = Vec::with_capacity(n_traces);
+        let mut saturation = Vec::with_capacity(n_traces);
+        let mut quality = Vec::with_capacity(n_traces);
+
+        let traces = tunings.iter().flat_map(|tuning| {
+            descriptions
+                .iter()
+                .zip(pol_data.iter())
+                .map(move |(name, polarization_data)| (*tuning, name, polarization_data))
+        });
+        for ((mut freq_row, mut data_row), (tuning, name, polarization_data)) in freqs_out
+            .outer_iter_mut()
+            .zip(data_out.outer_iter_mut())
+            .zip(traces)
         {
-            inner_data_out.assign(&polarization_data.flatten());
+            freq_row.assign(&freqs.row(tuning));
+            data_row.assign(&polarization_data.row(tuning));
+            names.push(format!("{name} T{}", tuning + 1));
+            saturation.push(header.saturation_fraction(name, tuning));
+            quality.push(header.data_quality(name, tuning));
+        }
+
+        let mut spec = AutoSpectra::with_freqs(names, freqs_out, data_out, false);
+        spec.saturation = Some(saturation);
+        spec.quality = Some(quality);
+        spec.timestamp = Some(header.timestamp);
+        spec.beams = vec![header.beam];
+        spec.tunings = tunings
+            .iter()
+            .map(|tuning| (*tuning as u8 + 1, header.frequencies[*tuning] / 1e6))
+            .collect();
+        spec
+    }
+
+    /// Computes Stokes I, Q, U, and V from the four linear products.
+    ///
+    /// Uses I = XX + YY, Q = XX - YY, U = 2 Re(XY), and V = -2 Im(XY).
+    /// Returns `None` unless the spectrum was recorded as `LinearFull`.
+    pub fn to_stokes(&self) -> Option<Self> {
+        if self.header.stokes_format != PolarizationType::LinearFull {
+            return None;
         }
 
-        let flat_freqs = freqs.flatten().to_owned();
+        // the last axis is ordered XX, Re(XY), Im(XY), YY
+        let product = |index: usize| self.data.index_axis(Axis(2), index);
+        let (xx, re_xy, im_xy, yy) = (product(0), product(1), product(2), product(3));
+
+        let data = ndarray::stack![Axis(2), &xx + &yy, &xx - &yy, &re_xy * 2.0, &im_xy * -2.0];
 
-        AutoSpectra::new(descriptions, flat_freqs, data_out, false)
+        let mut header = self.header.clone();
+        header.stokes_format = PolarizationType::StokesFull;
+
+        Some(Self { header, data })
+    }
+
+    /// Converts to plotted spectra, first computing the Stokes parameters if
+    /// `stokes` is set.
+    ///
+    /// `stokes` is switched off if the spectrum does not have the linear
+    /// products needed.
+    fn prepare(self, pols: &[String], tunings: Tunings, stokes: &mut bool) -> AutoSpectra {
+        let spectrum = match *stokes {
+            true => self.to_stokes().unwrap_or_else(|| {
+                log::warn!(
+                    "Stokes parameters need LinearFull data, this file has {:?}.",
+                    self.header.stokes_format
+                );
+                *stokes = false;
+                self
+            }),
+            false => self,
+        };
+        spectrum.into_autospectra(pols, tunings)
     }
 }
 
@@ -673,18 +971,91 @@ pub(crate) struct DiskLoader {
     file: PathBuf,
 
     saturations: Option<SaturationStats>,
+
+    /// Byte offsets of the spectra located in the file so far
+    offsets: Vec<u64>,
+
+    /// Index of the spectrum returned by the next call to `get_data`
+    index: usize,
+
+    /// Polarizations to plot, empty for all
+    pols: Vec<String>,
+
+    /// Tunings to plot
+    tunings: Tunings,
+
+    /// Center frequency in MHz to plot the nearest tuning of, overrides `tunings`
+    center_freq: Option<f64>,
+
+    /// Plot Stokes parameters computed from the linear products
+    stokes: bool,
 }
 impl DiskLoader {
     pub fn new(input_file: PathBuf) -> Self {
         Self {
             file: input_file,
             saturations: None,
+            offsets: Vec::new(),
+            index: 0,
+            pols: Vec::new(),
+            tunings: Tunings::default(),
+            center_freq: None,
+            stokes: false,
         }
     }
 
+    /// Plots only the tuning centered closest to `freq` MHz.
+    pub fn with_center_freq(mut self, freq: Option<f64>) -> Self {
+        self.center_freq = freq;
+        self
+    }
+
+    pub fn cycle_tunings(&mut self) {
+        self.center_freq = None;
+        self.tunings = self.tunings.next();
+        log::info!("Plotting tunings: {:?}", self.tunings);
+    }
+
+    pub fn toggle_stokes(&mut self) {
+        self.stokes = !self.stokes;
+        log::info!(
+            "Plotting {} products.",
+            if self.stokes { "Stokes" } else { "recorded" }
+        );
+    }
+
     pub fn get_stats(&self) -> Option<SaturationStats> {
         self.saturations.clone()
     }
+
+    /// Moves the cursor relative to the most recently read spectrum.
+    ///
+    /// A step of 1 is the same as the next `get_data` call, -1 re-reads the previous spectrum.
+    pub fn step(&mut self, step: isize) {
+        self.index = (self.index as isize + step - 1).max(0) as usize;
+    }
+
+    /// Finds the byte offset of spectrum `index`, scanning forward
+    /// through the file as needed. Returns `None` past the end of the file.
+    fn offset_of<R: Read + Seek>(
+        &mut self,
+        reader: &mut BufReader<R>,
+        index: usize,
+    ) -> Result<Option<u64>> {
+        while self.offsets.len() <= index {
+            let start = self
+                .offsets
+                .last()
+                .map_or(0, |offset| offset + DRHeader::LEN as u64);
+            reader.seek(SeekFrom::Start(start))?;
+
+            if DRSpectrum::find_next_spectra(reader).is_err() {
+                return Ok(None);
+            }
+            self.offsets.push(reader.stream_position()?);
+        }
+        Ok(Some(self.offsets[index]))
+    }
 }
 #[async_trait]
 impl SpectrumLoader for DiskLoader {
@@ -696,41 +1067,186 @@ impl SpectrumLoader for DiskLoader {
                 .with_context(|| format!("Unable to open {}", self.file.display()))
                 .ok()?,
         );
+
+        let offset = match self.offset_of(&mut file_handle, self.index).ok()? {
+            Some(offset) => offset,
+            None if self.index > 0 => {
+                log::info!("Reached the end of {}, wrapping.", self.file.display());
+                self.index = 0;
+                self.offset_of(&mut file_handle, 0).ok()??
+            }
+            None => {
+                log::error!("No spectra found in {}", self.file.display());
+                return None;
+            }
+        };
+        file_handle.seek(SeekFrom::Start(offset)).ok()?;
+
         let spec = DRSpectrum::from_bytes(&mut file_handle).ok()?;
+        if self.index == 0 {
+            log::info!(
+                "Reading beam {} from spectrometer version {} data.",
+                spec.header.beam,
+                spec.header.specrometer_version
+            );
+        }
+        log::info!(
+            "Showing spectrum {} from {}",
+            self.index,
+            self.file.display()
+        );
+        self.index += 1;
+
         let saturation = spec.header.calc_saturation();
 
         self.saturations.replace(saturation);
 
-        Some(spec.into_autospectra())
+        pick_tuning(&mut self.tunings, self.center_freq, &spec.header);
+        Some(spec.prepare(&self.pols, self.tunings, &mut self.stokes))
     }
 
-    /// Filters the antennas to be plotted based on their string names.
-    fn filter_antenna(&mut self, _antenna_number: &[String]) -> Result<()> {
+    /// Filters the polarizations to be plotted based on their string names.
+    fn filter_antenna(&mut self, antenna_number: &[String]) -> Result<()> {
+        self.pols = antenna_number.to_vec();
         Ok(())
     }
 }
 
+/// Switches `tunings` to the one centered closest to `center_freq` MHz, if given.
+fn pick_tuning(tunings: &mut Tunings, center_freq: Option<f64>, header: &DRHeader) {
+    let Some(freq) = center_freq else {
+        return;
+    };
+    let nearest = header.nearest_tuning(freq * 1e6);
+    if nearest != *tunings {
+        log::info!("Plotting {nearest:?}, the tuning closest to {freq} MHz.");
+        *tunings = nearest;
+    }
+}
+
+/// Splits `user@host` into the user and host, using `default_user` when
+/// only a host is given.
+fn split_user_host<'a>(target: &'a str, default_user: &'a str) -> (&'a str, &'a str) {
+    match target.split_once('@') {
+        Some((user, host)) if !user.is_empty() => (user, host),
+        Some((_, host)) => (default_user, host),
+        None => (default_user, target),
+    }
+}
+
+/// libssh2 session error codes that mean the connection itself is gone
+const CONNECTION_ERRORS: [i32; 5] = [
+    -7,  // LIBSSH2_ERROR_SOCKET_SEND
+    -9,  // LIBSSH2_ERROR_TIMEOUT
+    -13, // LIBSSH2_ERROR_SOCKET_DISCONNECT
+    -30, // LIBSSH2_ERROR_SOCKET_TIMEOUT
+    -43, // LIBSSH2_ERROR_SOCKET_RECV
+];
+
+/// Checks if an error was caused by losing the connection to the data recorder.
+fn is_connection_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(error) = cause.downcast_ref::<ssh2::Error>() {
+            match error.code() {
+                ErrorCode::Session(code) => CONNECTION_ERRORS.contains(&code),
+                // LIBSSH2_FX_NO_CONNECTION and LIBSSH2_FX_CONNECTION_LOST
+                ErrorCode::SFTP(code) => code == 6 || code == 7,
+            }
+        } else if let Some(error) = cause.downcast_ref::<std::io::Error>() {
+            matches!(
+                error.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::NotConnected
+                    | ErrorKind::TimedOut
+            )
+        } else {
+            false
+        }
+    })
+}
+
+/// How to authenticate with the data recorders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub(crate) enum SshAuth {
+    /// Try the keys in the SSH agent, then the identity file
+    #[default]
+    Auto,
+    /// Only use the keys in the SSH agent
+    Agent,
+    /// Only use the identity file
+    Key,
+}
+
+/// Prefix of a data recorder that names a local file to follow instead
+const LOCAL_PREFIX: &str = "file:";
+
+/// Where a [DRLoader] reads its spectrum files from
+enum DRSource {
+    /// The data recorder's storage, over SFTP
+    Remote(Sftp),
+    /// A file on the local filesystem, e.g. one copied from a data recorder
+    Local,
+}
+
 /// A Spectrum loader for the LWA North Arm
 /// connects to the datarecorder and reads from the spectrum
 /// file on disk
+///
+/// Given `file:` and the path of a local file instead of a data recorder it
+/// follows that file as it grows, the same way it follows a recorder's newest file.
 pub struct DRLoader {
     /// The DataRecorder this loader listens to
     pub data_recorder: String,
 
+    /// Account to log in to the data recorder as
+    username: String,
+
+    /// SSH identity file used to (re)connect to the data recorder
+    identity_file: PathBuf,
+
+    /// How to authenticate when (re)connecting
+    auth: SshAuth,
+
     /// DataRecorder spectrum file
     pub filename: Option<PathBuf>,
 
     /// the basename of the file we are reading
     pub file_tag: Option<String>,
 
-    /// SFTP session use to query for new files and read data
-    sftp: Sftp,
+    /// SFTP session used to query for new files and read data, unless reading a local file
+    source: DRSource,
 
     /// the last timestamp data was gathered for
     last_timestamp: Epoch,
 
     /// Saturation statistics
     saturation: Option<SaturationStats>,
+
+    /// Polarizations to plot, empty for all
+    pols: Vec<String>,
+
+    /// Tunings to plot
+    tunings: Tunings,
+
+    /// Center frequency in MHz to plot the nearest tuning of, overrides `tunings`
+    center_freq: Option<f64>,
+
+    /// Plot Stokes parameters computed from the linear products
+    stokes: bool,
+
+    /// Layout of the current file, found on the first read
+    layout: Option<FileLayout>,
+
+    /// Offset just past the last spectrum read from the current file
+    read_until: u64,
+
+    /// The last spectrum read, returned again if nothing new was written
+    last_spectrum: Option<DRSpectrum>,
+
+    /// Reports the health of the connection to the interface
+    status: Option<StatusReporter>,
 }
 impl std::fmt::Debug for DRLoader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -741,8 +1257,69 @@ impl std::fmt::Debug for DRLoader {
     }
 }
 impl DRLoader {
-    pub fn new<P: AsRef<str>, R: AsRef<Path>>(data_recorder: P, identity_file: R) -> Result<Self> {
-        let data_recorder = data_recorder.as_ref();
+    /// Connects to `data_recorder`, given as `host` or `user@host`.
+    ///
+    /// `username` is used when the host does not name a user. If
+    /// `data_recorder` is `file:` followed by a path that local file is
+    /// followed instead, a bare path is always taken as a host.
+    pub fn new<P: AsRef<str>, R: AsRef<Path>>(
+        data_recorder: P,
+        identity_file: R,
+        username: &str,
+        auth: SshAuth,
+    ) -> Result<Self> {
+        let (username, data_recorder, source, filename) =
+            match data_recorder.as_ref().strip_prefix(LOCAL_PREFIX) {
+                Some(local) => {
+                    let local = Path::new(local);
+                    ensure!(local.is_file(), "{} is not a file.", local.display());
+                    (
+                        username,
+                        data_recorder.as_ref(),
+                        DRSource::Local,
+                        Some(local.to_owned()),
+                    )
+                }
+                None => {
+                    let (username, host) = split_user_host(data_recorder.as_ref(), username);
+                    let sftp = Self::connect(host, username, identity_file.as_ref(), auth)?;
+                    (username, host, DRSource::Remote(sftp), None)
+                }
+            };
+        let identity_file = identity_file.as_ref();
+
+        let mut me = Self {
+            data_recorder: data_recorder.to_owned(),
+            username: username.to_owned(),
+            identity_file: identity_file.to_owned(),
+            auth,
+            filename,
+            file_tag: None,
+            source,
+            last_timestamp: Epoch::from_unix_seconds(0.0),
+            saturation: None,
+            pols: Vec::new(),
+            tunings: Tunings::default(),
+            center_freq: None,
+            stokes: false,
+            layout: None,
+            read_until: 0,
+            last_spectrum: None,
+            status: None,
+        };
+
+        me.find_latest_file()?;
+
+        Ok(me)
+    }
+
+    /// Opens an authenticated SFTP session to the data recorder.
+    fn connect(
+        data_recorder: &str,
+        username: &str,
+        identity_file: &Path,
+        auth: SshAuth,
+    ) -> Result<Sftp> {
         // Connect to the local SSH server
         let tcp = TcpStream::connect(format!("{}:22", data_recorder))
             .context("Error initializing TCP connection")?;
@@ -751,36 +1328,67 @@ impl DRLoader {
         sess.set_tcp_stream(tcp);
         sess.handshake().context("SSH Handshake error")?;
 
-        // Try to authenticate with the first identity in the agent.
-        sess.userauth_pubkey_file("mcsdr", None, identity_file.as_ref(), None)
-            .context("Error authenticating as mcsdr")?;
+        let key_auth = |sess: &Session| {
+            sess.userauth_pubkey_file(username, None, identity_file, None)
+                .with_context(|| {
+                    format!(
+                        "Error authenticating as {username} with {}",
+                        identity_file.display()
+                    )
+                })
+        };
+        match auth {
+            SshAuth::Agent => sess.userauth_agent(username).with_context(|| {
+                format!("Error authenticating as {username} with the SSH agent")
+            })?,
+            SshAuth::Key => key_auth(&sess)?,
+            SshAuth::Auto => {
+                if let Err(agent_err) = sess.userauth_agent(username) {
+                    log::debug!("SSH agent authentication failed: {agent_err}");
+                    key_auth(&sess).with_context(|| {
+                        format!("Neither the SSH agent ({agent_err}) nor the identity file could authenticate")
+                    })?;
+                }
+            }
+        }
         // Make sure we succeeded
         ensure!(
             sess.authenticated(),
             "SSH Session could not be authenticated"
         );
 
-        let mut me = Self {
-            data_recorder: data_recorder.to_owned(),
-            filename: None,
-            file_tag: None,
-            sftp: sess.sftp().context("Error initializing sftp server")?,
-            last_timestamp: Epoch::from_unix_seconds(0.0),
-            saturation: None,
-        };
-
-        me.find_latest_file()?;
+        sess.sftp().context("Error initializing sftp server")
+    }
 
-        Ok(me)
+    /// Rebuilds the SSH session after the connection to the data recorder drops.
+    fn reconnect(&mut self) -> Result<()> {
+        if let DRSource::Local = self.source {
+            return Ok(());
+        }
+        log::warn!("Reconnecting to {}", self.data_recorder);
+
+        self.source = DRSource::Remote(Self::connect(
+            &self.data_recorder,
+            &self.username,
+            &self.identity_file,
+            self.auth,
+        )?);
+        // the recorder may have restarted and begun a new file
+        self.find_latest_file()?;
+
+        log::info!("Reconnected to {}", self.data_recorder);
+        Ok(())
     }
 
     fn get_file<P: AsRef<Path>>(&mut self, pathname: P) -> Result<Option<PathBuf>, ssh2::Error> {
-        Ok(self
-            .sftp
+        let DRSource::Remote(sftp) = &self.source else {
+            return Ok(None);
+        };
+        Ok(sftp
             .readdir(pathname.as_ref())?
             .into_iter()
             .filter_map(|(path, stat)| if stat.is_dir() { Some(path) } else { None })
-            .map(|path| self.sftp.readdir(&path.join("DROS/Spec/")))
+            .map(|path| sftp.readdir(&path.join("DROS/Spec/")))
             .filter_map(Result::ok)
             .flatten()
             .filter(|(path, stat)| {
@@ -795,6 +1403,20 @@ impl DRLoader {
     }
 
     fn find_latest_file(&mut self) -> Result<()> {
+        if let DRSource::Local = self.source {
+            // a local file is followed on its own, there is nothing newer to find
+            if self.file_tag.is_none() {
+                self.file_tag = self
+                    .filename
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .and_then(|name| name.to_str().map(|x| x.to_owned()));
+                log::info!("Following {}", self.data_recorder);
+            }
+            return Ok(());
+        }
+
+        let previous = self.filename.clone();
         self.filename = 'file_block: {
             let paths_to_check = [
                 "/LWA_STORAGE/Internal/",
@@ -820,6 +1442,9 @@ impl DRLoader {
             }
             None
         };
+        if self.filename != previous {
+            self.reset_position();
+        }
 
         if let Some(path) = &self.filename {
             self.file_tag = path
@@ -838,35 +1463,94 @@ impl DRLoader {
         Ok(())
     }
 
+    /// Forgets where we were in the current file so the next read starts over.
+    fn reset_position(&mut self) {
+        self.layout = None;
+        self.read_until = 0;
+        self.last_spectrum = None;
+    }
+
+    /// Reads the newest complete spectrum in the file.
+    ///
+    /// The first read of a file finds where the spectra start, after that only
+    /// a spectrum appended since the previous read is transferred.
     fn get_latest_spectra(&mut self) -> Result<Option<DRSpectrum>> {
-        if let Some(filename) = &self.filename {
-            let file_handle = self
-                .sftp
-                .open(filename)
-                .with_context(|| format!("Error opening remote file: {}", filename.display()))?;
-            let mut reader = BufReader::new(file_handle);
-
-            let res = DRSpectrum::read_last_spectrum(&mut reader).map(Some);
-            if let Err(ref err) = res {
-                log::error!("Error reading specutrm file: {err}");
+        let Some(filename) = self.filename.clone() else {
+            return Ok(None);
+        };
+
+        match &self.source {
+            DRSource::Remote(sftp) => {
+                let size = sftp
+                    .stat(&filename)
+                    .with_context(|| {
+                        format!("Error reading size of remote file: {}", filename.display())
+                    })?
+                    .size
+                    .unwrap_or_default();
+                let file_handle = sftp.open(&filename).with_context(|| {
+                    format!("Error opening remote file: {}", filename.display())
+                })?;
+                self.read_newest(BufReader::new(file_handle), size, &filename)
+            }
+            DRSource::Local => {
+                let file_handle = File::open(&filename)
+                    .with_context(|| format!("Error opening {}", filename.display()))?;
+                let size = file_handle
+                    .metadata()
+                    .with_context(|| format!("Error reading size of {}", filename.display()))?
+                    .len();
+                self.read_newest(BufReader::new(file_handle), size, &filename)
             }
-            res
-        } else {
-            Ok(None)
         }
     }
 
-    pub fn get_stats(&self) -> Option<SaturationStats> {
-        self.saturation.clone()
+    /// Reads the newest complete spectrum from `reader`, a file of `size` bytes.
+    fn read_newest<R: Read + Seek>(
+        &mut self,
+        mut reader: BufReader<R>,
+        size: u64,
+        filename: &Path,
+    ) -> Result<Option<DRSpectrum>> {
+        if size < self.read_until {
+            log::info!("{} shrank, reading it from the start.", filename.display());
+            self.reset_position();
+        }
+
+        let layout = match self.layout {
+            Some(layout) => layout,
+            None => {
+                let layout = FileLayout::from_reader(&mut reader)?;
+                self.layout = Some(layout);
+                layout
+            }
+        };
+
+        let Some(offset) = layout.last_record(size) else {
+            return Ok(None);
+        };
+        if offset + layout.record_len <= self.read_until {
+            // nothing new has been written
+            return Ok(self.last_spectrum.clone());
+        }
+
+        reader.seek(SeekFrom::Start(offset))?;
+        match DRSpectrum::from_bytes(&mut reader) {
+            Ok(spectrum) => {
+                self.read_until = offset + layout.record_len;
+                self.last_spectrum = Some(spectrum.clone());
+                Ok(Some(spectrum))
+            }
+            Err(err) => {
+                log::error!("Error reading specutrm file: {err}");
+                Err(err)
+            }
+        }
     }
-}
 
-#[async_trait]
-impl SpectrumLoader for DRLoader {
-    /// Loads autospectrum data from the underlying source and sends
-    /// correlations (freq, val) pairs over the channel to the main process.
-    async fn get_data(&mut self) -> Option<AutoSpectra> {
-        let spectra = match self.get_latest_spectra() {
+    /// Reads the latest spectrum, retrying once if it is still being written.
+    fn read_spectra(&mut self) -> Result<Option<DRSpectrum>> {
+        match self.get_latest_spectra() {
             Ok(val) => Ok(val),
             Err(err) => match err.downcast::<std::io::Error>() {
                 Ok(error) if error.kind() == ErrorKind::UnexpectedEof => {
@@ -879,6 +1563,70 @@ impl SpectrumLoader for DRLoader {
                 Err(error) => Err(error),
             },
         }
+    }
+
+    pub fn get_stats(&self) -> Option<SaturationStats> {
+        self.saturation.clone()
+    }
+
+    /// Plots only the tuning centered closest to `freq` MHz.
+    pub fn with_center_freq(mut self, freq: Option<f64>) -> Self {
+        self.center_freq = freq;
+        self
+    }
+
+    /// Reports the connection status through `status`, starting as connected.
+    pub fn with_status(mut self, mut status: StatusReporter) -> Self {
+        status.set(BackendStatus::Connected);
+        self.status = Some(status);
+        self
+    }
+
+    fn report(&mut self, status: BackendStatus) {
+        if let Some(reporter) = self.status.as_mut() {
+            reporter.set(status);
+        }
+    }
+
+    pub fn cycle_tunings(&mut self) {
+        self.center_freq = None;
+        self.tunings = self.tunings.next();
+        log::info!("Plotting tunings: {:?}", self.tunings);
+    }
+
+    pub fn toggle_stokes(&mut self) {
+        self.stokes = !self.stokes;
+        log::info!(
+            "Plotting {} products.",
+            if self.stokes { "Stokes" } else { "recorded" }
+        );
+    }
+}
+
+#[async_trait]
+impl SpectrumLoader for DRLoader {
+    /// Loads autospectrum data from the underlying source and sends
+    /// correlations (freq, val) pairs over the channel to the main process.
+    async fn get_data(&mut self) -> Option<AutoSpectra> {
+        let spectra = match self.read_spectra() {
+            Err(err) if is_connection_error(&err) => {
+                log::warn!("Lost connection to {}: {err:#}", self.data_recorder);
+                self.report(BackendStatus::Reconnecting);
+                match self.reconnect() {
+                    // retry the read once now that we are connected again
+                    Ok(()) => {
+                        self.report(BackendStatus::Connected);
+                        self.read_spectra()
+                    }
+                    Err(err) => {
+                        log::error!("Unable to reconnect to {}: {err:#}", self.data_recorder);
+                        self.report(BackendStatus::Error);
+                        return None;
+                    }
+                }
+            }
+            res => res,
+        }
         .ok()
         .flatten()?;
 
@@ -886,22 +1634,23 @@ impl SpectrumLoader for DRLoader {
             log::info!("Timestamp unchanged, attempting to find new file.");
             // no new data has been written, close this file and look for a new one.
             self.find_latest_file().ok()?;
-            self.get_latest_spectra()
-                .ok()
-                .flatten()
-                .map(|spec| spec.into_autospectra())
+            self.get_latest_spectra().ok().flatten().map(|spec| {
+                pick_tuning(&mut self.tunings, self.center_freq, &spec.header);
+                spec.prepare(&self.pols, self.tunings, &mut self.stokes)
+            })
         } else {
             self.last_timestamp = spectra.header.timestamp;
 
             self.saturation.replace(spectra.header.calc_saturation());
 
-            Some(spectra.into_autospectra())
+            pick_tuning(&mut self.tunings, self.center_freq, &spectra.header);
+            Some(spectra.prepare(&self.pols, self.tunings, &mut self.stokes))
         }
     }
 
-    /// Filters the antennas to be plotted based on their string names.
-    fn filter_antenna(&mut self, _antenna_number: &[String]) -> Result<()> {
-        // not sure if we can even do anything with this
+    /// Filters the polarizations to be plotted based on their string names.
+    fn filter_antenna(&mut self, antenna_number: &[String]) -> Result<()> {
+        self.pols = antenna_number.to_vec();
         Ok(())
     }
 }
@@ -939,7 +1688,7 @@ mod test {
                 00,
                 25,
                 23,
-                312430336,
+                312430204,
                 hifitime::TimeScale::UTC,
             ),
             time_offset: 0,
@@ -1030,4 +1779,283 @@ mod test {
 
         assert_eq!(expected_spectra, spectrum)
     }
+
+    #[test]
+    fn tuning_words() {
+        // the tunings of the test data
+        for freq in [51999999.984167516, 69999999.98044223] {
+            let word = DRHeader::calc_tuning(freq).expect("Frequency out of range");
+            assert_eq!(freq, DRHeader::calc_freq(word));
+        }
+        // a word is ~0.05 Hz wide
+        let word = DRHeader::calc_tuning(52e6).unwrap();
+        assert!((DRHeader::calc_freq(word) - 52e6).abs() < 0.05);
+
+        assert_eq!(Some(0), DRHeader::calc_tuning(0.0));
+        assert_eq!(None, DRHeader::calc_tuning(-1e6));
+        assert_eq!(None, DRHeader::calc_tuning(DRHeader::CLOCK_SPEED));
+    }
+
+    #[test]
+    fn nearest_tuning() {
+        let data_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("two_spectra");
+        let mut file_handle = BufReader::new(fs::File::open(&data_file).unwrap());
+        let spectrum = DRSpectrum::from_bytes(&mut file_handle).expect("Unable to read test data");
+
+        // tunings at 52 and 70 MHz
+        let header = &spectrum.header;
+        assert_eq!(Tunings::Tuning1, header.nearest_tuning(40e6));
+        assert_eq!(Tunings::Tuning1, header.nearest_tuning(60e6));
+        assert_eq!(Tunings::Tuning2, header.nearest_tuning(62e6));
+        assert_eq!(Tunings::Tuning2, header.nearest_tuning(90e6));
+
+        let spec = spectrum.into_autospectra(&[], Tunings::Tuning2);
+        let [(number, center)] = spec.tunings[..] else {
+            panic!("Expected one tuning, got {:?}", spec.tunings);
+        };
+        assert_eq!(2, number);
+        assert!((center - 70.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn file_layout() {
+        let data_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("two_spectra");
+        let mut file_handle = BufReader::new(
+            fs::OpenOptions::new()
+                .read(true)
+                .open(&data_file)
+                .unwrap_or_else(|_| panic!("Unable to open {}", data_file.display())),
+        );
+
+        let _ = DRSpectrum::from_bytes(&mut file_handle).expect("unable to read test data.");
+        let expected_spectra =
+            DRSpectrum::from_bytes(&mut file_handle).expect("unable to read test data.");
+        let size = file_handle
+            .stream_position()
+            .expect("unable to read file position.");
+
+        file_handle.rewind().expect("unable to rewind test file.");
+        let layout = FileLayout::from_reader(&mut file_handle).expect("Unable to read layout.");
+        assert_eq!(
+            FileLayout {
+                start: 0,
+                record_len: 32844
+            },
+            layout
+        );
+
+        // a partially written spectrum is skipped
+        assert_eq!(Some(0), layout.last_record(size - 1));
+        assert_eq!(None, layout.last_record(100));
+
+        let offset = layout
+            .last_record(size)
+            .expect("No complete spectrum found.");
+        file_handle
+            .seek(SeekFrom::Start(offset))
+            .expect("unable to seek test file.");
+        let spectrum = DRSpectrum::from_bytes(&mut file_handle).expect("unable to read test data.");
+
+        assert_eq!(expected_spectra, spectrum)
+    }
+
+    #[test]
+    fn stokes_from_linear() {
+        let data_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("two_spectra");
+        let mut file_handle = BufReader::new(
+            fs::OpenOptions::new()
+                .read(true)
+                .open(&data_file)
+                .unwrap_or_else(|_| panic!("Unable to open {}", data_file.display())),
+        );
+
+        let spectrum = DRSpectrum::from_bytes(&mut file_handle).expect("Unable to read test data");
+        let stokes = spectrum
+            .to_stokes()
+            .expect("Test data should be LinearFull");
+
+        assert_eq!(PolarizationType::StokesFull, stokes.header.stokes_format);
+        assert_eq!(spectrum.data.shape(), stokes.data.shape());
+
+        let linear = spectrum.data.slice(ndarray::s![0, 100, ..]);
+        let expected = [
+            linear[0] + linear[3],
+            linear[0] - linear[3],
+            2.0 * linear[1],
+            -2.0 * linear[2],
+        ];
+        for (expected, val) in expected
+            .iter()
+            .zip(stokes.data.slice(ndarray::s![0, 100, ..]))
+        {
+            assert!((expected - val).abs() < 1e-9);
+        }
+
+        // only the linear products can be converted
+        assert!(stokes.to_stokes().is_none());
+    }
+
+    #[test]
+    fn zero_fill_flagged() {
+        let data_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("two_spectra");
+        let mut bytes = fs::read(&data_file)
+            .unwrap_or_else(|_| panic!("Unable to read {}", data_file.display()));
+
+        // the fills follow the sync word, time tag, time offset, decimation and tunings
+        let fills_start = 4 + 8 + 2 + 2 + 2 * 4;
+        bytes[fills_start..fills_start + 4].copy_from_slice(&0_u32.to_le_bytes());
+
+        let spectrum =
+            DRSpectrum::from_bytes(&mut bytes.as_slice()).expect("Unable to read test data");
+        assert_eq!(0, spectrum.header.fills[0]);
+
+        // X on the first tuning feeds XX and both halves of XY, YY is still good
+        let first = spectrum.data.index_axis(Axis(0), 0);
+        for pol in 0..3 {
+            assert!(first.index_axis(Axis(1), pol).iter().all(|x| x.is_nan()));
+        }
+        assert!(first.index_axis(Axis(1), 3).iter().all(|x| x.is_finite()));
+        assert!(spectrum
+            .data
+            .index_axis(Axis(0), 1)
+            .iter()
+            .all(|x| x.is_finite()));
+
+        // the flagged channels don't reach the axis limits
+        let spec = spectrum.into_autospectra(&[], Tunings::Both);
+        assert!(spec.ymin(spec.plot_log).is_finite());
+        assert!(spec.ymax(spec.plot_log).is_finite());
+    }
+
+    #[test]
+    fn timetag_round_trip() {
+        // the time tag from the test data and a spread of others, some
+        // landing between nanoseconds
+        let time_tags = [
+            339_043_920_969_236_320_u64,
+            0,
+            1,
+            195_999_999,
+            196_000_000,
+            338_860_479_000_000_001,
+            338_860_479_195_999_999,
+            u32::MAX as u64 * 196,
+        ];
+
+        for time_tag in time_tags {
+            for offset in [0_u16, 1, 6_000] {
+                let time_tag = time_tag + offset as u64;
+                let header = DRHeader {
+                    timestamp: DRHeader::calc_epoch(time_tag, offset),
+                    time_offset: offset,
+                    decimation_factor: 10,
+                    frequencies: [51999999.984167516, 69999999.98044223],
+                    fills: [768_u32; 4],
+                    errors: [0_u8; 4],
+                    beam: 1,
+                    stokes_format: PolarizationType::LinearFull,
+                    specrometer_version: 2,
+                    flags: 0,
+                    n_freqs: 1024,
+                    n_ints: 768,
+                    saturation_count: [0; 4],
+                };
+                assert_eq!(time_tag, header.calc_timetag(), "offset {offset}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn follow_local() {
+        let data_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("two_spectra");
+        let data = fs::read(&data_file).expect("unable to read test data.");
+        // both spectra are the same size
+        let (first, second) = data.split_at(data.len() / 2);
+
+        let path = std::env::temp_dir().join(format!("spectrum-tui-follow-{}", std::process::id()));
+        fs::write(&path, first).unwrap();
+
+        let mut loader = DRLoader::new(
+            format!("file:{}", path.display()),
+            "unused",
+            "mcsdr",
+            SshAuth::Auto,
+        )
+        .unwrap();
+        let first = loader
+            .get_data()
+            .await
+            .expect("unable to read first spectrum.");
+
+        // the recorder writes another spectrum
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(second)
+            .unwrap();
+        let second = loader
+            .get_data()
+            .await
+            .expect("unable to read second spectrum.");
+
+        assert!(second.timestamp > first.timestamp);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn user_host() {
+        assert_eq!(("mcsdr", "dr1"), split_user_host("dr1", "mcsdr"));
+        assert_eq!(("tester", "dr1"), split_user_host("tester@dr1", "mcsdr"));
+        assert_eq!(("mcsdr", "dr1"), split_user_host("@dr1", "mcsdr"));
+    }
+
+    #[test]
+    fn clock_conversions() {
+        let epoch = Epoch::from_gregorian_utc(2024, 10, 25, 0, 25, 23, 0);
+        let ticks = DRHeader::epoch_to_clock(epoch);
+        assert_eq!(0, ticks % 196_000_000);
+        assert_eq!(epoch, DRHeader::clock_to_epoch(ticks));
+
+        // one tick is about 5.1 ns
+        let later = DRHeader::clock_to_epoch(ticks + 1);
+        assert_eq!(5, (later - epoch).total_nanoseconds());
+    }
+
+    #[test]
+    fn saturation_log_appends() {
+        let stats = SaturationStats {
+            tuning1: vec![Stats::new(0.25)],
+            tuning2: vec![Stats::new(0.5)],
+            pols: vec!["XX".into()],
+        };
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 10, 25);
+
+        let path =
+            std::env::temp_dir().join(format!("spectrum-tui-sat-{}.csv", std::process::id()));
+        let mut sat_log = SaturationLog::create(path.clone()).unwrap();
+        sat_log.append(&stats, epoch).unwrap();
+        sat_log.append(&stats, epoch).unwrap();
+
+        // every poll is on disk before the log is closed
+        let written = fs::read_to_string(&path).unwrap();
+        let lines = written.lines().collect::<Vec<_>>();
+        assert_eq!(5, lines.len());
+        assert_eq!("epoch,pol,tuning,saturation", lines[0]);
+        assert!(lines[1].ends_with(",XX,1,0.25"));
+        assert!(lines[2].ends_with(",XX,2,0.5"));
+
+        drop(sat_log);
+        fs::remove_file(path).unwrap();
+    }
 }
@@ -1,18 +1,230 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use async_trait::async_trait;
 use etcd_client::{Client, WatchOptions};
+use flate2::read::GzDecoder;
 use futures::StreamExt;
+use hifitime::Epoch;
 use itertools::Itertools;
-use log::info;
-use ndarray::{concatenate, Array, Axis, Ix2};
-use ndarray_npy::read_npy;
+use log::{info, warn};
+use ndarray::{concatenate, s, Array, ArrayD, Axis, Ix1, Ix2};
+use ndarray_npy::{read_npy, ReadNpyExt};
 use serde_json::{json, Value};
-use std::{collections::HashSet, path::PathBuf, time::SystemTime};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::Read,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
 
-use crate::loader::{AutoSpectra, SpectrumLoader};
+use crate::loader::{AutoSpectra, BackendStatus, SpectrumLoader, StatusReporter, XUnit};
 
 const ETCD_RESP_KEY: &str = "/resp/snap/";
 const ETCD_CMD_ROOT: &str = "/cmd/snap/";
+/// Key holding the correlator configuration
+const CONFIG_KEY: &str = "/cfg/system";
+
+/// How long to wait for the correlator to respond to a spectrum request
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Number of times to try reading the configuration, and failed reconnections
+/// before the source is reported as down. Reconnecting is still retried.
+const RECONNECT_ATTEMPTS: u32 = 5;
+/// How long to wait for the etcd server to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest wait between attempts to reconnect
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Highest frequency (MHz) of the band the spectra cover
+const BAND_TOP_MHZ: f64 = 98.3;
+
+/// Leading bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Checks the extension and leading bytes of a file for gzip compression.
+fn is_gzipped(path: &Path) -> bool {
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        return true;
+    }
+
+    let mut magic = [0_u8; 2];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| magic == GZIP_MAGIC)
+}
+
+/// Decompresses a gzipped npy file in memory and reads the array.
+fn read_gzipped_npy(path: &Path) -> Result<ArrayD<f64>> {
+    let file = File::open(path).with_context(|| format!("Unable to open {}", path.display()))?;
+
+    let mut buffer = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut buffer)
+        .with_context(|| format!("Unable to decompress {}", path.display()))?;
+
+    ArrayD::<f64>::read_npy(buffer.as_slice())
+        .with_context(|| format!("Unable to read npy data from {}", path.display()))
+}
+
+/// Reads a file of one spectrum per row, a 1-D file holds a single spectrum.
+fn read_spectra(path: &Path) -> Result<Array<f64, Ix2>> {
+    let data = if is_gzipped(path) {
+        read_gzipped_npy(path)?
+    } else {
+        read_npy(path).with_context(|| format!("Unable to read {}", path.display()))?
+    };
+
+    match data.ndim() {
+        1 => Ok(data.into_dimensionality::<Ix1>()?.insert_axis(Axis(0))),
+        2 => Ok(data.into_dimensionality::<Ix2>()?),
+        ndim => bail!(
+            "{} holds a {ndim}-D array, expected one spectrum per row.",
+            path.display()
+        ),
+    }
+}
+
+/// Flattens a response holding one list of values per input.
+///
+/// Values that are not numbers, e.g. `null` while the FPGAs are reprogrammed,
+/// become NaN so a bad sample is skipped instead of dropping the whole response.
+fn parse_response(response: &Value) -> Result<Vec<f64>> {
+    let vals = response
+        .as_array()
+        .context("The response is not a list of spectra.")?
+        .iter()
+        .map(|spec| spec.as_array().context("A spectrum is not a list."))
+        .flatten_ok()
+        .map_ok(|val| val.as_f64().unwrap_or(f64::NAN))
+        .collect::<Result<Vec<_>>>()?;
+
+    let n_invalid = vals.iter().filter(|val| val.is_nan()).count();
+    if n_invalid > 0 {
+        warn!("{n_invalid} spectrum values were not numbers, treating them as NaN.");
+    }
+    Ok(vals)
+}
+
+/// Builds the x-axis values for a spectrum with `nfreqs` channels.
+fn x_axis(unit: XUnit, nfreqs: usize) -> Array<f64, Ix1> {
+    match unit {
+        XUnit::Mhz => Array::linspace(0.0, BAND_TOP_MHZ, nfreqs),
+        XUnit::Channel => Array::range(0.0, nfreqs as f64, 1.0),
+    }
+}
+
+/// Checks that a frequency range (MHz) overlaps the band, warning if it has
+/// to be clamped to it.
+fn check_freq_range((min, max): (f64, f64)) -> Result<()> {
+    let (low, high) = (min.min(max), min.max(max));
+    ensure!(
+        high >= 0.0 && low <= BAND_TOP_MHZ,
+        "The frequency range {low}-{high} MHz is outside the band (0-{BAND_TOP_MHZ} MHz)."
+    );
+    if low < 0.0 || high > BAND_TOP_MHZ {
+        warn!("Clamping the frequency range {low}-{high} MHz to the band (0-{BAND_TOP_MHZ} MHz).");
+    }
+    Ok(())
+}
+
+/// Channels of a spectrum with `nfreqs` channels whose frequency lies within
+/// `range` (MHz), the range is clamped to the available band.
+fn channel_window(nfreqs: usize, range: Option<(f64, f64)>) -> Range<usize> {
+    let Some((min, max)) = range.filter(|_| nfreqs > 0) else {
+        return 0..nfreqs;
+    };
+    let freqs = x_axis(XUnit::Mhz, nfreqs);
+    let (band_min, band_max) = (freqs[0], freqs[nfreqs - 1]);
+    let low = min.min(max).clamp(band_min, band_max);
+    let high = min.max(max).clamp(band_min, band_max);
+
+    let start = freqs.iter().position(|freq| *freq >= low).unwrap_or(0);
+    let stop = freqs
+        .iter()
+        .rposition(|freq| *freq <= high)
+        .map_or(nfreqs, |index| index + 1);
+
+    // keep at least one channel if the range falls between two of them
+    start..stop.max(start + 1)
+}
+
+/// Keeps only the channels within `range` (MHz) of the x-axis and data.
+fn select_channels(
+    xs: Array<f64, Ix1>,
+    data: Array<f64, Ix2>,
+    range: Option<(f64, f64)>,
+) -> (Array<f64, Ix1>, Array<f64, Ix2>) {
+    let window = channel_window(xs.len(), range);
+    (
+        xs.slice_move(s![window.clone()]),
+        data.slice_move(s![.., window]),
+    )
+}
+
+/// Layout of the autospectra a snap returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SpectraShape {
+    /// Number of signal blocks on a snap
+    pub(crate) n_signal_blocks: usize,
+    /// Number of FPGA inputs whose spectra are returned by one request
+    pub(crate) inputs_per_block: usize,
+    /// Number of channels in each spectrum
+    pub(crate) n_channels: usize,
+}
+impl Default for SpectraShape {
+    fn default() -> Self {
+        Self {
+            n_signal_blocks: 4,
+            inputs_per_block: 16,
+            n_channels: 4096,
+        }
+    }
+}
+impl SpectraShape {
+    pub fn new(n_signal_blocks: usize, inputs_per_block: usize, n_channels: usize) -> Result<Self> {
+        ensure!(
+            n_signal_blocks > 0 && inputs_per_block > 0 && n_channels > 0,
+            "Spectra shape must be non-zero, got {n_signal_blocks} blocks of \
+            {inputs_per_block} inputs with {n_channels} channels."
+        );
+        Ok(Self {
+            n_signal_blocks,
+            inputs_per_block,
+            n_channels,
+        })
+    }
+
+    /// Reads the shape from the `fengines` section of the system configuration.
+    ///
+    /// Each of `n_signal_blocks`, `inputs_per_block` and `nchans` is optional
+    /// and falls back to the current firmware's layout.
+    fn from_config(config: &Value) -> Result<Self> {
+        let default = Self::default();
+        let field = |name: &str, default: usize| -> Result<usize> {
+            match config
+                .get("fengines")
+                .and_then(|fengines| fengines.get(name))
+            {
+                None => Ok(default),
+                Some(val) => val
+                    .as_u64()
+                    .and_then(|val| usize::try_from(val).ok())
+                    .with_context(|| format!("fengines.{name} is not a count: {val}")),
+            }
+        };
+
+        Self::new(
+            field("n_signal_blocks", default.n_signal_blocks)?,
+            field("inputs_per_block", default.inputs_per_block)?,
+            field("nchans", default.n_channels)?,
+        )
+    }
+
+    /// Number of FPGA inputs on a snap.
+    fn n_inputs(&self) -> usize {
+        self.n_signal_blocks * self.inputs_per_block
+    }
+}
 
 #[derive(Debug, Clone)]
 struct AntInfo {
@@ -21,6 +233,185 @@ struct AntInfo {
     pola_fpga_num: i64,
     polb_fpga_num: i64,
 }
+impl AntInfo {
+    /// Reads the antenna table from the system configuration.
+    ///
+    /// `lwacfg` is either keyed by field then antenna (it has a
+    /// `snap2_location` key) or by antenna then field.
+    /// Fields missing for an antenna get placeholder values.
+    fn from_config(config: &Value) -> Result<Vec<Self>> {
+        let dict = config
+            .get("lwacfg")
+            .context("No lwacfg entry in the configuration.")?
+            .as_object()
+            .context("lwacfg is not an object.")?;
+
+        match dict.contains_key("snap2_location") {
+            true => {
+                let columns = dict
+                    .iter()
+                    .map(|(field, column)| {
+                        column
+                            .as_object()
+                            .map(|column| (field.as_str(), column))
+                            .with_context(|| format!("lwacfg.{field} is not an object."))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let ants = columns
+                    .iter()
+                    .flat_map(|(_, column)| column.keys())
+                    .collect::<HashSet<_>>();
+
+                Ok(ants
+                    .into_iter()
+                    .map(|ant| {
+                        let field = |name: &str| {
+                            columns
+                                .iter()
+                                .find(|(field, _)| *field == name)
+                                .and_then(|(_, column)| column.get(ant))
+                        };
+                        Self::from_fields(field)
+                    })
+                    .collect())
+            }
+            false => dict
+                .iter()
+                .map(|(ant, ant_dict)| {
+                    let ant_dict = ant_dict
+                        .as_object()
+                        .with_context(|| format!("lwacfg.{ant} is not an object."))?;
+                    Ok(Self::from_fields(|name| ant_dict.get(name)))
+                })
+                .collect(),
+        }
+    }
+
+    /// Builds the info from a lookup of each field by name.
+    fn from_fields<'a, F: Fn(&str) -> Option<&'a Value>>(field: F) -> Self {
+        let number = |name: &str| field(name).and_then(|val| val.as_i64()).unwrap_or(-1);
+
+        Self {
+            antname: field("antname")
+                .and_then(|name| name.as_str())
+                .unwrap_or("null")
+                .to_owned(),
+            snap2_location: number("snap2_location"),
+            pola_fpga_num: number("pola_fpga_num"),
+            polb_fpga_num: number("polb_fpga_num"),
+        }
+    }
+}
+
+/// Splits a name like LWA-010 into its prefix and trailing number.
+fn split_number(name: &str) -> Option<(String, u64)> {
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let number = name[prefix.len()..].parse().ok()?;
+    Some((prefix.to_lowercase(), number))
+}
+
+/// One requested antenna, matched case insensitively and ignoring zero padding.
+#[derive(Debug, Clone, PartialEq)]
+enum AntennaPattern {
+    /// A name without a trailing number, lowercase
+    Name(String),
+    /// A name like LWA-010, split into its prefix and number
+    Numbered(String, u64),
+    /// An inclusive range like LWA-1..LWA-10
+    Range(String, u64, u64),
+}
+impl AntennaPattern {
+    fn parse(item: &str) -> Result<Self> {
+        let Some((start, end)) = item.split_once("..") else {
+            return Ok(split_number(item).map_or_else(
+                || Self::Name(item.to_lowercase()),
+                |(prefix, number)| Self::Numbered(prefix, number),
+            ));
+        };
+        let ((prefix, first), (end_prefix, last)) = split_number(start.trim())
+            .zip(split_number(end.trim()))
+            .with_context(|| {
+                format!("Unable to parse the antenna range {item}, expected e.g. LWA-1..LWA-10")
+            })?;
+        ensure!(
+            prefix == end_prefix,
+            "The ends of the antenna range {item} have different prefixes."
+        );
+        Ok(Self::Range(prefix, first.min(last), first.max(last)))
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Name(pattern) => name.to_lowercase() == *pattern,
+            Self::Numbered(prefix, number) => {
+                split_number(name).is_some_and(|(name_prefix, name_number)| {
+                    name_prefix == *prefix && name_number == *number
+                })
+            }
+            Self::Range(prefix, first, last) => {
+                split_number(name).is_some_and(|(name_prefix, number)| {
+                    name_prefix == *prefix && (*first..=*last).contains(&number)
+                })
+            }
+        }
+    }
+}
+
+/// The non-empty items of comma separated antenna requests.
+fn request_items<'a>(
+    requested: impl IntoIterator<Item = &'a str>,
+) -> impl Iterator<Item = &'a str> {
+    requested
+        .into_iter()
+        .flat_map(|req| req.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Checks that every item of a request, as accepted by [expand_antennas],
+/// matches at least one of the `known` antenna names.
+pub(crate) fn matches_known_antennas(request: &str, known: &[String]) -> bool {
+    let mut items = request_items([request]).peekable();
+    items.peek().is_some()
+        && items.all(|item| {
+            AntennaPattern::parse(item)
+                .is_ok_and(|pattern| known.iter().any(|name| pattern.matches(name)))
+        })
+}
+
+/// Expands the requested antennas into their configuration entries.
+///
+/// Each request is a comma separated list of names or inclusive ranges like
+/// LWA-1..LWA-10, matched case insensitively and ignoring zero padding.
+/// Names not in the configuration are skipped with a warning.
+fn expand_antennas(requested: &[String], known: &[AntInfo]) -> Vec<AntInfo> {
+    let mut selected: Vec<AntInfo> = Vec::new();
+    for item in request_items(requested.iter().map(String::as_str)) {
+        let pattern = match AntennaPattern::parse(item) {
+            Ok(pattern) => pattern,
+            Err(err) => {
+                warn!("{err}");
+                continue;
+            }
+        };
+        let matches = known
+            .iter()
+            .filter(|info| pattern.matches(&info.antname))
+            .collect::<Vec<_>>();
+
+        if matches.is_empty() {
+            warn!("No antenna matching {item} in the configuration.");
+        }
+        for info in matches {
+            if !selected.iter().any(|sel| sel.antname == info.antname) {
+                selected.push(info.clone());
+            }
+        }
+    }
+    selected
+}
+
 impl core::cmp::PartialEq for AntInfo {
     fn eq(&self, other: &Self) -> bool {
         self.snap2_location == other.snap2_location
@@ -39,147 +430,304 @@ impl core::cmp::Ord for AntInfo {
 }
 
 pub(crate) struct DiskLoader {
-    n_spectra: usize,
+    /// Number of antennas to load, `None` loads every valid spectrum
+    n_spectra: Option<usize>,
     file: PathBuf,
+    x_unit: XUnit,
+    /// Frequencies (MHz) of the channels to keep, all channels if `None`
+    freq_range: Option<(f64, f64)>,
+    /// Modification time of the file when it was last read
+    loaded: Option<SystemTime>,
 }
 impl DiskLoader {
-    pub fn new(file: PathBuf) -> Self {
-        Self { n_spectra: 0, file }
+    pub fn new(file: PathBuf, x_unit: XUnit) -> Self {
+        Self {
+            n_spectra: None,
+            file,
+            x_unit,
+            freq_range: None,
+            loaded: None,
+        }
+    }
+
+    /// Only load the channels between the given frequencies in MHz.
+    ///
+    /// Fails if the range does not overlap the band.
+    pub fn with_freq_range(mut self, freq_range: Option<(f64, f64)>) -> Result<Self> {
+        freq_range.map(check_freq_range).transpose()?;
+        self.freq_range = freq_range;
+        Ok(self)
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.file)
+            .and_then(|meta| meta.modified())
+            .ok()
+    }
+
+    /// Checks if the file has been rewritten since it was last read.
+    pub fn has_changed(&self) -> bool {
+        self.modified()
+            .is_some_and(|modified| Some(modified) != self.loaded)
     }
 }
 #[async_trait]
 impl SpectrumLoader for DiskLoader {
     async fn get_data(&mut self) -> Option<AutoSpectra> {
-        let data: Array<f64, Ix2> = read_npy(&self.file).expect("unabe to read.");
+        // a file caught mid-write is read again once the write finishes and
+        // changes the modification time, a bad file is only reported once
+        self.loaded = self.modified();
+        let data = match read_spectra(&self.file) {
+            Ok(data) => data,
+            Err(err) => {
+                log::error!("{err:#}");
+                return None;
+            }
+        };
+
         let nfreqs = data.shape()[1];
 
-        let mut data_out = Array::<f64, Ix2>::zeros((2 * self.n_spectra, nfreqs));
-        for (mut inner_data_out, good_inner) in data_out.outer_iter_mut().zip(
-            data.outer_iter()
-                .filter(|inner| !inner.iter().all(|y| y.is_nan() || y <= &0.0))
-                .take(2 * self.n_spectra),
-        ) {
-            inner_data_out.assign(&good_inner);
+        let n_rows = match self.n_spectra {
+            Some(n_spectra) => 2 * n_spectra,
+            None => data.shape()[0],
+        };
+
+        // every row keeps its place so the names match the inputs, rows that
+        // are all zero or NaN (or missing) are left as NaN and shown as no data
+        let mut data_out = Array::<f64, Ix2>::from_elem((n_rows, nfreqs), f64::NAN);
+        for (mut inner_data_out, inner) in data_out.outer_iter_mut().zip(data.outer_iter()) {
+            if !inner.iter().all(|y| y.is_nan() || y <= &0.0) {
+                inner_data_out.assign(&inner);
+            }
         }
 
-        let xs = Array::linspace(0.0, 98.3, nfreqs);
+        let (xs, data_out) =
+            select_channels(x_axis(self.x_unit, nfreqs), data_out, self.freq_range);
 
-        let ant_names = (0..(2 * self.n_spectra))
+        let ant_names = (0..n_rows)
             .map(|x| match x % 2 == 0 {
                 true => (x / 2).to_string() + "A",
                 false => (x / 2).to_string() + "B",
             })
             .collect::<Vec<_>>();
 
-        Some(AutoSpectra::new(ant_names, xs, data_out, true))
+        let mut spec = AutoSpectra::new(ant_names, xs, data_out, true);
+        spec.x_unit = self.x_unit;
+        Some(spec)
     }
 
     fn filter_antenna(&mut self, antenna_number: &[String]) -> Result<()> {
-        self.n_spectra = antenna_number.len();
+        self.n_spectra = Some(antenna_number.len());
 
         Ok(())
     }
 }
 
 pub(crate) struct EtcdLoader {
+    /// Addresses of the etcd servers, kept to reconnect
+    endpoints: Vec<String>,
     /// etcd3 client to communicate with correlator
     client: Client,
     /// Antenna configuration matrix
     ant_info: Vec<AntInfo>,
+    /// Layout of the spectra returned by each snap
+    shape: SpectraShape,
     /// Antenna Filter to apply on FGPA call
     /// Filter consists of [Antenna Number, FPGA number, polA index, polB index]
     filter: Option<Vec<AntInfo>>,
+    /// Units of the x-axis
+    x_unit: XUnit,
+    /// Frequencies (MHz) of the channels to keep, all channels if `None`
+    freq_range: Option<(f64, f64)>,
+    /// Reports the health of the connection to the interface
+    status: Option<StatusReporter>,
+    /// Failed attempts to reconnect since the connection was lost
+    failed_reconnects: u32,
+    /// When to try reconnecting next, `None` while connected
+    retry_at: Option<Instant>,
 }
 impl EtcdLoader {
-    pub async fn new<T: AsRef<str>>(address: T) -> Result<Self> {
-        let mut client = Client::connect(&[address.as_ref()], None)
-            .await
-            .context("Error connecting to etcd server.")?;
-
-        let config = client.get("/cfg/system", None).await?;
-        let full_json = serde_json::from_str::<Value>(config.kvs()[0].value_str()?)
-            .context("Error generating JSON from etcd respose.")?;
+    /// Connects to the etcd cluster, any of the `endpoints` may answer.
+    pub async fn new<T: AsRef<str>>(endpoints: &[T], x_unit: XUnit) -> Result<Self> {
+        let endpoints = endpoints
+            .iter()
+            .map(|endpoint| endpoint.as_ref().to_owned())
+            .collect::<Vec<_>>();
+        let mut client = Self::connect(&endpoints).await?;
 
-        let dict = full_json.get("lwacfg").unwrap().as_object().unwrap();
+        let full_json = Self::fetch_config(&mut client).await?;
 
-        let ant_info = match dict.keys().find(|x| x.eq(&"snap2_location")) {
-            Some(_) => {
-                let ants = dict
-                    .values()
-                    .flat_map(|val| val.as_object().unwrap().keys())
-                    .collect::<HashSet<_>>();
-                let mut all_series = vec![];
-                for ant in ants.iter() {
-                    all_series.push(AntInfo {
-                        antname: dict
-                            .get("antname")
-                            .and_then(|name| {
-                                name.as_object()
-                                    .and_then(|next| next.get(*ant).and_then(|val| val.as_str()))
-                            })
-                            .unwrap_or("null")
-                            .to_owned(),
-                        snap2_location: dict
-                            .get("snap2_location")
-                            .and_then(|name| {
-                                name.as_object()
-                                    .and_then(|next| next.get(*ant).and_then(|val| val.as_i64()))
-                            })
-                            .unwrap_or(-1),
-                        pola_fpga_num: dict
-                            .get("pola_fpga_num")
-                            .and_then(|name| {
-                                name.as_object()
-                                    .and_then(|next| next.get(*ant).and_then(|val| val.as_i64()))
-                            })
-                            .unwrap_or(-1),
-                        polb_fpga_num: dict
-                            .get("polb_fpga_num")
-                            .and_then(|name| {
-                                name.as_object()
-                                    .and_then(|next| next.get(*ant).and_then(|val| val.as_i64()))
-                            })
-                            .unwrap_or(-1),
-                    });
-                }
-                all_series
-            }
-            None => {
-                let mut all_series = vec![];
-
-                for ant_dict in dict.values() {
-                    all_series.push(AntInfo {
-                        antname: ant_dict
-                            .get("antname")
-                            .and_then(|name| name.as_str())
-                            .unwrap_or("null")
-                            .to_owned(),
-                        snap2_location: ant_dict
-                            .get("snap2_location")
-                            .and_then(|snap| snap.as_i64())
-                            .unwrap_or(-1),
-                        pola_fpga_num: ant_dict
-                            .get("pola_fpga_num")
-                            .and_then(|fpga| fpga.as_i64())
-                            .unwrap_or(-1),
-                        polb_fpga_num: ant_dict
-                            .get("polb_fpga_num")
-                            .and_then(|fpga| fpga.as_i64())
-                            .unwrap_or(-1),
-                    });
-                }
-                all_series
-            }
-        };
+        let ant_info = AntInfo::from_config(&full_json)
+            .with_context(|| format!("Unable to parse {CONFIG_KEY}."))?;
+        let shape = SpectraShape::from_config(&full_json)
+            .with_context(|| format!("Unable to parse {CONFIG_KEY}."))?;
         info!("Configuration loaded.");
 
         Ok(Self {
+            endpoints,
             client,
             ant_info,
+            shape,
             filter: None,
+            x_unit,
+            freq_range: None,
+            status: None,
+            failed_reconnects: 0,
+            retry_at: None,
+        })
+    }
+
+    /// Reports the connection status through `status`, starting as connected.
+    pub fn with_status(mut self, mut status: StatusReporter) -> Self {
+        status.set(BackendStatus::Connected);
+        self.status = Some(status);
+        self
+    }
+
+    fn report(&mut self, status: BackendStatus) {
+        if let Some(reporter) = self.status.as_mut() {
+            reporter.set(status);
+        }
+    }
+
+    /// Only load the channels between the given frequencies in MHz.
+    ///
+    /// Fails if the range does not overlap the band.
+    pub fn with_freq_range(mut self, freq_range: Option<(f64, f64)>) -> Result<Self> {
+        freq_range.map(check_freq_range).transpose()?;
+        self.freq_range = freq_range;
+        Ok(self)
+    }
+
+    /// Uses this layout for the spectra instead of the one in the configuration.
+    pub fn with_spectra_shape(mut self, shape: Option<SpectraShape>) -> Self {
+        if let Some(shape) = shape {
+            info!(
+                "Expecting {} signal blocks of {} inputs with {} channels.",
+                shape.n_signal_blocks, shape.inputs_per_block, shape.n_channels
+            );
+            self.shape = shape;
+        }
+        self
+    }
+
+    /// Reads and parses the system configuration.
+    async fn read_config(client: &mut Client) -> Result<Value> {
+        let response = tokio::time::timeout(RESPONSE_TIMEOUT, client.get(CONFIG_KEY, None))
+            .await
+            .context("Timed out requesting the configuration.")?
+            .context("Error requesting the configuration.")?;
+        let keyval = response
+            .kvs()
+            .first()
+            .ok_or_else(|| anyhow!("{CONFIG_KEY} is empty."))?;
+
+        serde_json::from_str::<Value>(
+            keyval
+                .value_str()
+                .context("Configuration is not valid UTF-8.")?,
+        )
+        .context("Error generating JSON from etcd response.")
+    }
+
+    /// Reads the system configuration, retrying with an exponential backoff
+    /// while the key is missing or unreadable (e.g. the correlator is restarting).
+    async fn fetch_config(client: &mut Client) -> Result<Value> {
+        let mut delay = Duration::from_secs(1);
+
+        for attempt in 1..RECONNECT_ATTEMPTS {
+            match Self::read_config(client).await {
+                Ok(config) => return Ok(config),
+                Err(err) => {
+                    warn!(
+                        "Unable to read {CONFIG_KEY} (attempt {attempt}/{RECONNECT_ATTEMPTS}): {err:#}"
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+
+        Self::read_config(client).await.with_context(|| {
+            format!("Unable to read {CONFIG_KEY} after {RECONNECT_ATTEMPTS} attempts.")
         })
     }
 
+    /// Connects to any of the `endpoints`, giving up after [CONNECT_TIMEOUT].
+    async fn connect(endpoints: &[String]) -> Result<Client> {
+        // connecting is lazy so request the status to make sure the server is there
+        let attempt = async {
+            let mut client = Client::connect(endpoints, None).await?;
+            client.status().await?;
+            Ok::<_, etcd_client::Error>(client)
+        };
+
+        tokio::time::timeout(CONNECT_TIMEOUT, attempt)
+            .await
+            .with_context(|| {
+                format!(
+                    "Timed out connecting to etcd server {}.",
+                    endpoints.join(", ")
+                )
+            })?
+            .with_context(|| format!("Error connecting to etcd server {}.", endpoints.join(", ")))
+    }
+
+    /// Tries once to re-establish the connection to the etcd server.
+    ///
+    /// After a failure the next attempt waits for an exponentially growing
+    /// delay, see [Self::waiting_to_reconnect], so a poll never blocks on the backoff.
+    async fn reconnect(&mut self) -> Result<()> {
+        self.failed_reconnects += 1;
+        warn!(
+            "Reconnecting to etcd server {} (attempt {})",
+            self.endpoints.join(", "),
+            self.failed_reconnects
+        );
+
+        match Self::connect(&self.endpoints).await {
+            Ok(client) => {
+                self.client = client;
+                self.failed_reconnects = 0;
+                self.retry_at = None;
+                info!("Reconnected to etcd server {}", self.endpoints.join(", "));
+                Ok(())
+            }
+            Err(err) => {
+                let delay = (Duration::from_secs(1)
+                    * 2_u32.pow((self.failed_reconnects - 1).min(5)))
+                .min(MAX_RECONNECT_DELAY);
+                self.retry_at = Some(Instant::now() + delay);
+                Err(err.context(format!("Retrying in {} s.", delay.as_secs())))
+            }
+        }
+    }
+
+    /// Whether the connection is down and the backoff has not run out yet.
+    fn waiting_to_reconnect(&self) -> bool {
+        self.retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+    }
+
+    /// Reconnects after a failed request, reporting the outcome.
+    async fn recover(&mut self) {
+        match self.reconnect().await {
+            Ok(()) => self.report(BackendStatus::Connected),
+            Err(err) if self.failed_reconnects < RECONNECT_ATTEMPTS => {
+                warn!("{err:#}");
+                self.report(BackendStatus::Reconnecting);
+            }
+            Err(err) => {
+                log::error!("{err:#}");
+                self.report(BackendStatus::Error);
+            }
+        }
+    }
+
     fn get_snaps(&self) -> Option<Vec<i64>> {
         self.filter.as_ref().map(|ants| {
             ants.iter()
@@ -190,19 +738,42 @@ impl EtcdLoader {
         })
     }
 
+    /// Signal blocks holding the FPGA inputs of the filtered antennas on a snap.
+    fn needed_blocks(&self, snap_location: i64) -> Vec<usize> {
+        self.filter
+            .iter()
+            .flatten()
+            .filter(|info| info.snap2_location == snap_location)
+            .flat_map(|info| [info.pola_fpga_num, info.polb_fpga_num])
+            .filter_map(|fpga_num| usize::try_from(fpga_num).ok())
+            .map(|fpga_num| fpga_num / self.shape.inputs_per_block)
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// Requests the autospectra of a snap, one signal block at a time.
+    ///
+    /// Only the given `blocks` are requested, the rows of the others are left at zero.
+    /// Takes its own handle to the client so several snaps can be requested at once.
     async fn get_spectra_for_snap(
-        &mut self,
+        mut client: Client,
+        shape: SpectraShape,
         snap_location: Option<i64>,
+        blocks: Vec<usize>,
     ) -> Result<Array<f64, Ix2>> {
         let cmd_key = snap_location
             .as_ref()
             .map_or(format!("{ETCD_CMD_ROOT}0"), |info| {
                 format!("{ETCD_CMD_ROOT}{:0>2}", info)
             });
-        let mut spectra = Array::<f64, Ix2>::zeros((64, 4096));
+        let mut spectra = Array::<f64, Ix2>::zeros((shape.n_inputs(), shape.n_channels));
 
-        for (signal_block, mut chunk) in
-            spectra.exact_chunks_mut((16, 4096)).into_iter().enumerate()
+        for (signal_block, mut chunk) in spectra
+            .exact_chunks_mut((shape.inputs_per_block, shape.n_channels))
+            .into_iter()
+            .enumerate()
+            .filter(|(signal_block, _)| blocks.contains(signal_block))
         {
             let timestamp = SystemTime::now()
                 .duration_since(SystemTime::UNIX_EPOCH)
@@ -210,7 +781,13 @@ impl EtcdLoader {
                 .as_micros() as f64
                 * 1e-6_f64;
 
-            let seq_id = format!("{}", (timestamp * 1e6).round() as i64);
+            // snaps are requested at the same time, so the timestamp alone
+            // does not tell their responses apart
+            let seq_id = format!(
+                "{}-{}",
+                (timestamp * 1e6).round() as i64,
+                snap_location.unwrap_or_default()
+            );
             let command = serde_json::to_string(&json!({
                 "cmd": "get_new_spectra",
                 "val": {
@@ -222,19 +799,24 @@ impl EtcdLoader {
             }))
             .context("Unable to format request JSON")?;
 
-            let (_watcher, mut stream) = self
-                .client
+            let (_watcher, mut stream) = client
                 .watch(ETCD_RESP_KEY, Some(WatchOptions::new().with_prefix()))
                 .await
                 .context("Unable to watch ETCD response key")?;
 
             // send command
-            self.client
+            client
                 .put(cmd_key.clone(), command, None)
                 .await
                 .context("Unable to put spectrum request.")?;
 
-            'while_loop: while let Some(Ok(response)) = stream.next().await {
+            'while_loop: loop {
+                let response = match tokio::time::timeout(RESPONSE_TIMEOUT, stream.next()).await {
+                    Err(_) => bail!("Timed out waiting for a response to {cmd_key}"),
+                    Ok(None) => bail!("etcd watch stream closed before a response was received."),
+                    Ok(Some(response)) => response.context("Error reading etcd watch stream")?,
+                };
+
                 for event in response.events() {
                     if let Some(Ok(dict)) = event
                         .kv()
@@ -242,21 +824,25 @@ impl EtcdLoader {
                     {
                         if let Some(id) = dict.get("id").and_then(|val| val.as_str()) {
                             if id == seq_id {
-                                let spectra = dict["val"]["response"]
-                                    .as_array()
-                                    .unwrap()
-                                    .iter()
-                                    .flat_map(|spec| {
-                                        spec.as_array().unwrap().iter().map(|x| x.as_f64().unwrap())
-                                    })
-                                    .collect::<Vec<f64>>();
-                                {
-                                    chunk.assign(
-                                        &Array::from_shape_vec((16, 4096), spectra)
-                                            .context("Cannot fit spectra in to shape (16, 4096)")?,
-                                    );
-                                    break 'while_loop;
-                                }
+                                let spectra = parse_response(&dict["val"]["response"])
+                                    .with_context(|| {
+                                        format!("Bad response to {cmd_key} for signal block {signal_block}")
+                                    })?;
+                                let expected = shape.inputs_per_block * shape.n_channels;
+                                ensure!(
+                                    spectra.len() == expected,
+                                    "{cmd_key} returned {} values for signal block {signal_block}, \
+                                    expected {} inputs of {} channels ({expected}). \
+                                    Has the firmware changed? Try --spectra-shape.",
+                                    spectra.len(),
+                                    shape.inputs_per_block,
+                                    shape.n_channels,
+                                );
+                                chunk.assign(&Array::from_shape_vec(
+                                    (shape.inputs_per_block, shape.n_channels),
+                                    spectra,
+                                )?);
+                                break 'while_loop;
                             }
                         }
                     }
@@ -268,11 +854,20 @@ impl EtcdLoader {
 
     pub async fn request_autos(&mut self) -> Result<Array<f64, Ix2>> {
         if let Some(snaps) = self.get_snaps() {
-            let mut all_sectra = Array::zeros((0, 4096));
+            let mut all_sectra = Array::zeros((0, self.shape.n_channels));
 
-            for snap in snaps {
-                let mut spectra = self.get_spectra_for_snap(Some(snap)).await?;
+            // the snaps answer independently, wait for all of them at once
+            let requests = snaps.iter().map(|&snap| {
+                Self::get_spectra_for_snap(
+                    self.client.clone(),
+                    self.shape,
+                    Some(snap),
+                    self.needed_blocks(snap),
+                )
+            });
+            let responses = futures::future::try_join_all(requests).await?;
 
+            for (snap, mut spectra) in snaps.into_iter().zip(responses) {
                 if let Some(all_info) = self.filter.as_ref() {
                     let mut axes = vec![];
                     for info in all_info {
@@ -293,25 +888,61 @@ impl EtcdLoader {
                             })
                             .flatten(),
                     )
-                    .to_shape((2, 4096))?
+                    .to_shape((2, self.shape.n_channels))?
                     .to_owned();
                     all_sectra = concatenate![Axis(0), all_sectra.view(), spectra.view()];
                 }
             }
             Ok(all_sectra)
         } else {
-            Ok(self.get_spectra_for_snap(None).await?)
+            let all_blocks = (0..self.shape.n_signal_blocks).collect::<Vec<_>>();
+            Self::get_spectra_for_snap(self.client.clone(), self.shape, None, all_blocks).await
         }
     }
+
+    /// Names of every antenna in the correlator configuration.
+    pub fn antenna_names(&self) -> Vec<String> {
+        self.ant_info
+            .iter()
+            .map(|info| info.antname.clone())
+            .filter(|name| name != "null")
+            .collect()
+    }
 }
 
 #[async_trait]
 impl SpectrumLoader for EtcdLoader {
     async fn get_data(&mut self) -> Option<AutoSpectra> {
-        let data = self.request_autos().await.ok()?;
+        // a lost connection is retried on later polls, leaving the backend
+        // free to handle filters and commands in between
+        if self.waiting_to_reconnect() {
+            return None;
+        }
+        if self.retry_at.is_some() {
+            self.recover().await;
+            if self.retry_at.is_some() {
+                return None;
+            }
+        }
+
+        // the snaps don't report when the spectra were taken
+        let requested = Epoch::now().ok();
+        let data = match self.request_autos().await {
+            Ok(data) => {
+                self.report(BackendStatus::Connected);
+                data
+            }
+            Err(err) => {
+                warn!("Error requesting autospectra: {err:#}");
+                self.report(BackendStatus::Reconnecting);
+                self.recover().await;
+                return None;
+            }
+        };
         let n_specs = data.shape()[0];
 
-        let xs = Array::linspace(0.0, 98.3, data.shape()[1]);
+        let (xs, data) =
+            select_channels(x_axis(self.x_unit, data.shape()[1]), data, self.freq_range);
 
         let ant_names = if let Some(all_info) = self.filter.as_ref() {
             all_info
@@ -322,22 +953,204 @@ impl SpectrumLoader for EtcdLoader {
             (0..n_specs).map(|x| format!("{x}")).collect()
         };
 
-        Some(AutoSpectra::new(ant_names, xs, data, true))
+        let mut spec = AutoSpectra::new(ant_names, xs, data, true);
+        spec.x_unit = self.x_unit;
+        spec.timestamp = requested;
+        Some(spec)
     }
 
     fn filter_antenna(&mut self, antenna_number: &[String]) -> Result<()> {
-        self.filter = antenna_number
+        let selected = expand_antennas(antenna_number, &self.ant_info);
+        ensure!(
+            antenna_number.is_empty() || !selected.is_empty(),
+            "None of the requested antennas are in the configuration."
+        );
+        // this sorts them by snap location
+        self.filter = Some(selected.into_iter().sorted().collect());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sorted_names(info: &[AntInfo]) -> Vec<&str> {
+        info.iter()
+            .map(|info| info.antname.as_str())
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn config_by_field() {
+        let config = json!({"lwacfg": {
+            "antname": {"0": "LWA-001", "1": "LWA-002"},
+            "snap2_location": {"0": 1, "1": 2},
+            "pola_fpga_num": {"0": 0, "1": 2},
+            "polb_fpga_num": {"0": 1, "1": 3},
+        }});
+
+        let info = AntInfo::from_config(&config).expect("Unable to parse config");
+        assert_eq!(vec!["LWA-001", "LWA-002"], sorted_names(&info));
+
+        let second = info.iter().find(|info| info.antname == "LWA-002").unwrap();
+        assert_eq!(2, second.snap2_location);
+        assert_eq!(2, second.pola_fpga_num);
+        assert_eq!(3, second.polb_fpga_num);
+    }
+
+    #[test]
+    fn config_by_antenna() {
+        let config = json!({"lwacfg": {
+            "0": {"antname": "LWA-001", "snap2_location": 1, "pola_fpga_num": 0, "polb_fpga_num": 1},
+            "1": {"antname": "LWA-002", "snap2_location": 2, "pola_fpga_num": 2},
+        }});
+
+        let info = AntInfo::from_config(&config).expect("Unable to parse config");
+        assert_eq!(vec!["LWA-001", "LWA-002"], sorted_names(&info));
+
+        // missing fields get placeholders
+        let second = info.iter().find(|info| info.antname == "LWA-002").unwrap();
+        assert_eq!(2, second.pola_fpga_num);
+        assert_eq!(-1, second.polb_fpga_num);
+    }
+
+    #[test]
+    fn antenna_ranges() {
+        let known = ["LWA-001", "LWA-002", "LWA-010", "LWA-011", "LWB-005"]
             .iter()
-            .map(|ant| {
-                self.ant_info
-                    .iter()
-                    .find(|info| info.antname.to_lowercase() == *ant.to_lowercase())
-                    .cloned()
+            .enumerate()
+            .map(|(loc, name)| AntInfo {
+                antname: name.to_string(),
+                snap2_location: loc as i64,
+                pola_fpga_num: 0,
+                polb_fpga_num: 1,
             })
-            // this sorts them by snap location
-            .sorted()
-            .collect();
+            .collect::<Vec<_>>();
+        let expand = |req: &[&str]| {
+            let req = req.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+            expand_antennas(&req, &known)
+                .into_iter()
+                .map(|info| info.antname)
+                .collect::<Vec<_>>()
+        };
 
-        Ok(())
+        assert_eq!(
+            vec!["LWA-001", "LWA-002", "LWA-010"],
+            expand(&["lwa-1..LWA-10"])
+        );
+        assert_eq!(
+            vec!["LWA-011", "LWB-005"],
+            expand(&["LWA-011,LWB-005", "LWA-999"])
+        );
+        assert_eq!(
+            vec!["LWA-002", "LWA-001"],
+            expand(&["LWA-002", "LWA-2..LWA-1"])
+        );
+        assert!(expand(&["LWA-1..LWB-5", "LWA..LWA-3"]).is_empty());
+        // single names ignore zero padding too
+        assert_eq!(vec!["LWA-001", "LWB-005"], expand(&["lwa-1", "LWB-05"]));
+
+        let names = known
+            .iter()
+            .map(|info| info.antname.clone())
+            .collect::<Vec<_>>();
+        assert!(matches_known_antennas("LWA-1", &names));
+        assert!(matches_known_antennas("lwa-1..lwa-3, LWB-005", &names));
+        assert!(!matches_known_antennas("LWA-1,LWA-999", &names));
+        assert!(!matches_known_antennas("LWA-1..LWB-5", &names));
+        assert!(!matches_known_antennas(" , ", &names));
+    }
+
+    #[tokio::test]
+    async fn one_dimensional_npy() {
+        let path = std::env::temp_dir().join(format!("spectrum-tui-1d-{}.npy", std::process::id()));
+        ndarray_npy::write_npy(&path, &ndarray::arr1(&[1.0, 2.0, 3.0, 4.0])).unwrap();
+
+        let mut loader = DiskLoader::new(path.clone(), XUnit::Channel);
+        let spec = loader.get_data().await.expect("Unable to load a 1-D file");
+        assert_eq!(vec!["0A"], spec.ant_names);
+        assert_eq!(
+            vec![(0.0, 1.0), (1.0, 2.0), (2.0, 3.0), (3.0, 4.0)],
+            spec.spectra[0]
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn dead_rows_keep_their_place() {
+        let path =
+            std::env::temp_dir().join(format!("spectrum-tui-dead-{}.npy", std::process::id()));
+        let data = ndarray::arr2(&[[1.0, 2.0], [0.0, 0.0], [f64::NAN, 0.0], [3.0, 4.0]]);
+        ndarray_npy::write_npy(&path, &data).unwrap();
+
+        let mut loader = DiskLoader::new(path.clone(), XUnit::Channel);
+        let spec = loader.get_data().await.expect("Unable to load the file");
+        assert_eq!(vec!["0A", "0B", "1A", "1B"], spec.ant_names);
+        assert_eq!(vec![(0.0, 3.0), (1.0, 4.0)], spec.spectra[3]);
+        // the offline inputs have no dB values, so they are flagged as no data
+        assert!(spec.log_spectra[1].is_empty() && spec.log_spectra[2].is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn spectra_shape() {
+        assert_eq!(
+            SpectraShape::default(),
+            SpectraShape::from_config(&json!({"lwacfg": {}})).unwrap()
+        );
+
+        let shape = SpectraShape::from_config(&json!({"fengines": {"nchans": 2048}})).unwrap();
+        assert_eq!(2048, shape.n_channels);
+        assert_eq!(64, shape.n_inputs());
+
+        assert!(SpectraShape::from_config(&json!({"fengines": {"nchans": -1}})).is_err());
+        assert!(SpectraShape::new(4, 0, 4096).is_err());
+    }
+
+    #[test]
+    fn non_numeric_response() {
+        let response = json!([[1.0, 2.0], [3.0, 4.0]]);
+        assert_eq!(vec![1.0, 2.0, 3.0, 4.0], parse_response(&response).unwrap());
+
+        let parsed = parse_response(&json!([[1.0, "2"], [null, 4.0]])).unwrap();
+        assert_eq!(4, parsed.len());
+        assert!(parsed[1].is_nan() && parsed[2].is_nan());
+        assert_eq!((1.0, 4.0), (parsed[0], parsed[3]));
+        assert!(parse_response(&json!([1.0, 2.0])).is_err());
+    }
+
+    #[test]
+    fn freq_range_channels() {
+        // channels every 9.83 MHz
+        assert_eq!(0..11, channel_window(11, None));
+        assert_eq!(2..4, channel_window(11, Some((10.0, 30.0))));
+        assert_eq!(2..4, channel_window(11, Some((30.0, 10.0))));
+        // a range between two channels keeps the one above it
+        assert_eq!(2..3, channel_window(11, Some((10.0, 11.0))));
+        // clamped to the top of the band
+        assert_eq!(10..11, channel_window(11, Some((90.0, 200.0))));
+        assert_eq!(0..0, channel_window(0, Some((10.0, 30.0))));
+
+        assert!(check_freq_range((10.0, 30.0)).is_ok());
+        assert!(check_freq_range((50.0, 200.0)).is_ok());
+        assert!(check_freq_range((120.0, 200.0)).is_err());
+        assert!(check_freq_range((-5.0, -1.0)).is_err());
+    }
+
+    #[test]
+    fn malformed_config() {
+        for config in [
+            json!({}),
+            json!({"lwacfg": [1, 2, 3]}),
+            json!({"lwacfg": {"snap2_location": [1, 2], "antname": {"0": "LWA-001"}}}),
+            json!({"lwacfg": {"0": "LWA-001"}}),
+        ] {
+            assert!(AntInfo::from_config(&config).is_err(), "{config} parsed");
+        }
     }
 }
@@ -0,0 +1,415 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Lines, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{ensure, Context, Result};
+use async_trait::async_trait;
+use hifitime::{Duration as HifiDuration, Epoch};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::mpsc::{self, Sender},
+    time::{Duration, Instant},
+};
+
+use crate::loader::{AutoSpectra, DataQuality, SpectrumLoader, XUnit};
+
+/// Spectra queued for writing before new ones are dropped
+const RECORD_QUEUE: usize = 30;
+
+/// One recorded spectrum, a recording has one per line as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Frame {
+    /// Unix time in seconds the spectrum was received
+    received: f64,
+    ant_names: Vec<String>,
+    spectra: Vec<Vec<(f64, f64)>>,
+    plot_log: bool,
+    x_unit: XUnit,
+    saturation: Option<Vec<f64>>,
+    quality: Option<Vec<DataQuality>>,
+    /// When the spectrum was recorded as TAI nanoseconds, kept exact
+    tai_nanoseconds: Option<i128>,
+    /// Per-channel statistic of each trace, e.g. the spectral kurtosis
+    #[serde(default)]
+    channel_stat: Option<Vec<Vec<f64>>>,
+    /// Beams the spectra came from, in recordings made since they were kept
+    #[serde(default)]
+    beams: Vec<u8>,
+    /// Tuning numbers and their center frequencies in MHz
+    #[serde(default)]
+    tunings: Vec<(u8, f64)>,
+}
+impl Frame {
+    fn new(spec: &AutoSpectra) -> Self {
+        Self {
+            received: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |time| time.as_secs_f64()),
+            ant_names: spec.ant_names.clone(),
+            spectra: spec.spectra.clone(),
+            plot_log: spec.plot_log,
+            x_unit: spec.x_unit,
+            saturation: spec.saturation.clone(),
+            quality: spec.quality.clone(),
+            tai_nanoseconds: spec
+                .timestamp
+                .map(|time| time.to_tai_duration().total_nanoseconds()),
+            channel_stat: spec.channel_stat.clone(),
+            beams: spec.beams.clone(),
+            tunings: spec.tunings.clone(),
+        }
+    }
+
+    fn time(&self) -> FrameTime {
+        FrameTime {
+            tai_nanoseconds: self.tai_nanoseconds,
+            received: self.received,
+        }
+    }
+
+    /// Rebuilds the spectra, keeping only the traces that pass the filter.
+    fn into_spectra(self, filter: &[String]) -> AutoSpectra {
+        let keep = self
+            .ant_names
+            .iter()
+            .map(|name| filter.is_empty() || filter.iter().any(|ant| name.starts_with(ant)))
+            .collect::<Vec<_>>();
+        let select = |keep: &[bool], vals: Vec<_>| {
+            vals.into_iter()
+                .zip(keep)
+                .filter(|(_, keep)| **keep)
+                .map(|(val, _)| val)
+                .collect::<Vec<_>>()
+        };
+
+        let mut spec = AutoSpectra::from_pairs(
+            select(&keep, self.ant_names),
+            select(&keep, self.spectra),
+            self.plot_log,
+        );
+        spec.x_unit = self.x_unit;
+        spec.saturation = self.saturation.map(|vals| select(&keep, vals));
+        spec.quality = self.quality.map(|vals| select(&keep, vals));
+        spec.channel_stat = self.channel_stat.map(|vals| select(&keep, vals));
+        spec.beams = self.beams;
+        spec.tunings = self.tunings;
+        spec.timestamp = self
+            .tai_nanoseconds
+            .map(|nanos| Epoch::from_tai_duration(HifiDuration::from_total_nanoseconds(nanos)));
+        spec
+    }
+}
+
+/// When a frame was recorded, used to pace the playback.
+#[derive(Debug, Clone, Copy)]
+struct FrameTime {
+    tai_nanoseconds: Option<i128>,
+    received: f64,
+}
+impl FrameTime {
+    /// Seconds from `earlier` to this frame, by the spectra's own timestamps
+    /// when both have one and by when they were received otherwise.
+    fn seconds_since(&self, earlier: &Self) -> f64 {
+        match (self.tai_nanoseconds, earlier.tai_nanoseconds) {
+            (Some(time), Some(earlier)) => (time - earlier) as f64 * 1e-9,
+            _ => self.received - earlier.received,
+        }
+    }
+}
+
+/// Opens `path` for appending and starts writing every spectrum sent to the
+/// returned channel to it in the background, one line each in the order received.
+///
+/// Recording stops with a warning if a write fails.
+pub(crate) fn spawn_recorder(path: PathBuf) -> Result<Sender<AutoSpectra>> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Unable to open {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    let (sender, mut recv) = mpsc::channel::<AutoSpectra>(RECORD_QUEUE);
+    tokio::task::spawn_blocking(move || {
+        while let Some(spec) = recv.blocking_recv() {
+            let written = serde_json::to_writer(&mut writer, &Frame::new(&spec))
+                .map_err(anyhow::Error::from)
+                .and_then(|_| writeln!(writer).map_err(anyhow::Error::from))
+                // flush every frame so an interrupted session is still readable
+                .and_then(|_| writer.flush().map_err(anyhow::Error::from));
+            if let Err(err) = written {
+                log::warn!("Stopped recording to {}: {err:#}", path.display());
+                break;
+            }
+        }
+    });
+
+    Ok(sender)
+}
+
+/// Plays back a recording made with [spawn_recorder].
+pub(crate) struct ReplayLoader {
+    path: PathBuf,
+    lines: Lines<BufReader<File>>,
+    /// Playback rate relative to the recording
+    speed: f64,
+    /// Antenna names whose traces are kept, all when empty
+    filter: Vec<String>,
+    /// The next frame, held while waiting for its time to come
+    pending: Option<Frame>,
+    /// Start again from the first frame after the last
+    looping: bool,
+    /// When the previous frame was due and when it was recorded
+    previous: Option<(Instant, FrameTime)>,
+}
+impl ReplayLoader {
+    pub fn new<P: AsRef<Path>>(path: P, speed: f64) -> Result<Self> {
+        ensure!(
+            speed.is_finite() && speed > 0.0,
+            "Replay speed must be positive, got {speed}."
+        );
+
+        let path = path.as_ref().to_owned();
+        let file =
+            File::open(&path).with_context(|| format!("Unable to open {}", path.display()))?;
+
+        Ok(Self {
+            lines: BufReader::new(file).lines(),
+            path,
+            speed,
+            filter: vec![],
+            pending: None,
+            looping: false,
+            previous: None,
+        })
+    }
+
+    /// Plays the recording again from the start each time it ends.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Goes back to the first frame of the recording.
+    fn rewind(&mut self) -> Result<()> {
+        let file = File::open(&self.path)
+            .with_context(|| format!("Unable to open {}", self.path.display()))?;
+        self.lines = BufReader::new(file).lines();
+        // the first frame again is not paced against the last one
+        self.previous = None;
+        Ok(())
+    }
+
+    /// The next frame, starting over at the end of the recording when looping.
+    fn next_frame(&mut self) -> Option<Frame> {
+        if let Some(frame) = self.read_frame() {
+            return Some(frame);
+        }
+        if !self.looping {
+            return None;
+        }
+
+        debug!("Restarting {}.", self.path.display());
+        if let Err(err) = self.rewind() {
+            log::warn!("{err:#}");
+            return None;
+        }
+        // an empty recording has nothing to loop over
+        self.read_frame()
+    }
+
+    /// Reads the next frame, skipping blank lines.
+    ///
+    /// Returns `None` at the end of the recording or if it cannot be read.
+    fn read_frame(&mut self) -> Option<Frame> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => line,
+                Err(err) => {
+                    log::warn!("Unable to read {}: {err}", self.path.display());
+                    return None;
+                }
+            };
+            match serde_json::from_str(&line) {
+                Ok(frame) => return Some(frame),
+                Err(err) => {
+                    log::warn!("Unable to parse {}: {err}", self.path.display());
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SpectrumLoader for ReplayLoader {
+    /// Waits until the next frame is due, the time between the recorded
+    /// timestamps divided by the speed, then returns it.
+    ///
+    /// Cancelling the wait keeps the frame for the next call.
+    async fn get_data(&mut self) -> Option<AutoSpectra> {
+        if self.pending.is_none() {
+            self.pending = self.next_frame();
+        }
+        let time = self.pending.as_ref()?.time();
+
+        let due = match self.previous {
+            Some((due, previous)) => {
+                due + Duration::from_secs_f64((time.seconds_since(&previous) / self.speed).max(0.0))
+            }
+            None => Instant::now(),
+        };
+        tokio::time::sleep_until(due).await;
+
+        let frame = self.pending.take()?;
+        self.previous = Some((due, time));
+        Some(frame.into_spectra(&self.filter))
+    }
+
+    /// Keeps only the traces whose names start with one of `antenna_number`.
+    fn filter_antenna(&mut self, antenna_number: &[String]) -> Result<()> {
+        self.filter = antenna_number.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use ndarray::{arr2, Array};
+
+    fn frame(name: &str, received: f64, seconds: i128) -> Frame {
+        let mut spec = AutoSpectra::new(
+            vec![format!("{name}A"), format!("{name}B")],
+            Array::linspace(0.0, 200.0, 3),
+            arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]),
+            true,
+        );
+        spec.timestamp = Some(Epoch::from_tai_duration(
+            HifiDuration::from_total_nanoseconds(seconds * 1_000_000_000),
+        ));
+        let mut frame = Frame::new(&spec);
+        frame.received = received;
+        frame
+    }
+
+    fn write_recording(name: &str, frames: &[Frame]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("spectrum-tui-{name}-{}.jsonl", std::process::id()));
+        let text = frames
+            .iter()
+            .map(|frame| serde_json::to_string(frame).unwrap() + "\n")
+            .collect::<String>();
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn replay_round_trip() {
+        let path = write_recording(
+            "round-trip",
+            &[frame("LWA-1", 0.0, 10), frame("LWA-2", 5.0, 11)],
+        );
+        // a second apart, played back a million times faster
+        let mut loader = ReplayLoader::new(&path, 1e6).unwrap();
+
+        let first = loader.get_data().await.unwrap();
+        assert_eq!(vec!["LWA-1A", "LWA-1B"], first.ant_names);
+        assert_eq!(
+            vec![(0.0, 1.0), (100.0, 2.0), (200.0, 3.0)],
+            first.spectra[0]
+        );
+        assert!(first.plot_log);
+        assert_eq!(
+            Some(Epoch::from_tai_duration(
+                HifiDuration::from_total_nanoseconds(10_000_000_000)
+            )),
+            first.timestamp
+        );
+
+        loader.filter_antenna(&["LWA-2B".to_owned()]).unwrap();
+        let second = loader.get_data().await.unwrap();
+        assert_eq!(vec!["LWA-2B"], second.ant_names);
+        assert_eq!(
+            vec![(0.0, 4.0), (100.0, 5.0), (200.0, 6.0)],
+            second.spectra[0]
+        );
+
+        assert!(loader.get_data().await.is_none());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn replay_loops() {
+        let path = write_recording("loop", &[frame("LWA-1", 0.0, 10), frame("LWA-2", 1.0, 11)]);
+        let mut loader = ReplayLoader::new(&path, 1e6).unwrap().with_looping(true);
+
+        let names = [
+            loader.get_data().await.unwrap(),
+            loader.get_data().await.unwrap(),
+            loader.get_data().await.unwrap(),
+        ]
+        .map(|spec| spec.ant_names[0].clone());
+        assert_eq!(["LWA-1A", "LWA-2A", "LWA-1A"], names);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn frame_metadata() {
+        let mut spec = AutoSpectra::new(
+            vec!["XX".to_owned(), "YY".to_owned()],
+            Array::linspace(0.0, 200.0, 3),
+            arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]),
+            true,
+        );
+        spec.beams = vec![2];
+        spec.tunings = vec![(1, 38.0), (2, 74.0)];
+        spec.channel_stat = Some(vec![vec![1.0; 3], vec![2.0; 3]]);
+
+        let line = serde_json::to_string(&Frame::new(&spec)).unwrap();
+        let replayed = serde_json::from_str::<Frame>(&line)
+            .unwrap()
+            .into_spectra(&["YY".to_owned()]);
+        assert_eq!(vec![2], replayed.beams);
+        assert_eq!(vec![(1, 38.0), (2, 74.0)], replayed.tunings);
+        assert_eq!(Some(vec![vec![2.0; 3]]), replayed.channel_stat);
+
+        // recordings from before the metadata was kept still load
+        let mut old = serde_json::to_value(Frame::new(&spec)).unwrap();
+        let old_frame = old.as_object_mut().unwrap();
+        old_frame.remove("beams");
+        old_frame.remove("channel_stat");
+        old_frame.remove("tunings");
+        let replayed = serde_json::from_value::<Frame>(old)
+            .unwrap()
+            .into_spectra(&[]);
+        assert!(replayed.beams.is_empty() && replayed.channel_stat.is_none());
+        assert!(replayed.tunings.is_empty());
+    }
+
+    #[test]
+    fn bad_speed() {
+        assert!(ReplayLoader::new("missing.jsonl", 0.0).is_err());
+    }
+
+    #[test]
+    fn paced_by_timestamp() {
+        let (first, second) = (
+            frame("LWA-1", 100.0, 10).time(),
+            frame("LWA-1", 0.0, 12).time(),
+        );
+        assert_eq!(2.0, second.seconds_since(&first));
+
+        let untimed = FrameTime {
+            tai_nanoseconds: None,
+            received: 103.0,
+        };
+        assert_eq!(3.0, untimed.seconds_since(&first));
+    }
+}
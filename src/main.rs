@@ -1,4 +1,4 @@
-use std::{io, time::Duration};
+use std::{io, path::PathBuf, time::Duration};
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
@@ -17,11 +17,14 @@ use ratatui::{
 };
 use tui_logger::{init_logger, set_default_level};
 
-#[cfg(any(feature = "ovro", feature = "lwa-na"))]
-use std::path::PathBuf;
+#[cfg(feature = "ovro")]
+use loader::XUnit;
+
+#[cfg(feature = "lwa-na")]
+use loader::north_arm::SshAuth;
 
 mod app;
-use app::App;
+use app::{palette::Palette, theme::Theme, App};
 
 mod loader;
 
@@ -31,10 +34,75 @@ enum Action {
     NewAnt,
     #[cfg(feature = "ovro")]
     DelAnt,
+    #[cfg(feature = "ovro")]
+    SavePreset,
+    #[cfg(feature = "ovro")]
+    NextPreset,
     ToggleLog,
+    ToggleLogAxis,
+    SplitScales,
     #[cfg(feature = "lwa-na")]
     ToggleStats,
+    #[cfg(feature = "lwa-na")]
+    ToggleQuality,
+    ToggleStatsPanel,
+    MoreVerbose,
+    LessVerbose,
     ChangeYLims,
+    AutofitY,
+    ResetView,
+    LockAutoscale,
+    ChangeXLims,
+    ToggleWaterfall,
+    IncreaseAveraging,
+    ToggleSpread,
+    DecreaseAveraging,
+    IncreaseSmoothing,
+    DecreaseSmoothing,
+    ToggleOutliers,
+    ToggleKurtosis,
+    IncreaseOutlierSigma,
+    DecreaseOutlierSigma,
+    TogglePause,
+    Crosshair,
+    CopyFrequency,
+    ExportCsv,
+    SaveImage,
+    SaveDefaults,
+    #[cfg(feature = "ovro")]
+    ExportNpy,
+    HideAntennas,
+    SoloNext,
+    SoloPrevious,
+    ShowAll,
+    Compare,
+    CycleMedian,
+    ToggleMaxHold,
+    ResetMaxHold,
+    CaptureReference,
+    #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+    IncreaseDelay,
+    #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+    DecreaseDelay,
+    CycleReference,
+    #[cfg(feature = "lwa-na")]
+    NextSpectrum,
+    #[cfg(feature = "lwa-na")]
+    PreviousSpectrum,
+    #[cfg(feature = "lwa-na")]
+    CycleTuning,
+    #[cfg(feature = "lwa-na")]
+    ToggleStokes,
+    SetRefLevel,
+    ToggleNoiseFloor,
+    AddMarker,
+    CyclePalette,
+    ToggleTheme,
+    CycleMarker,
+    ToggleScatter,
+    ToggleFitWidth,
+    ToggleLegend,
+    ShowHelp,
 }
 impl Action {
     pub fn from_event(event: KeyEvent) -> Option<Self> {
@@ -55,6 +123,17 @@ impl Action {
                 kind: _,
                 state: _,
             } => Some(Self::DelAnt),
+            #[cfg(feature = "ovro")]
+            KeyEvent {
+                code: KeyCode::Char('U'),
+                ..
+            } => Some(Self::SavePreset),
+            #[cfg(feature = "ovro")]
+            KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => Some(Self::NextPreset),
             KeyEvent {
                 code: KeyCode::Esc,
                 modifiers: KeyModifiers::NONE,
@@ -71,15 +150,235 @@ impl Action {
                 code: KeyCode::Char('l'),
                 ..
             } => Some(Self::ToggleLog),
+            KeyEvent {
+                code: KeyCode::Char('L'),
+                ..
+            } => Some(Self::ToggleLogAxis),
+            KeyEvent {
+                code: KeyCode::Char('|'),
+                ..
+            } => Some(Self::SplitScales),
             KeyEvent {
                 code: KeyCode::Char('y'),
                 ..
             } => Some(Self::ChangeYLims),
+            KeyEvent {
+                code: KeyCode::Char('f'),
+                ..
+            } => Some(Self::AutofitY),
+            KeyEvent {
+                code: KeyCode::Char('0'),
+                ..
+            } => Some(Self::ResetView),
+            KeyEvent {
+                code: KeyCode::Char('Y'),
+                ..
+            } => Some(Self::LockAutoscale),
+            KeyEvent {
+                code: KeyCode::Char('x'),
+                ..
+            } => Some(Self::ChangeXLims),
+            KeyEvent {
+                code: KeyCode::Char('w'),
+                ..
+            } => Some(Self::ToggleWaterfall),
+            KeyEvent {
+                code: KeyCode::Char(' '),
+                ..
+            } => Some(Self::TogglePause),
+            KeyEvent {
+                code: KeyCode::Char(']'),
+                ..
+            } => Some(Self::IncreaseAveraging),
+            KeyEvent {
+                code: KeyCode::Char('['),
+                ..
+            } => Some(Self::DecreaseAveraging),
+            KeyEvent {
+                code: KeyCode::Char('V'),
+                ..
+            } => Some(Self::ToggleSpread),
+            KeyEvent {
+                code: KeyCode::Char('>'),
+                ..
+            } => Some(Self::IncreaseSmoothing),
+            KeyEvent {
+                code: KeyCode::Char('<'),
+                ..
+            } => Some(Self::DecreaseSmoothing),
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                ..
+            } => Some(Self::ToggleOutliers),
+            KeyEvent {
+                code: KeyCode::Char('K'),
+                ..
+            } => Some(Self::ToggleKurtosis),
+            KeyEvent {
+                code: KeyCode::Char(')'),
+                ..
+            } => Some(Self::IncreaseOutlierSigma),
+            KeyEvent {
+                code: KeyCode::Char('('),
+                ..
+            } => Some(Self::DecreaseOutlierSigma),
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                ..
+            } => Some(Self::Crosshair),
+            KeyEvent {
+                code: KeyCode::Char('X'),
+                ..
+            } => Some(Self::CopyFrequency),
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                ..
+            } => Some(Self::ExportCsv),
+            KeyEvent {
+                code: KeyCode::Char('i'),
+                ..
+            } => Some(Self::SaveImage),
+            KeyEvent {
+                code: KeyCode::Char('W'),
+                ..
+            } => Some(Self::SaveDefaults),
+            #[cfg(feature = "ovro")]
+            KeyEvent {
+                code: KeyCode::Char('N'),
+                ..
+            } => Some(Self::ExportNpy),
+            KeyEvent {
+                code: KeyCode::Char('v'),
+                ..
+            } => Some(Self::HideAntennas),
+            KeyEvent {
+                code: KeyCode::Char('n'),
+                ..
+            } => Some(Self::SoloNext),
+            KeyEvent {
+                code: KeyCode::Char('p'),
+                ..
+            } => Some(Self::SoloPrevious),
+            KeyEvent {
+                code: KeyCode::Char('A'),
+                ..
+            } => Some(Self::ShowAll),
+            KeyEvent {
+                code: KeyCode::Char('D'),
+                ..
+            } => Some(Self::Compare),
+            KeyEvent {
+                code: KeyCode::Char('m'),
+                ..
+            } => Some(Self::CycleMedian),
+            KeyEvent {
+                code: KeyCode::Char('h'),
+                ..
+            } => Some(Self::ToggleMaxHold),
+            KeyEvent {
+                code: KeyCode::Char('H'),
+                ..
+            } => Some(Self::ResetMaxHold),
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                ..
+            } => Some(Self::CaptureReference),
+            KeyEvent {
+                code: KeyCode::Char('R'),
+                ..
+            } => Some(Self::CycleReference),
+            #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+            KeyEvent {
+                code: KeyCode::Char('+'),
+                ..
+            } => Some(Self::IncreaseDelay),
+            #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+            KeyEvent {
+                code: KeyCode::Char('-'),
+                ..
+            } => Some(Self::DecreaseDelay),
             #[cfg(feature = "lwa-na")]
             KeyEvent {
                 code: KeyCode::Char('s'),
                 ..
             } => Some(Self::ToggleStats),
+            #[cfg(feature = "lwa-na")]
+            KeyEvent {
+                code: KeyCode::Char('F'),
+                ..
+            } => Some(Self::ToggleQuality),
+            KeyEvent {
+                code: KeyCode::Char('T'),
+                ..
+            } => Some(Self::ToggleStatsPanel),
+            KeyEvent {
+                code: KeyCode::Char('}'),
+                ..
+            } => Some(Self::MoreVerbose),
+            KeyEvent {
+                code: KeyCode::Char('{'),
+                ..
+            } => Some(Self::LessVerbose),
+            #[cfg(feature = "lwa-na")]
+            KeyEvent {
+                code: KeyCode::Right,
+                ..
+            } => Some(Self::NextSpectrum),
+            #[cfg(feature = "lwa-na")]
+            KeyEvent {
+                code: KeyCode::Left,
+                ..
+            } => Some(Self::PreviousSpectrum),
+            #[cfg(feature = "lwa-na")]
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                ..
+            } => Some(Self::CycleTuning),
+            #[cfg(feature = "lwa-na")]
+            KeyEvent {
+                code: KeyCode::Char('S'),
+                ..
+            } => Some(Self::ToggleStokes),
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                ..
+            } => Some(Self::ToggleLegend),
+            KeyEvent {
+                code: KeyCode::Char('P'),
+                ..
+            } => Some(Self::CyclePalette),
+            KeyEvent {
+                code: KeyCode::Char('C'),
+                ..
+            } => Some(Self::ToggleTheme),
+            KeyEvent {
+                code: KeyCode::Char('B'),
+                ..
+            } => Some(Self::CycleMarker),
+            KeyEvent {
+                code: KeyCode::Char('G'),
+                ..
+            } => Some(Self::ToggleScatter),
+            KeyEvent {
+                code: KeyCode::Char('Z'),
+                ..
+            } => Some(Self::ToggleFitWidth),
+            KeyEvent {
+                code: KeyCode::Char('b'),
+                ..
+            } => Some(Self::SetRefLevel),
+            KeyEvent {
+                code: KeyCode::Char('z'),
+                ..
+            } => Some(Self::ToggleNoiseFloor),
+            KeyEvent {
+                code: KeyCode::Char('M'),
+                ..
+            } => Some(Self::AddMarker),
+            KeyEvent {
+                code: KeyCode::Char('?'),
+                ..
+            } => Some(Self::ShowHelp),
             _ => None,
         }
     }
@@ -90,6 +389,10 @@ impl Action {
                 Cell::from(Span::styled("<Esc>/q", key_style)),
                 Cell::from(Span::styled("Quit", help_style)),
             ]),
+            Row::new(vec![
+                Cell::from(Span::styled("?", key_style)),
+                Cell::from(Span::styled("Full Help", help_style)),
+            ]),
             #[cfg(feature = "ovro")]
             Row::new(vec![
                 Cell::from(Span::styled("a", key_style)),
@@ -100,19 +403,315 @@ impl Action {
                 Cell::from(Span::styled("d", key_style)),
                 Cell::from(Span::styled("Remove Antenna", help_style)),
             ]),
+            #[cfg(feature = "ovro")]
+            Row::new(vec![
+                Cell::from(Span::styled("u", key_style)),
+                Cell::from(Span::styled("Cycle Filter Presets", help_style)),
+            ]),
+            #[cfg(feature = "ovro")]
+            Row::new(vec![
+                Cell::from(Span::styled("U", key_style)),
+                Cell::from(Span::styled("Save Filter as Preset", help_style)),
+            ]),
             Row::new(vec![
                 Cell::from(Span::styled("l", key_style)),
                 Cell::from(Span::styled("Toggle dB", help_style)),
             ]),
+            Row::new(vec![
+                Cell::from(Span::styled("L", key_style)),
+                Cell::from(Span::styled("Toggle Log Y-axis", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("|", key_style)),
+                Cell::from(Span::styled("Split Raw/dB Charts", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("b", key_style)),
+                Cell::from(Span::styled("dB Reference Level", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("z", key_style)),
+                Cell::from(Span::styled("dB Above Noise Floor", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("M", key_style)),
+                Cell::from(Span::styled("Frequency Marker", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("g", key_style)),
+                Cell::from(Span::styled("Toggle Legend Panel", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("P", key_style)),
+                Cell::from(Span::styled("Cycle Color Palette", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("C", key_style)),
+                Cell::from(Span::styled("Toggle Dark/Light Theme", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("B", key_style)),
+                Cell::from(Span::styled("Cycle Marker (Braille/Dot/Block)", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("G", key_style)),
+                Cell::from(Span::styled("Toggle Lines/Points", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("Z", key_style)),
+                Cell::from(Span::styled(
+                    "Toggle Full/Fit-to-Width Rendering",
+                    help_style,
+                )),
+            ]),
             Row::new(vec![
                 Cell::from(Span::styled("y", key_style)),
                 Cell::from(Span::styled("Change Y-lims", help_style)),
             ]),
+            Row::new(vec![
+                Cell::from(Span::styled("f", key_style)),
+                Cell::from(Span::styled("Fit Y to Data/Auto", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("0", key_style)),
+                Cell::from(Span::styled("Reset the View", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("Y", key_style)),
+                Cell::from(Span::styled("Lock/Unlock Y Auto-scale", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("x", key_style)),
+                Cell::from(Span::styled("Change X-lims", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("w", key_style)),
+                Cell::from(Span::styled("Toggle Waterfall", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("<Space>", key_style)),
+                Cell::from(Span::styled("Pause/Resume", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("[/]", key_style)),
+                Cell::from(Span::styled("Change Averaging", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("V", key_style)),
+                Cell::from(Span::styled("±1σ Band of the Average", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("</>", key_style)),
+                Cell::from(Span::styled("Change Smoothing", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("o", key_style)),
+                Cell::from(Span::styled("Flag Outlier Channels", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("K", key_style)),
+                Cell::from(Span::styled("Color by Spectral Kurtosis", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("(/)", key_style)),
+                Cell::from(Span::styled("Change Flagging Sigma", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("c", key_style)),
+                Cell::from(Span::styled("Crosshair (←/→)", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("X", key_style)),
+                Cell::from(Span::styled("Copy Crosshair/Peak Frequency", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("e", key_style)),
+                Cell::from(Span::styled("Export CSV", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("i", key_style)),
+                Cell::from(Span::styled("Save PNG", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("W", key_style)),
+                Cell::from(Span::styled("Save View as Default", help_style)),
+            ]),
+            #[cfg(feature = "ovro")]
+            Row::new(vec![
+                Cell::from(Span::styled("N", key_style)),
+                Cell::from(Span::styled("Save npy Snapshot", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("v", key_style)),
+                Cell::from(Span::styled("Show/Hide Antennas", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("n/p", key_style)),
+                Cell::from(Span::styled("Focus Next/Previous Antenna", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("A", key_style)),
+                Cell::from(Span::styled("Color All Antennas", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("D", key_style)),
+                Cell::from(Span::styled("Compare Two Antennas/Off", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("m", key_style)),
+                Cell::from(Span::styled("Cycle Median View", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("h/H", key_style)),
+                Cell::from(Span::styled("Max Hold/Reset", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("r/R", key_style)),
+                Cell::from(Span::styled("Capture/Cycle Reference", help_style)),
+            ]),
+            #[cfg(any(feature = "ovro", feature = "lwa-na"))]
+            Row::new(vec![
+                Cell::from(Span::styled("+/-", key_style)),
+                Cell::from(Span::styled("Slower/Faster Polling", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("T", key_style)),
+                Cell::from(Span::styled("Toggle Trace Statistics", help_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled("{/}", key_style)),
+                Cell::from(Span::styled("Less/More Verbose Logs", help_style)),
+            ]),
             #[cfg(feature = "lwa-na")]
             Row::new(vec![
                 Cell::from(Span::styled("s", key_style)),
                 Cell::from(Span::styled("Toggle Saturation Stats", help_style)),
             ]),
+            #[cfg(feature = "lwa-na")]
+            Row::new(vec![
+                Cell::from(Span::styled("F", key_style)),
+                Cell::from(Span::styled("Toggle Data Quality", help_style)),
+            ]),
+            #[cfg(feature = "lwa-na")]
+            Row::new(vec![
+                Cell::from(Span::styled("←/→", key_style)),
+                Cell::from(Span::styled("Step Through File", help_style)),
+            ]),
+            #[cfg(feature = "lwa-na")]
+            Row::new(vec![
+                Cell::from(Span::styled("t", key_style)),
+                Cell::from(Span::styled("Cycle Tunings", help_style)),
+            ]),
+            #[cfg(feature = "lwa-na")]
+            Row::new(vec![
+                Cell::from(Span::styled("S", key_style)),
+                Cell::from(Span::styled("Toggle Stokes from Linear", help_style)),
+            ]),
+        ]
+    }
+
+    /// The bindings of the popups and other input modes, grouped by mode.
+    pub fn gen_mode_help<'a>(key_style: Style, help_style: Style) -> Vec<(&'a str, Vec<Row<'a>>)> {
+        let row = |key: &'a str, help: &'a str| {
+            Row::new(vec![
+                Cell::from(Span::styled(key, key_style)),
+                Cell::from(Span::styled(help, help_style)),
+            ])
+        };
+
+        vec![
+            #[cfg(feature = "ovro")]
+            (
+                "Add Antenna",
+                vec![
+                    row("<Enter>", "Add Antenna"),
+                    row("←/→", "Move Cursor"),
+                    row("<Esc>", "Cancel"),
+                ],
+            ),
+            #[cfg(feature = "ovro")]
+            (
+                "Remove Antenna",
+                vec![
+                    row("j/k ↓/↑", "Select Antenna"),
+                    row("<PgUp>/<PgDn>", "Move a Page"),
+                    row("<Home>/<End>", "First/Last Antenna"),
+                    row("<Enter>", "Remove Antenna"),
+                    row("<Esc>", "Cancel"),
+                ],
+            ),
+            #[cfg(feature = "ovro")]
+            (
+                "Save Filter Preset",
+                vec![
+                    row("<Enter>", "Save under Name, replacing any"),
+                    row("<Esc>", "Cancel"),
+                ],
+            ),
+            (
+                "Y-limits / X-limits",
+                vec![
+                    row("<Tab>", "Change Focus"),
+                    row("<Enter>", "Apply, empty or auto for auto"),
+                    row("Span ±", "Y-limits around the data median instead"),
+                    row("<Esc>", "Cancel"),
+                ],
+            ),
+            (
+                "dB Reference Level",
+                vec![
+                    row("<Enter>", "Apply, empty or none for 1.0"),
+                    row("<Esc>", "Cancel"),
+                ],
+            ),
+            (
+                "Frequency Marker",
+                vec![
+                    row("<Enter>", "Add FREQ [LABEL], clear removes all"),
+                    row("<Esc>", "Cancel"),
+                ],
+            ),
+            (
+                "Crosshair",
+                vec![
+                    row("←/→", "Move One Channel"),
+                    row("<Shift>←/→", "Move Ten Channels"),
+                    row("X", "Copy Frequency to Clipboard"),
+                    row("<Esc>/c", "Leave Crosshair"),
+                ],
+            ),
+            (
+                "Show/Hide Antennas",
+                vec![
+                    row("j/k ↓/↑", "Select Antenna"),
+                    row("<Enter>/<Space>", "Show/Hide Antenna"),
+                    row("<Esc>/v", "Close"),
+                ],
+            ),
+            (
+                "Compare Antennas",
+                vec![
+                    row("j/k ↓/↑", "Select Antenna"),
+                    row("<Enter>/<Space>", "Pick First, then Second"),
+                    row("<Esc>/D", "Cancel"),
+                ],
+            ),
+            (
+                "Mouse (on the chart)",
+                vec![
+                    row("Left Drag", "Zoom to Region"),
+                    row("Wheel", "Zoom Around Cursor"),
+                    row("Right Drag", "Pan"),
+                ],
+            ),
+            (
+                "Help",
+                vec![
+                    row("j/k ↓/↑", "Scroll"),
+                    row("<PgDn>/<PgUp>", "Scroll a Page"),
+                    row("<Esc>/?/q", "Close"),
+                ],
+            ),
         ]
     }
 }
@@ -127,12 +726,37 @@ enum TuiType {
     /// Plot spectra from an RFIMonitorTool output npy file
     File {
         #[cfg(feature = "ovro")]
-        #[clap(short = 'n', required = true)]
-        /// The number of antenna spectra to load
-        nspectra: usize,
+        #[clap(short = 'n')]
+        /// The number of antenna spectra to load, all valid spectra in the file by default
+        nspectra: Option<usize>,
+
+        #[cfg(feature = "ovro")]
+        #[clap(long = "x-unit", value_enum, default_value_t = XUnit::Mhz)]
+        /// Units of the x-axis
+        x_unit: XUnit,
+
+        #[cfg(feature = "ovro")]
+        #[clap(long = "freq-range", num_args = 2, value_names = ["MIN", "MAX"])]
+        /// Only load channels between MIN and MAX MHz, clamped to the available band
+        freq_range: Option<Vec<f64>>,
         #[clap()]
         /// Numpy save file from the RFIMonitor
         input_file: PathBuf,
+
+        #[cfg(feature = "lwa-na")]
+        #[clap(long = "pol", num_args = 1.., value_delimiter = ' ')]
+        /// Polarization(s) to plot, e.g. XX YY or I. Plots all polarizations by default.
+        pols: Vec<String>,
+
+        #[cfg(feature = "lwa-na")]
+        #[clap(long = "center-freq", value_name = "MHZ")]
+        /// Only plot the tuning centered closest to this frequency in MHz
+        center_freq: Option<f64>,
+
+        #[cfg(feature = "lwa-na")]
+        #[clap(long = "saturation-threshold", default_value_t = 0.1)]
+        /// Fraction of saturated samples above which a tuning is highlighted on the chart
+        saturation_threshold: f64,
     },
     #[clap(arg_required_else_help = true)]
     /// Watch live autospectra from the correlator
@@ -144,15 +768,23 @@ enum TuiType {
         ///
         /// This should be a string like LWA-250.
         ///
-        /// This antenna name is matched against the configuration name exactly.
+        /// This antenna name is matched against the configuration name, ignoring case.
         ///
-        /// This can also be a space separated list of antennas: LWA-124 LWA-250 ...etc
+        /// This can also be a space or comma separated list of antennas: LWA-124 LWA-250 ...etc,
+        /// or an inclusive range of antennas: LWA-1..LWA-10
         antenna: Vec<String>,
 
         #[cfg(feature = "lwa-na")]
-        #[clap()]
-        /// The hostname of the data recorder from which spectra will be loaded.
-        data_recorder: String,
+        #[clap(num_args = 1.., value_delimiter = ' ')]
+        /// The hostname(s) of the data recorder(s) from which spectra will be loaded.
+        ///
+        /// A host can be given as user@host to log in as someone other than --username.
+        ///
+        /// This can also be a space separated list of data recorders: dr1 dr2 ...etc
+        /// in which case the traces are labelled with their data recorder.
+        ///
+        /// A local spectrum file can be given as file:PATH instead of a host to follow it as it grows.
+        data_recorder: Vec<String>,
 
         #[cfg(feature = "lwa-na")]
         #[clap(
@@ -165,9 +797,74 @@ enum TuiType {
         /// SSH identity file used to connect to the data recorder.
         identity_file: PathBuf,
 
+        #[cfg(feature = "lwa-na")]
+        #[clap(long, default_value = "mcsdr")]
+        /// Account used to log in to the data recorder, unless given as user@host
+        username: String,
+
+        #[cfg(feature = "lwa-na")]
+        #[clap(long, value_enum, default_value_t = SshAuth::Auto)]
+        /// How to authenticate with the data recorder, auto tries the SSH agent before the identity file
+        auth: SshAuth,
+
+        #[cfg(feature = "lwa-na")]
+        #[clap(long = "pol", num_args = 1.., value_delimiter = ' ')]
+        /// Polarization(s) to plot, e.g. XX YY or I. Plots all polarizations by default.
+        pols: Vec<String>,
+
+        #[cfg(feature = "lwa-na")]
+        #[clap(long = "center-freq", value_name = "MHZ")]
+        /// Only plot the tuning centered closest to this frequency in MHz
+        center_freq: Option<f64>,
+
+        #[cfg(feature = "lwa-na")]
+        #[clap(long = "saturation-threshold", default_value_t = 0.1)]
+        /// Fraction of saturated samples above which a tuning is highlighted on the chart
+        saturation_threshold: f64,
+
         #[clap(long, short, default_value_t = 30.0)]
         /// The interval in seconds at which to poll for new autos
         delay: f64,
+
+        #[cfg(feature = "ovro")]
+        #[clap(
+            long = "etcd-endpoint",
+            value_delimiter = ',',
+            default_value = "etcdv3service:2379"
+        )]
+        /// Address of the etcd server, or a comma separated list to fail over between
+        etcd_endpoint: Vec<String>,
+
+        #[cfg(feature = "ovro")]
+        #[clap(long = "x-unit", value_enum, default_value_t = XUnit::Mhz)]
+        /// Units of the x-axis
+        x_unit: XUnit,
+
+        #[cfg(feature = "ovro")]
+        #[clap(long = "freq-range", num_args = 2, value_names = ["MIN", "MAX"])]
+        /// Only load channels between MIN and MAX MHz, clamped to the available band
+        freq_range: Option<Vec<f64>>,
+
+        #[cfg(feature = "ovro")]
+        #[clap(long = "spectra-shape", num_args = 3, value_names = ["BLOCKS", "INPUTS", "CHANNELS"])]
+        /// Signal blocks per snap, inputs per block and channels per spectrum the
+        /// snaps return, read from the correlator configuration by default
+        spectra_shape: Option<Vec<usize>>,
+    },
+    #[clap(arg_required_else_help = true)]
+    /// Play back a session saved with --record
+    Replay {
+        #[clap()]
+        /// Recording written by --record
+        input_file: PathBuf,
+
+        #[clap(long, default_value_t = 1.0)]
+        /// Playback rate relative to the recording, 2 plays twice as fast
+        speed: f64,
+
+        #[clap(long = "loop")]
+        /// Start over from the beginning when the recording ends
+        looping: bool,
     },
 }
 #[cfg(feature = "lwa-na")]
@@ -177,6 +874,7 @@ impl TuiType {
         match self {
             TuiType::File { .. } => 1.0,
             TuiType::Live { delay, .. } => *delay,
+            TuiType::Replay { .. } => 1.0,
         }
     }
 }
@@ -186,8 +884,70 @@ impl TuiType {
 struct Cli {
     #[clap(subcommand)]
     tv_type: TuiType,
+
+    #[clap(long, global = true, default_value_t = 100)]
+    /// The number of spectra to keep in the waterfall history
+    history: usize,
+
+    #[clap(
+        long = "tick-ms",
+        global = true,
+        default_value_t = 100,
+        value_name = "MS"
+    )]
+    /// Milliseconds between idle repaints, clamped to 20-1000.
+    ///
+    /// New data and key presses are drawn as they arrive, this only sets how
+    /// often the screen is redrawn in between, e.g. for the spinner.
+    tick_ms: u64,
+
+    #[clap(long, global = true, value_enum)]
+    /// Colors used for the traces, auto unless saved as a default
+    palette: Option<Palette>,
+
+    #[clap(long, global = true, value_enum, default_value_t = Theme::Dark)]
+    /// Colors of the interface, light for terminals with a light background
+    theme: Theme,
+
+    #[clap(long, global = true)]
+    /// Save the first spectra to a PNG and exit without starting the interface
+    oneshot: bool,
+
+    #[clap(long, global = true, requires = "oneshot")]
+    /// Where --oneshot writes the PNG, a timestamped file in the current directory by default
+    output: Option<PathBuf>,
+
+    #[clap(long, global = true, value_name = "PATH")]
+    /// Append every spectrum received to PATH, play it back later with the replay command
+    record: Option<PathBuf>,
+
+    #[cfg(feature = "lwa-na")]
+    #[clap(long = "sat-log", global = true, value_name = "PATH")]
+    /// Append the saturation of every poll to PATH, as JSON lines if it ends in .json or .jsonl and CSV otherwise
+    sat_log: Option<PathBuf>,
+
+    #[clap(long, global = true, allow_negative_numbers = true)]
+    /// Lower y-axis limit in plotted units, as typed into the Y limits popup
+    ymin: Option<f64>,
+
+    #[clap(long, global = true, allow_negative_numbers = true)]
+    /// Upper y-axis limit in plotted units, as typed into the Y limits popup
+    ymax: Option<f64>,
+
+    #[clap(long, global = true, conflicts_with = "linear")]
+    /// Start plotting the spectra in dB
+    log: bool,
+
+    #[clap(long, global = true)]
+    /// Start plotting the spectra in linear units
+    linear: bool,
 }
 
+/// Fastest idle repaint allowed by --tick-ms, about 50 frames per second
+const MIN_TICK_MS: u64 = 20;
+/// Slowest idle repaint allowed by --tick-ms
+const MAX_TICK_MS: u64 = 1000;
+
 fn get_log_level() -> LevelFilter {
     std::env::var("LOG")
         .or(std::env::var("RUST_LOG"))
@@ -196,13 +956,61 @@ fn get_log_level() -> LevelFilter {
         .unwrap_or(LevelFilter::Info)
 }
 
+/// Logs to stderr for --oneshot, which never shows the interface's log.
+struct StderrLogger;
+impl log::Log for StderrLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        eprintln!("[{}] {}", record.level(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    init_logger(LevelFilter::Trace).unwrap();
-    set_default_level(get_log_level());
-
+    let log_level = get_log_level();
     let cli = Cli::parse();
 
+    if cli.oneshot {
+        log::set_logger(&StderrLogger)?;
+        log::set_max_level(log_level);
+    } else {
+        init_logger(LevelFilter::Trace).unwrap();
+        set_default_level(log_level);
+    }
+
+    let app = App::new(
+        Duration::from_millis(cli.tick_ms.clamp(MIN_TICK_MS, MAX_TICK_MS)),
+        cli.tv_type,
+        cli.history,
+        cli.palette,
+        log_level,
+        !cli.oneshot,
+    )
+    .theme(cli.theme)
+    .plot_limits(
+        cli.ymin,
+        cli.ymax,
+        match (cli.log, cli.linear) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
+    )
+    .record(cli.record)?;
+    #[cfg(feature = "lwa-na")]
+    let app = app.saturation_log(cli.sat_log)?;
+
+    if cli.oneshot {
+        let path = app.oneshot(cli.output).await?;
+        println!("Saved plot to {}", path.display());
+        return Ok(());
+    }
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -210,7 +1018,6 @@ async fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let app = App::new(Duration::from_millis(100), cli.tv_type);
     let result = app.run(&mut terminal).await;
 
     // we always want to restore the terminal